@@ -0,0 +1,119 @@
+#![allow(clippy::cast_precision_loss)]
+use crate::cast_usize;
+use crate::core::Instance;
+use anyhow::Result;
+use minilp::{ComparisonOp, LinearExpr, OptimizationDirection, Problem, Variable};
+
+/// Computes an upper bound of the score from the LP relaxation of the `ILP2` model.
+///
+/// The relaxation minimizes the fractional weight of tardy tasks, and the bound is the total
+/// weight minus its optimum. It needs no Gurobi license, but the model has `O(n * deadline)`
+/// variables, so it is practical only for small deadlines.
+///
+/// # Errors
+/// - If the LP solver fails.
+pub fn fractional_bound(instance: &Instance) -> Result<f64> {
+    let tasks = &instance.tasks;
+    let d = cast_usize(instance.deadline);
+    let mut problem = Problem::new(OptimizationDirection::Minimize);
+
+    let u: Vec<_> = tasks
+        .iter()
+        .map(|task| problem.add_var(task.weight as f64, (0.0, 1.0)))
+        .collect();
+
+    // Start time variables, tasks longer than the deadline have none and are always tardy.
+    let v: Vec<Vec<_>> = tasks
+        .iter()
+        .map(|task| {
+            let starts = (d + 1).saturating_sub(cast_usize(task.time));
+            (0..starts)
+                .map(|_| problem.add_var(0.0, (0.0, 1.0)))
+                .collect()
+        })
+        .collect();
+
+    for (&uj, vj) in u.iter().zip(&v) {
+        let mut expr = LinearExpr::empty();
+        expr.add(uj, 1.0);
+        for &var in vj {
+            expr.add(var, 1.0);
+        }
+        problem.add_constraint(expr, ComparisonOp::Eq, 1.0);
+    }
+
+    for t in 0..d {
+        let mut expr = LinearExpr::empty();
+        for (vj, task) in v.iter().zip(tasks) {
+            let start = (t + 1).saturating_sub(cast_usize(task.time));
+            for &var in vj.iter().take(t + 1).skip(start) {
+                expr.add(var, 1.0);
+            }
+        }
+        problem.add_constraint(expr, ComparisonOp::Le, instance.processors as f64);
+    }
+
+    let y: Vec<Vec<(usize, Variable)>> = (0..tasks.len())
+        .map(|j| {
+            let mut conflicts: Vec<_> = instance.graph.conflicts(j).iter().copied().collect();
+            conflicts.sort_unstable();
+            conflicts
+                .into_iter()
+                .map(|g| (g, problem.add_var(0.0, (0.0, 1.0))))
+                .collect()
+        })
+        .collect();
+
+    // Task `j` finishes before task `g` starts, unless `y[j][g]` relaxes the constraint.
+    for (j, yj) in y.iter().enumerate() {
+        let pj = tasks[j].time as f64;
+
+        for &(g, yjg) in yj {
+            let mut expr = LinearExpr::empty();
+            for (t, &var) in v[j].iter().enumerate() {
+                expr.add(var, t as f64);
+            }
+            for (t, &var) in v[g].iter().enumerate() {
+                expr.add(var, -(t as f64));
+            }
+            expr.add(u[j], -pj);
+            expr.add(yjg, -(d as f64));
+            problem.add_constraint(expr, ComparisonOp::Le, -pj);
+
+            if j < g {
+                if let Some(&(_, ygj)) = y[g].iter().find(|&&(other, _)| other == j) {
+                    problem.add_constraint([(yjg, 1.0), (ygj, 1.0)], ComparisonOp::Le, 1.0);
+                }
+            }
+        }
+    }
+
+    Ok(instance.total_weight() as f64 - problem.solve()?.objective())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn bound_should_not_be_below_optimum() -> Result<()> {
+        for entry in std::fs::read_dir("samples")? {
+            let path = entry?.path();
+            let name = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or_default();
+            if !name.ends_with("_unit.in") {
+                continue;
+            }
+
+            let optimum: f64 = name.split('_').nth(1).unwrap_or_default().parse()?;
+            let mut reader = std::io::BufReader::new(std::fs::File::open(&path)?);
+            let instance: Instance = crate::data::deserialize(&mut reader)?;
+
+            assert!(fractional_bound(&instance)? >= optimum - 1e-6);
+        }
+
+        Ok(())
+    }
+}
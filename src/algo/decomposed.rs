@@ -0,0 +1,184 @@
+use super::{intern, Tresoldi};
+use crate::core::{
+    weighted_task_comparator, Decision, Instance, Schedule, ScheduleBuilder, Scheduler,
+};
+use std::time::Duration;
+
+/// Runs the inner scheduler on every connected component of the conflict graph separately.
+///
+/// Tasks of different components never conflict, so each component is scheduled as a smaller
+/// instance with the same processors and deadline. The components share the machines, so their
+/// schedules are merged by placing the tasks in order of start time on the earliest free machine.
+/// Tasks which no longer fit are retried like tardy tasks. Large instances with sparse conflicts
+/// become many small ones. The scheduler is named after the inner one with a `-decomposed`
+/// suffix.
+pub struct Decomposed<S> {
+    inner: S,
+    name: &'static str,
+}
+
+impl<S: Scheduler> Decomposed<S> {
+    /// Creates a new scheduler running `inner` on every component.
+    #[must_use]
+    pub fn new(inner: S) -> Self {
+        let name = intern(format!("{}-decomposed", inner.name()));
+        Self { inner, name }
+    }
+
+    /// Schedules every component with `run` and merges the schedules.
+    fn decompose<'a>(
+        &mut self,
+        instance: &'a Instance,
+        mut run: impl for<'b> FnMut(&mut S, &'b Instance) -> anyhow::Result<Schedule<'b>>,
+    ) -> anyhow::Result<Schedule<'a>> {
+        let mut combined = Schedule::new(instance);
+
+        let components = instance.graph.connected_components(instance.tasks.len());
+        for (subinstance, mapping) in instance.subinstances(&components) {
+            let schedule = run(&mut self.inner, &subinstance)?;
+            combined.apply_from(&schedule, &mapping);
+        }
+
+        Ok(merge(instance, &combined).into())
+    }
+}
+
+/// Places the tasks of the overlapping component schedules on the machines.
+/// Tasks are taken in order of start time, ties prefer tasks with higher weight per time
+/// and then lower ids.
+fn merge<'a>(instance: &'a Instance, combined: &Schedule) -> ScheduleBuilder<'a> {
+    let mut builder = ScheduleBuilder::new(instance);
+    let mut machines = builder.new_machine_free_times();
+
+    let mut tasks = Vec::with_capacity(instance.tasks.len());
+    for (id, task) in instance.tasks.iter().enumerate() {
+        match combined.get_schedule(id) {
+            Some(info) => tasks.push((info.start, (id, task.clone()))),
+            None => builder.tardy(id),
+        }
+    }
+    tasks.sort_unstable_by(|(first_start, first), (second_start, second)| {
+        first_start
+            .cmp(second_start)
+            .then_with(|| weighted_task_comparator(first, second))
+            .then(first.0.cmp(&second.0))
+    });
+
+    for (_, (id, task)) in tasks {
+        let Some(mut machine) = machines.pop_eligible(&task) else {
+            builder.tardy(id);
+            continue;
+        };
+
+        if let Some(time) = builder.calculate_non_conflict_time(id, machine.free) {
+            builder.schedule(id, time, machine.id);
+            machine.free = task.completion(time);
+            machine.tasks += 1;
+        } else {
+            builder.tardy(id);
+        }
+
+        machines.insert(machine);
+    }
+
+    // No machine changes, so only the tardy tasks are placed again.
+    builder.reorganize_schedule(|_, _| (Vec::new(), Vec::new()));
+    builder
+}
+
+impl<S: Scheduler> Scheduler for Decomposed<S> {
+    fn schedule<'a>(&mut self, instance: &'a Instance) -> Schedule<'a> {
+        let schedule = self.decompose(instance, |inner, instance| Ok(inner.schedule(instance)));
+        schedule.unwrap_or_else(|_| unreachable!("Scheduling a component cannot fail"))
+    }
+
+    fn try_schedule<'a>(&mut self, instance: &'a Instance) -> anyhow::Result<Schedule<'a>> {
+        self.decompose(instance, S::try_schedule)
+    }
+
+    /// The budget is split between the components by their number of tasks.
+    fn schedule_with_budget<'a>(
+        &mut self,
+        instance: &'a Instance,
+        budget: Duration,
+    ) -> Schedule<'a> {
+        #[allow(clippy::cast_precision_loss)]
+        let total = instance.tasks.len().max(1) as f64;
+        let schedule = self.decompose(instance, |inner, instance| {
+            #[allow(clippy::cast_precision_loss)]
+            let share = instance.tasks.len() as f64 / total;
+            Ok(inner.schedule_with_budget(instance, budget.mul_f64(share)))
+        });
+        schedule.unwrap_or_else(|_| unreachable!("Scheduling a component cannot fail"))
+    }
+
+    fn try_schedule_with_budget<'a>(
+        &mut self,
+        instance: &'a Instance,
+        budget: Duration,
+    ) -> anyhow::Result<Schedule<'a>> {
+        #[allow(clippy::cast_precision_loss)]
+        let total = instance.tasks.len().max(1) as f64;
+        self.decompose(instance, |inner, instance| {
+            #[allow(clippy::cast_precision_loss)]
+            let share = instance.tasks.len() as f64 / total;
+            inner.try_schedule_with_budget(instance, budget.mul_f64(share))
+        })
+    }
+
+    fn try_schedule_traced<'a>(
+        &mut self,
+        instance: &'a Instance,
+    ) -> anyhow::Result<(Schedule<'a>, Vec<Decision>)> {
+        let schedule = self.try_schedule(instance)?;
+        let decisions = Decision::from_schedule(&schedule);
+        Ok((schedule, decisions))
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.inner.set_seed(seed);
+    }
+
+    fn is_randomized(&self) -> bool {
+        self.inner.is_randomized()
+    }
+
+    fn supports(&self, instance: &Instance) -> bool {
+        self.inner.supports(instance)
+    }
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+#[allow(unsafe_code)]
+#[linkme::distributed_slice(super::SCHEDULERS)]
+static INSTANCE: fn() -> Box<dyn Scheduler> = || Box::new(Decomposed::new(Tresoldi::default()));
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algo::List;
+    use crate::core::{Conflict, Task};
+    use crate::data::samples;
+
+    #[test]
+    fn test_decomposed() {
+        let mut decomposed = Decomposed::new(List);
+        assert_eq!(decomposed.name(), "List-decomposed");
+        assert!(samples(0, &mut decomposed).is_ok());
+    }
+
+    #[test]
+    fn components_should_share_machines() {
+        let task = Task::new(2, 1);
+        let conflicts = vec![Conflict::new(0, 1)];
+        let instance = Instance::new(2, 4, vec![task; 4], conflicts);
+
+        // Every component alone starts on the first machine at time zero.
+        let schedule = Decomposed::new(List).schedule(&instance);
+        assert!(schedule.verify());
+        assert_eq!(schedule.calculate_score(), 4);
+    }
+}
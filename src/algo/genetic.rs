@@ -1,13 +1,58 @@
-use crate::core::{Instance, Machine, Schedule, ScheduleInfo, Scheduler};
+use crate::core::{Instance, MachineOrder, Machines, Schedule, ScheduleInfo, Scheduler};
 use rand::prelude::*;
 use std::cmp::Ordering;
-use std::collections::BTreeSet;
+use std::time::{Duration, Instant};
+
+/// Strategy of drawing parents from the population.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Selection {
+    /// Every solution is drawn with the same probability.
+    #[default]
+    Uniform,
+    /// The best of `k` uniformly drawn solutions is selected.
+    Tournament { k: usize },
+}
+
+impl Selection {
+    /// Draws a solution from the population.
+    fn select<'p>(self, population: &'p [Solution], rng: &mut impl Rng) -> Option<&'p Solution> {
+        match self {
+            Self::Uniform => population.choose(rng),
+            // Solutions are ordered from the best, so the minimum wins the tournament.
+            Self::Tournament { k } => population.choose_multiple(rng, k.max(1)).min(),
+        }
+    }
+}
+
+/// Crossover operator combining two parent permutations.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Crossover {
+    /// Alternately takes the first missing task of each parent.
+    #[default]
+    Interleave,
+    /// Order crossover (OX): copies a random slice of the first parent
+    /// and fills the rest in the relative order of the second parent.
+    Order,
+}
 
 /// Performs a genetic algorithm to solve the problem.
+/// Solutions are permutations of tasks decoded into schedules.
+///
+/// By default the decoder defers tasks that would wait for a conflicting task
+/// until the rest of the permutation is placed, which reduces idle time on dense instances.
+/// The random number generator can be replaced with [`Genetic::with_rng`].
 #[derive(Clone, Debug)]
-pub struct Genetic {
+pub struct Genetic<R = StdRng> {
     generations: usize,
-    rng: StdRng,
+    defer_conflicts: bool,
+    population_size: Option<usize>,
+    selection: Selection,
+    crossover: Crossover,
+    elite: usize,
+    crossover_rate: f64,
+    mutation_rate: f64,
+    convergence: Option<Vec<u64>>,
+    rng: R,
 }
 
 impl Genetic {
@@ -15,63 +60,260 @@ impl Genetic {
     #[must_use]
     pub fn new(seed: u64, generations: usize) -> Self {
         let rng = StdRng::seed_from_u64(seed);
-        Self { generations, rng }
+        let defer_conflicts = true;
+        Self {
+            generations,
+            defer_conflicts,
+            population_size: None,
+            selection: Selection::Uniform,
+            crossover: Crossover::Interleave,
+            elite: 2,
+            crossover_rate: 1.0 / 9.0,
+            mutation_rate: 1.0 / 3.0,
+            convergence: None,
+            rng,
+        }
     }
 }
 
-impl Default for Genetic {
-    fn default() -> Self {
-        let generations = 800;
-        let rng = StdRng::from_entropy();
-        Self { generations, rng }
+impl<R: RngCore> Genetic<R> {
+    /// Replaces the random number generator, other settings are kept.
+    #[must_use]
+    pub fn with_rng<T: RngCore>(self, rng: T) -> Genetic<T> {
+        Genetic {
+            generations: self.generations,
+            defer_conflicts: self.defer_conflicts,
+            population_size: self.population_size,
+            selection: self.selection,
+            crossover: self.crossover,
+            elite: self.elite,
+            crossover_rate: self.crossover_rate,
+            mutation_rate: self.mutation_rate,
+            convergence: self.convergence,
+            rng,
+        }
     }
-}
 
-impl Scheduler for Genetic {
-    fn schedule<'a>(&mut self, instance: &'a Instance) -> Schedule<'a> {
+    /// Sets whether the decoder defers tasks waiting for conflicting tasks.
+    /// Without it, tasks are placed strictly in the permutation order.
+    #[must_use]
+    pub const fn with_deferred_conflicts(mut self, defer_conflicts: bool) -> Self {
+        self.defer_conflicts = defer_conflicts;
+        self
+    }
+
+    /// Sets the number of solutions kept between generations.
+    /// By default it is equal to the number of tasks.
+    #[must_use]
+    pub const fn with_population_size(mut self, population_size: usize) -> Self {
+        self.population_size = Some(population_size);
+        self
+    }
+
+    /// Sets the strategy of drawing parents for crossover and mutation.
+    #[must_use]
+    pub const fn with_selection(mut self, selection: Selection) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    /// Sets the crossover operator.
+    #[must_use]
+    pub const fn with_crossover(mut self, crossover: Crossover) -> Self {
+        self.crossover = crossover;
+        self
+    }
+
+    /// Sets the number of best solutions that always survive to the next generation.
+    /// Other solutions are replaced by new offspring first.
+    #[must_use]
+    pub const fn with_elite(mut self, elite: usize) -> Self {
+        self.elite = elite;
+        self
+    }
+
+    /// Sets the number of crossover offspring per generation, relative to the population size.
+    /// By default one offspring is produced for every nine solutions.
+    #[must_use]
+    pub const fn with_crossover_rate(mut self, crossover_rate: f64) -> Self {
+        self.crossover_rate = crossover_rate;
+        self
+    }
+
+    /// Sets the number of mutated offspring per generation, relative to the population size.
+    /// By default one offspring is produced for every three solutions.
+    #[must_use]
+    pub const fn with_mutation_rate(mut self, mutation_rate: f64) -> Self {
+        self.mutation_rate = mutation_rate;
+        self
+    }
+
+    /// Sets whether the best score of every generation is recorded.
+    /// It is off by default, so evolution does not allocate for the log.
+    #[must_use]
+    pub fn with_convergence_log(mut self, log: bool) -> Self {
+        self.convergence = log.then(Vec::new);
+        self
+    }
+
+    /// Returns the best score after each generation of the last run,
+    /// or `None` if the convergence log is off.
+    #[must_use]
+    pub fn convergence(&self) -> Option<&[u64]> {
+        self.convergence.as_deref()
+    }
+
+    /// Evolves the population for the given number of generations,
+    /// or until the deadline if it is set. At least one generation is evolved before the deadline.
+    fn evolve<'a>(&mut self, instance: &'a Instance, deadline: Option<Instant>) -> Schedule<'a> {
         if instance.tasks.is_empty() {
             return Schedule::new(instance);
         }
 
+        let defer = self.defer_conflicts;
+        if let Some(convergence) = &mut self.convergence {
+            convergence.clear();
+        }
+
         if instance.tasks.len() == 1 {
-            return Solution::new(vec![0], instance).to_schedule(instance);
+            return Solution::new(vec![0], instance, defer).to_schedule(instance, defer);
         }
 
-        let mut population: Vec<_> = (0..instance.tasks.len())
-            .map(|_| Solution::gen(&mut self.rng, instance))
+        let size = self.population_size.unwrap_or(instance.tasks.len()).max(1);
+        let selection = self.selection;
+        let crossover = self.crossover;
+        let crossovers = offspring_count(size, self.crossover_rate);
+        let mutations = offspring_count(size, self.mutation_rate);
+
+        let mut population: Vec<_> = (0..size)
+            .map(|_| Solution::gen(&mut self.rng, instance, defer))
             .collect();
 
         population.sort_unstable();
-        population.truncate(instance.tasks.len());
+        population.dedup();
+        population.truncate(size);
+
+        for generation in 0.. {
+            let finished = deadline.map_or(generation >= self.generations, |deadline| {
+                generation > 0 && Instant::now() >= deadline
+            });
+            if finished {
+                break;
+            }
 
-        for _ in 0..self.generations {
-            for i in 0..instance.tasks.len() / 3 {
-                if i % 3 == 0 {
+            let mut offspring = Vec::new();
+
+            for i in 0..crossovers.max(mutations) {
+                if i < crossovers {
                     let parents = (
-                        population[..instance.tasks.len()].choose(&mut self.rng),
-                        population[..instance.tasks.len()].choose(&mut self.rng),
+                        selection.select(&population, &mut self.rng),
+                        selection.select(&population, &mut self.rng),
                     );
 
                     if let (Some(first), Some(second)) = parents {
-                        population.push(Solution::cross(first, second, instance));
+                        let permutation = match crossover {
+                            Crossover::Interleave => Solution::interleave(first, second),
+                            Crossover::Order => Solution::order(first, second, &mut self.rng),
+                        };
+                        offspring.push(Solution::new(permutation, instance, defer));
                     }
                 }
 
-                if let Some(solution) = population[..instance.tasks.len()].choose(&mut self.rng) {
-                    population.push(solution.mutate(&mut self.rng, instance));
+                if i < mutations {
+                    if let Some(solution) = selection.select(&population, &mut self.rng) {
+                        offspring.push(solution.mutate(&mut self.rng, instance, defer));
+                    }
                 }
             }
 
-            population.sort_unstable();
-            population.truncate(instance.tasks.len());
+            population = next_generation(population, offspring, self.elite, size);
+            if let Some(convergence) = &mut self.convergence {
+                convergence.push(population[0].score);
+            }
         }
 
-        population[0].to_schedule(instance)
+        population[0].to_schedule(instance, defer)
+    }
+}
+
+/// Selects the next generation from the sorted population and the offspring.
+/// The elite is always kept, the remaining places go to the fittest of the other solutions
+/// and the offspring not present in the population.
+/// The result is sorted and contains no duplicate permutations.
+fn next_generation(
+    mut population: Vec<Solution>,
+    mut offspring: Vec<Solution>,
+    elite: usize,
+    size: usize,
+) -> Vec<Solution> {
+    offspring.sort_unstable();
+    offspring.dedup();
+    offspring.retain(|child| population.binary_search(child).is_err());
+
+    let mut rest = population.split_off(elite.min(population.len()));
+    rest.append(&mut offspring);
+    rest.sort_unstable();
+    rest.truncate(size.saturating_sub(population.len()));
+    population.append(&mut rest);
+    population.sort_unstable();
+    population
+}
+
+/// Returns the number of offspring for the population size, rounded to the nearest integer.
+/// Negative rates produce no offspring.
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+fn offspring_count(size: usize, rate: f64) -> usize {
+    (size as f64 * rate).round() as usize
+}
+
+impl Default for Genetic {
+    fn default() -> Self {
+        let generations = 800;
+        let defer_conflicts = true;
+        let rng = StdRng::from_entropy();
+        Self {
+            generations,
+            defer_conflicts,
+            population_size: None,
+            selection: Selection::Uniform,
+            crossover: Crossover::Interleave,
+            elite: 2,
+            crossover_rate: 1.0 / 9.0,
+            mutation_rate: 1.0 / 3.0,
+            convergence: None,
+            rng,
+        }
+    }
+}
+
+impl<R: RngCore + SeedableRng> Scheduler for Genetic<R> {
+    fn schedule<'a>(&mut self, instance: &'a Instance) -> Schedule<'a> {
+        self.evolve(instance, None)
+    }
+
+    fn schedule_with_budget<'a>(
+        &mut self,
+        instance: &'a Instance,
+        budget: Duration,
+    ) -> Schedule<'a> {
+        self.evolve(instance, Some(Instant::now() + budget))
     }
 
     fn name(&self) -> &'static str {
         "Genetic"
     }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.rng = R::seed_from_u64(seed);
+    }
+
+    fn is_randomized(&self) -> bool {
+        true
+    }
 }
 
 #[allow(unsafe_code)]
@@ -85,67 +327,100 @@ struct Solution {
 }
 
 impl Solution {
-    fn to_schedule<'a>(&self, instance: &'a Instance) -> Schedule<'a> {
-        Self::schedule(&self.permutation, instance)
+    fn to_schedule<'a>(&self, instance: &'a Instance, defer: bool) -> Schedule<'a> {
+        Self::schedule(&self.permutation, instance, defer)
     }
 
-    fn schedule<'a>(permutation: &[usize], instance: &'a Instance) -> Schedule<'a> {
+    /// Decodes the permutation into a schedule.
+    /// If `defer` is set, tasks that would wait for a conflicting task are placed after the
+    /// rest of the permutation, as the machine may be used by another task in the meantime.
+    fn schedule<'a>(permutation: &[usize], instance: &'a Instance, defer: bool) -> Schedule<'a> {
         let mut schedule = Schedule::new(instance);
-        let mut machines: BTreeSet<_> = (0..instance.processors).map(Machine::new).collect();
+        let mut machines = Machines::new(instance.processors, MachineOrder::Id);
+        let mut deferred = Vec::new();
 
-        let d = instance.deadline;
         for &index in permutation {
-            let task = instance.tasks[index];
-
-            if machines.first().is_some_and(|m| m.free + task.time > d) {
-                continue;
+            if !Self::place(&mut schedule, &mut machines, instance, index, defer) {
+                deferred.push(index);
             }
+        }
 
-            let Some(mut machine) = machines.pop_first() else {
-                unreachable!("No machines available");
-            };
-
-            let conflicts = instance.graph.conflicts(index).iter();
-            let time = conflicts
-                .filter_map(|&conflict| {
-                    let info = schedule.get_schedule(conflict);
-                    let info = info.map(|info| info.start + instance.tasks[conflict].time);
-                    info.filter(|&time| time >= machine.free)
-                })
-                .max()
-                .or(Some(machine.free))
-                .filter(|&time| time + task.time <= d);
-
-            if let Some(time) = time {
-                schedule.schedule(index, ScheduleInfo::new(time, machine.id));
-                machine.free = time + task.time;
-            }
+        for index in deferred {
+            Self::place(&mut schedule, &mut machines, instance, index, false);
+        }
+
+        schedule
+    }
 
+    /// Places the task on the earliest free eligible machine, after its release and conflicting
+    /// tasks.
+    /// Returns false if the task was not considered, because it would wait and `defer` is set.
+    fn place(
+        schedule: &mut Schedule,
+        machines: &mut Machines,
+        instance: &Instance,
+        index: usize,
+        defer: bool,
+    ) -> bool {
+        let task = &instance.tasks[index];
+        let d = instance.deadline;
+
+        if machines
+            .first()
+            .is_some_and(|m| !task.fits_before(m.free.max(task.release), d))
+        {
+            return true;
+        }
+
+        let Some(mut machine) = machines.pop_eligible(task) else {
+            return true;
+        };
+        let start = machine.free.max(task.release);
+
+        let conflicts = instance.graph.conflicts(index).iter();
+        let time = conflicts
+            .filter_map(|&conflict| {
+                let info = schedule.get_schedule(conflict);
+                let info = info.map(|info| info.end(&instance.tasks[conflict]));
+                info.filter(|&time| time >= start)
+            })
+            .max();
+
+        if defer && time.is_some_and(|time| time > start) {
             machines.insert(machine);
+            return false;
         }
 
-        schedule
+        let time = time.or(Some(start));
+        if let Some(time) = time.filter(|&time| task.fits_before(time, d)) {
+            schedule.schedule(index, ScheduleInfo::new(time, machine.id));
+            machine.free = task.completion(time);
+        }
+
+        machines.insert(machine);
+        true
     }
 
-    fn new(permutation: Vec<usize>, instance: &Instance) -> Self {
-        let score = Self::schedule(&permutation, instance).calculate_score();
+    fn new(permutation: Vec<usize>, instance: &Instance, defer: bool) -> Self {
+        let score = Self::schedule(&permutation, instance, defer).calculate_score();
         Self { permutation, score }
     }
 
-    fn gen(rng: &mut impl RngCore, instance: &Instance) -> Self {
+    fn gen(rng: &mut impl RngCore, instance: &Instance, defer: bool) -> Self {
         let mut permutation: Vec<_> = (0..instance.tasks.len()).collect();
         permutation.shuffle(rng);
-        Self::new(permutation, instance)
+        Self::new(permutation, instance, defer)
     }
 
-    fn cross(first: &Self, second: &Self, instance: &Instance) -> Self {
+    /// Alternately takes the first missing task of each parent.
+    fn interleave(first: &Self, second: &Self) -> Vec<usize> {
         let mut permutation = Vec::with_capacity(first.permutation.len());
 
         let mut missing = vec![true; first.permutation.len()];
         let mut first_iter = first.permutation.iter();
         let mut second_iter = second.permutation.iter();
 
-        for _ in 0..(first.permutation.len() + 1) / 2 {
+        for _ in 0..first.permutation.len().div_ceil(2) {
             for &next in first_iter.by_ref() {
                 if missing[next] {
                     permutation.push(next);
@@ -163,10 +438,34 @@ impl Solution {
             }
         }
 
-        Self::new(permutation, instance)
+        permutation
     }
 
-    fn mutate(&self, rng: &mut impl RngCore, instance: &Instance) -> Self {
+    /// Copies a random slice of the first parent to the same positions
+    /// and fills the remaining positions with missing tasks in the order of the second parent.
+    fn order(first: &Self, second: &Self, rng: &mut impl RngCore) -> Vec<usize> {
+        let len = first.permutation.len();
+        let mut bounds = [rng.gen_range(0..=len), rng.gen_range(0..=len)];
+        bounds.sort_unstable();
+        let [start, end] = bounds;
+
+        let mut missing = vec![true; len];
+        for &task in &first.permutation[start..end] {
+            missing[task] = false;
+        }
+
+        let mut rest = second
+            .permutation
+            .iter()
+            .copied()
+            .filter(|&task| missing[task]);
+        let mut permutation: Vec<_> = rest.by_ref().take(start).collect();
+        permutation.extend_from_slice(&first.permutation[start..end]);
+        permutation.extend(rest);
+        permutation
+    }
+
+    fn mutate(&self, rng: &mut impl RngCore, instance: &Instance, defer: bool) -> Self {
         let mut permutation = self.permutation.clone();
 
         let mut indexes = permutation.choose_multiple(rng, 2).copied();
@@ -174,7 +473,7 @@ impl Solution {
             permutation.swap(first, second);
         }
 
-        Self::new(permutation, instance)
+        Self::new(permutation, instance, defer)
     }
 }
 
@@ -198,10 +497,208 @@ impl Ord for Solution {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::algo::test::ScriptedRng;
+    use crate::core::Task;
     use crate::data::samples;
 
     #[test]
     fn test_genetic() {
         assert!(samples(0, &mut Genetic::new(10, 120)).is_ok());
     }
+
+    #[test]
+    fn test_genetic_original_decoder() {
+        let mut genetic = Genetic::new(10, 120).with_deferred_conflicts(false);
+        assert!(samples(0, &mut genetic).is_ok());
+    }
+
+    #[test]
+    fn test_genetic_tournament() {
+        let mut genetic = Genetic::new(10, 120)
+            .with_population_size(30)
+            .with_selection(Selection::Tournament { k: 3 });
+        assert!(samples(0, &mut genetic).is_ok());
+    }
+
+    #[test]
+    fn test_genetic_order_crossover() {
+        let mut genetic = Genetic::new(10, 120).with_crossover(Crossover::Order);
+        assert!(samples(0, &mut genetic).is_ok());
+    }
+
+    #[test]
+    fn convergence_log_should_record_every_generation() -> anyhow::Result<()> {
+        let file = std::fs::File::open("samples/3_50_0.in")?;
+        let instance: Instance = crate::data::deserialize(&mut std::io::BufReader::new(file))?;
+
+        let mut genetic = Genetic::new(10, 30);
+        genetic.schedule(&instance);
+        assert!(genetic.convergence().is_none());
+
+        let mut genetic = genetic.with_convergence_log(true);
+        let score = genetic.schedule(&instance).calculate_score();
+        let convergence = genetic.convergence().unwrap_or_default();
+        assert_eq!(convergence.len(), 30);
+        assert!(convergence.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(convergence.last(), Some(&score));
+        Ok(())
+    }
+
+    #[test]
+    fn initial_population_should_follow_rng() -> anyhow::Result<()> {
+        let file = std::fs::File::open("samples/3_50_0.in")?;
+        let instance: Instance = crate::data::deserialize(&mut std::io::BufReader::new(file))?;
+
+        // Drawing only zeros, shuffling rotates the tasks by one, so every solution is the same.
+        let mut permutation: Vec<_> = (0..instance.tasks.len()).collect();
+        permutation.rotate_left(1);
+        let expected = Solution::schedule(&permutation, &instance, true);
+
+        let mut genetic = Genetic::new(0, 0).with_rng(ScriptedRng::new(vec![0]));
+        assert_eq!(genetic.schedule(&instance), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_genetic_rates() {
+        let mut genetic = Genetic::new(10, 60)
+            .with_crossover_rate(1.0)
+            .with_mutation_rate(0.5);
+        assert!(samples(0, &mut genetic).is_ok());
+    }
+
+    #[test]
+    fn offspring_count_should_follow_rate() {
+        assert_eq!(offspring_count(50, 1.0 / 3.0), 17);
+        assert_eq!(offspring_count(50, 1.0 / 9.0), 6);
+        assert_eq!(offspring_count(10, 0.0), 0);
+        assert_eq!(offspring_count(10, -1.0), 0);
+        assert_eq!(offspring_count(10, 2.0), 20);
+    }
+
+    #[test]
+    fn next_generation_should_keep_population_size() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let solution = |rng: &mut StdRng| {
+            let mut permutation: Vec<_> = (0..6).collect();
+            permutation.shuffle(rng);
+            let score = rng.gen_range(0..10);
+            Solution { permutation, score }
+        };
+
+        for offspring in [0, 3, 20] {
+            let mut population: Vec<_> = (0..8).map(|_| solution(&mut rng)).collect();
+            population.sort_unstable();
+            population.dedup();
+            let offspring = (0..offspring).map(|_| solution(&mut rng)).collect();
+
+            let next = next_generation(population, offspring, 2, 8);
+            assert_eq!(next.len(), 8);
+            assert!(next.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+    }
+
+    #[test]
+    fn order_crossover_should_produce_permutation() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let solution = |permutation| Solution {
+            permutation,
+            score: 0,
+        };
+
+        for len in 0..20 {
+            let mut permutation: Vec<_> = (0..len).collect();
+            permutation.shuffle(&mut rng);
+            let first = solution(permutation.clone());
+            permutation.shuffle(&mut rng);
+            let second = solution(permutation);
+
+            for _ in 0..20 {
+                let mut child = Solution::order(&first, &second, &mut rng);
+                child.sort_unstable();
+                assert_eq!(child, (0..len).collect::<Vec<_>>());
+            }
+        }
+    }
+
+    #[test]
+    fn next_generation_should_keep_elite_and_drop_duplicates() {
+        let solution = |permutation, score| Solution { permutation, score };
+        let population = vec![
+            solution(vec![0, 1, 2], 5),
+            solution(vec![1, 0, 2], 4),
+            solution(vec![2, 1, 0], 3),
+        ];
+        let offspring = vec![
+            solution(vec![2, 0, 1], 1),
+            solution(vec![2, 0, 1], 1),
+            solution(vec![1, 0, 2], 4),
+        ];
+
+        let scores = |next: Vec<Solution>| {
+            next.iter()
+                .map(|solution| solution.score)
+                .collect::<Vec<_>>()
+        };
+
+        // Weaker offspring do not replace better parents.
+        let next = next_generation(population.clone(), offspring, 1, 3);
+        assert_eq!(scores(next), vec![5, 4, 3]);
+
+        let offspring = vec![solution(vec![0, 2, 1], 6)];
+        let next = next_generation(population.clone(), offspring, 1, 3);
+        assert_eq!(scores(next), vec![6, 5, 4]);
+
+        // The elite stays even when better offspring would fill the population.
+        let offspring = vec![solution(vec![0, 2, 1], 7), solution(vec![1, 2, 0], 6)];
+        let next = next_generation(population, offspring, 1, 2);
+        assert_eq!(scores(next), vec![7, 5]);
+    }
+
+    #[test]
+    fn deferred_decoder_should_fill_idle_time() {
+        let task = |time| Task::new(time, 1);
+        let conflicts = vec![crate::core::Conflict::new(0, 1)];
+        let tasks = vec![task(2), task(2), task(1), task(1), task(2)];
+        let instance = Instance::new(2, 4, tasks, conflicts);
+
+        // Task 1 waits for task 0, leaving the second machine idle.
+        let schedule = Solution::schedule(&[0, 1, 2, 3, 4], &instance, false);
+        assert_eq!(schedule.calculate_score(), 4);
+
+        let schedule = Solution::schedule(&[0, 1, 2, 3, 4], &instance, true);
+        assert_eq!(schedule.calculate_score(), 5);
+        assert!(schedule.verify());
+    }
+
+    #[test]
+    fn decoder_should_use_eligible_machines() {
+        let task = |eligible| Task {
+            eligible,
+            ..Task::new(2, 1)
+        };
+        let tasks = vec![task(Some(vec![1])), task(Some(vec![1])), task(None)];
+        let instance = Instance::new_no_conflict(2, 4, tasks);
+
+        let schedule = Solution::schedule(&[0, 1, 2], &instance, true);
+        assert!(schedule.verify());
+        assert_eq!(schedule.get_schedule(1), Some(&ScheduleInfo::new(2, 1)));
+        assert_eq!(schedule.get_schedule(2), Some(&ScheduleInfo::new(0, 0)));
+        assert!(Genetic::new(0, 10).schedule(&instance).verify());
+    }
+
+    #[test]
+    fn decoder_should_respect_release_times() {
+        let task = |release| Task {
+            release,
+            ..Task::new(2, 1)
+        };
+        let instance = Instance::new_no_conflict(1, 6, vec![task(3), task(0)]);
+
+        let schedule = Solution::schedule(&[0, 1], &instance, true);
+        assert!(schedule.verify());
+        assert_eq!(schedule.get_schedule(0), Some(&ScheduleInfo::new(3, 0)));
+        assert_eq!(schedule.calculate_score(), 1);
+        assert!(Genetic::new(0, 10).schedule(&instance).verify());
+    }
 }
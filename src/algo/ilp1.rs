@@ -5,19 +5,49 @@
 )]
 use super::gurobi::{conflict_vars, create_model, tardy_vars};
 use crate::cast_usize;
-use crate::core::{Instance, Schedule, ScheduleInfo, Scheduler, Task};
+use crate::core::{Decision, Instance, Schedule, ScheduleInfo, Scheduler, Task};
 use anyhow::Result;
 use grb::prelude::*;
+use std::time::Duration;
 
 /// ILP1 scheduler.
 /// This scheduler uses the Gurobi solver to solve the instance.
-/// Its solve function panics if the Gurobi solver fails.
+/// Its `schedule` panics if the Gurobi solver fails, `try_schedule` returns the error.
 #[derive(Clone, Debug, Default)]
 pub struct ILP1;
 
 impl Scheduler for ILP1 {
     fn schedule<'a>(&mut self, instance: &'a Instance) -> Schedule<'a> {
-        ilp1_impl(instance).unwrap_or_else(|err| panic!("Gurobi failed {err}"))
+        self.try_schedule(instance)
+            .unwrap_or_else(|err| panic!("Gurobi failed {err}"))
+    }
+
+    fn try_schedule<'a>(&mut self, instance: &'a Instance) -> Result<Schedule<'a>> {
+        ilp1_impl(instance)
+    }
+
+    fn try_schedule_traced<'a>(
+        &mut self,
+        instance: &'a Instance,
+    ) -> Result<(Schedule<'a>, Vec<Decision>)> {
+        let schedule = self.try_schedule(instance)?;
+        let decisions = Decision::from_schedule(&schedule);
+        Ok((schedule, decisions))
+    }
+
+    /// The solver runs to completion, the budget is ignored.
+    fn try_schedule_with_budget<'a>(
+        &mut self,
+        instance: &'a Instance,
+        _budget: Duration,
+    ) -> Result<Schedule<'a>> {
+        self.try_schedule(instance)
+    }
+
+    /// Release times and eligible processors are not modelled, so such instances are not
+    /// supported.
+    fn supports(&self, instance: &Instance) -> bool {
+        !instance.has_release_times() && !instance.has_eligibility_restrictions()
     }
 
     fn name(&self) -> &'static str {
@@ -186,4 +216,18 @@ mod test {
     fn test_ilp1() {
         assert!(samples(usize::MAX, &mut ILP1).is_ok());
     }
+
+    #[test]
+    fn restricted_instances_should_not_be_supported() {
+        let task = |release, eligible| Task {
+            release,
+            eligible,
+            ..Task::new(1, 1)
+        };
+        let supports = |task| ILP1.supports(&Instance::new_no_conflict(2, 4, vec![task]));
+
+        assert!(supports(task(0, None)));
+        assert!(!supports(task(1, None)));
+        assert!(!supports(task(0, Some(vec![0]))));
+    }
 }
@@ -4,79 +4,257 @@
     clippy::cast_sign_loss
 )]
 use super::gurobi::{conflict_vars, create_model, tardy_vars};
-use crate::core::{Instance, Machine, Schedule, ScheduleInfo, Scheduler, Task};
+use crate::core::{
+    Decision, Instance, MachineOrder, Machines, Schedule, ScheduleInfo, Scheduler, Task,
+};
 use crate::{cast_u64, cast_usize};
 use anyhow::Result;
 use grb::prelude::*;
-use std::collections::BTreeSet;
+use std::time::Duration;
 
 /// ILP2 scheduler.
+///
 /// This scheduler uses the Gurobi solver to solve the instance.
-/// Its solve function panics if the Gurobi solver fails.
-#[derive(Clone, Debug, Default)]
-pub struct ILP2;
+/// Its `schedule` panics if the Gurobi solver fails, `try_schedule` returns the error.
+/// Instances needing too many variables are rejected before the model is built.
+#[derive(Clone, Debug)]
+pub struct ILP2 {
+    symmetry_breaking: bool,
+    objective: Objective,
+    max_variables: usize,
+}
+
+impl Default for ILP2 {
+    fn default() -> Self {
+        Self {
+            symmetry_breaking: false,
+            objective: Objective::default(),
+            max_variables: 10_000_000,
+        }
+    }
+}
+
+/// Objective of the `ILP2` model. Both give the same optimal schedules.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Objective {
+    /// Minimize the total weight of tardy tasks.
+    #[default]
+    TardyWeight,
+    /// Maximize the total weight of tasks scheduled before the deadline.
+    /// The objective value is the score, so it needs no conversion.
+    ScheduledWeight,
+}
 
 impl ILP2 {
+    /// Sets whether interchangeable tasks are ordered in the model.
+    ///
+    /// Equal tasks with the same conflicts can swap places in any solution, so the solver
+    /// explores every order of them. With symmetry breaking such tasks are tardy only after
+    /// the earlier ones are and start no earlier than them, which keeps the optimum.
+    #[must_use]
+    pub const fn with_symmetry_breaking(mut self, symmetry_breaking: bool) -> Self {
+        self.symmetry_breaking = symmetry_breaking;
+        self
+    }
+
+    /// Sets the objective of the model.
+    #[must_use]
+    pub const fn with_objective(mut self, objective: Objective) -> Self {
+        self.objective = objective;
+        self
+    }
+
+    /// Sets the maximum number of position variables of the model, 10 million by default.
+    /// There is a variable for every task and start time, so long deadlines need many of them.
+    #[must_use]
+    pub const fn with_max_variables(mut self, max_variables: usize) -> Self {
+        self.max_variables = max_variables;
+        self
+    }
+
     /// Estimate the upper bound of the instance.
     ///
     /// # Errors
+    /// - If the model would need more variables than allowed.
     /// - If the Gurobi solver fails.
     pub fn estimate_upper_bound(&self, instance: &Instance, timeout: f64) -> Result<u64> {
-        let (mut model, _) = prepare_model(instance, timeout)?;
+        let (mut model, ..) = prepare_model(instance, timeout, self)?;
         model.optimize()?;
 
-        let min_delayed = model.get_attr(attr::ObjBound)?.ceil() as u64;
-        Ok(instance.tasks.iter().map(|t| t.weight).sum::<u64>() - min_delayed)
+        upper_bound(&model, instance, self.objective)
+    }
+
+    /// Estimate the upper bound of the instance and return the best schedule found by the
+    /// same solve, whose score is at most the bound.
+    ///
+    /// # Errors
+    /// - If the model would need more variables than allowed.
+    /// - If the Gurobi solver fails or finds no schedule within the timeout.
+    pub fn estimate_with_schedule<'a>(
+        &self,
+        instance: &'a Instance,
+        timeout: f64,
+    ) -> Result<(u64, Schedule<'a>)> {
+        if instance.tasks.is_empty() {
+            return Ok((0, Schedule::new(instance)));
+        }
+
+        let (mut model, _, v) = prepare_model(instance, timeout, self)?;
+        model.optimize()?;
+
+        let bound = upper_bound(&model, instance, self.objective)?;
+        Ok((bound, read_schedule(&model, instance, &v)?))
+    }
+
+    /// Schedules the instance with a known schedule as the MIP start.
+    /// A good start lets the solver prune the search and prove optimality faster.
+    ///
+    /// # Errors
+    /// - If the model would need more variables than allowed.
+    /// - If the Gurobi solver fails.
+    pub fn schedule_with_start<'a>(
+        &mut self,
+        instance: &'a Instance,
+        start: &Schedule,
+    ) -> Result<Schedule<'a>> {
+        ilp2_impl(instance, Some(start), self)
     }
 }
 
 impl Scheduler for ILP2 {
     fn schedule<'a>(&mut self, instance: &'a Instance) -> Schedule<'a> {
-        ilp2_impl(instance).unwrap_or_else(|err| panic!("Gurobi failed {err}"))
+        self.try_schedule(instance)
+            .unwrap_or_else(|err| panic!("Gurobi failed {err}"))
+    }
+
+    fn try_schedule<'a>(&mut self, instance: &'a Instance) -> Result<Schedule<'a>> {
+        ilp2_impl(instance, None, self)
+    }
+
+    fn try_schedule_traced<'a>(
+        &mut self,
+        instance: &'a Instance,
+    ) -> Result<(Schedule<'a>, Vec<Decision>)> {
+        let schedule = self.try_schedule(instance)?;
+        let decisions = Decision::from_schedule(&schedule);
+        Ok((schedule, decisions))
+    }
+
+    /// The solver runs to completion, the budget is ignored.
+    fn try_schedule_with_budget<'a>(
+        &mut self,
+        instance: &'a Instance,
+        _budget: Duration,
+    ) -> Result<Schedule<'a>> {
+        self.try_schedule(instance)
+    }
+
+    /// Release times and eligible processors are not modelled, so such instances are not
+    /// supported.
+    fn supports(&self, instance: &Instance) -> bool {
+        !instance.has_release_times() && !instance.has_eligibility_restrictions()
     }
 
     fn name(&self) -> &'static str {
-        "ILP2"
+        if self.symmetry_breaking {
+            "ILP2-symmetry-breaking"
+        } else {
+            "ILP2"
+        }
     }
 }
 
 #[allow(unsafe_code)]
 #[linkme::distributed_slice(super::SCHEDULERS)]
-static INSTANCE: fn() -> Box<dyn Scheduler> = || Box::new(ILP2);
+static INSTANCE: fn() -> Box<dyn Scheduler> = || Box::new(ILP2::default());
 
-fn ilp2_impl(instance: &Instance) -> Result<Schedule> {
+#[allow(unsafe_code)]
+#[linkme::distributed_slice(super::SCHEDULERS)]
+static SYMMETRY_BREAKING: fn() -> Box<dyn Scheduler> =
+    || Box::new(ILP2::default().with_symmetry_breaking(true));
+
+fn ilp2_impl<'a>(
+    instance: &'a Instance,
+    start: Option<&Schedule>,
+    config: &ILP2,
+) -> Result<Schedule<'a>> {
     if instance.tasks.is_empty() {
         return Ok(Schedule::new(instance));
     }
 
-    let (mut model, v) = prepare_model(instance, 600.0)?;
+    let (mut model, u, v) = prepare_model(instance, 600.0, config)?;
+    if let Some(start) = start {
+        set_start(&model, start, &u, &v)?;
+    }
     model.optimize()?;
 
+    read_schedule(&model, instance, &v)
+}
+
+/// Returns the upper bound on the score proven by the solved model.
+fn upper_bound(model: &Model, instance: &Instance, objective: Objective) -> Result<u64> {
+    let bound = model.get_attr(attr::ObjBound)?;
+    Ok(match objective {
+        Objective::TardyWeight => instance.total_weight().saturating_sub(bound.ceil() as u64),
+        Objective::ScheduledWeight => bound.floor() as u64,
+    })
+}
+
+/// Reads the schedule of the best solution of the solved model.
+fn read_schedule<'a>(
+    model: &Model,
+    instance: &'a Instance,
+    v: &[Vec<Var>],
+) -> Result<Schedule<'a>> {
     let mut result = Schedule::new(instance);
-    let mut machines: BTreeSet<_> = (0..instance.processors).map(Machine::new).collect();
+    let mut machines = Machines::new(instance.processors, MachineOrder::Id);
 
     for t in 0..cast_usize(instance.deadline) {
         for (j, task) in v.iter().enumerate() {
             if task.len() > t && model.get_obj_attr(attr::X, &task[t])? as i64 == 1 {
-                let Some(machine) = machines.iter().find(|m| m.free <= cast_u64(t)) else {
+                // Machines are ordered by free time, so the first one is free if any is.
+                let Some(mut machine) = machines.pop_first().filter(|m| m.free <= cast_u64(t))
+                else {
                     unreachable!("Must be free machine before time `t`");
                 };
 
                 result.schedule(j, ScheduleInfo::new(cast_u64(t), machine.id));
 
-                let mut machine = *machine;
-                machines.remove(&machine);
                 machine.free = cast_u64(t) + instance.tasks[j].time;
                 machines.insert(machine);
             }
         }
     }
 
+    result.rebalance();
+
     Ok(result)
 }
 
-#[allow(clippy::useless_conversion)]
-fn prepare_model(instance: &Instance, timeout: f64) -> Result<(Model, Vec<Vec<Var>>)> {
+/// Sets the MIP start of tardy and position variables from the schedule.
+/// Tasks finishing after the deadline start as tardy, conflict variables are left to the solver.
+fn set_start(model: &Model, start: &Schedule, u: &[Var], v: &[Vec<Var>]) -> Result<()> {
+    for (j, (uj, vj)) in u.iter().zip(v).enumerate() {
+        let begin = start.get_schedule(j).map(|info| cast_usize(info.start));
+        let begin = begin.filter(|&t| t < vj.len());
+
+        model.set_obj_attr(attr::Start, uj, if begin.is_some() { 0.0 } else { 1.0 })?;
+        for (t, vjt) in vj.iter().enumerate() {
+            let value = if begin == Some(t) { 1.0 } else { 0.0 };
+            model.set_obj_attr(attr::Start, vjt, value)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::useless_conversion, clippy::type_complexity)]
+fn prepare_model(
+    instance: &Instance,
+    timeout: f64,
+    config: &ILP2,
+) -> Result<(Model, Vec<Var>, Vec<Vec<Var>>)> {
+    check_model_size(instance, config.max_variables)?;
     let mut model = create_model("ILP2", timeout)?;
 
     let tasks = &instance.tasks;
@@ -120,10 +298,82 @@ fn prepare_model(instance: &Instance, timeout: f64) -> Result<(Model, Vec<Vec<Va
         }
     }
 
-    let expr = u.iter().enumerate().map(|(j, &uj)| uj * tasks[j].weight);
-    model.set_objective(expr.grb_sum(), Minimize)?;
+    if config.symmetry_breaking {
+        let start = |vj: &[Var]| vj.iter().enumerate().map(|(t, &vjt)| t * vjt).grb_sum();
+
+        for (j, g) in interchangeable_pairs(instance) {
+            model.add_constr(&format!("c_4_{j}_{g}"), c!(u[j] <= u[g]))?;
+            let right = start(&v[g]) + d * u[g];
+            model.add_constr(&format!("c_5_{j}_{g}"), c!(start(&v[j]) <= right))?;
+        }
+    }
 
-    Ok((model, v))
+    match config.objective {
+        Objective::TardyWeight => {
+            let expr = u.iter().enumerate().map(|(j, &uj)| uj * tasks[j].weight);
+            model.set_objective(expr.grb_sum(), Minimize)?;
+        }
+        Objective::ScheduledWeight => {
+            let expr = u
+                .iter()
+                .enumerate()
+                .map(|(j, &uj)| tasks[j].weight * (1 - uj));
+            model.set_objective(expr.grb_sum(), Maximize)?;
+        }
+    }
+
+    Ok((model, u, v))
+}
+
+/// Returns pairs `(j, g)` of interchangeable tasks with `j < g`, consecutive in their group.
+/// Tasks are interchangeable when they are equal and conflict with the same other tasks,
+/// so swapping them in a solution gives another solution with the same objective.
+fn interchangeable_pairs(instance: &Instance) -> Vec<(usize, usize)> {
+    let tasks = &instance.tasks;
+    let graph = &instance.graph;
+    let same_conflicts = |j: usize, g: usize| {
+        let others = |a: usize, b: usize| graph.conflicts(a).iter().filter(move |&&c| c != b);
+        others(j, g).count() == others(g, j).count()
+            && others(j, g).all(|c| graph.conflicts(g).contains(c))
+    };
+
+    let mut pairs = Vec::new();
+    let mut grouped = vec![false; tasks.len()];
+    for j in 0..tasks.len() {
+        if grouped[j] {
+            continue;
+        }
+
+        let mut last = j;
+        for g in j + 1..tasks.len() {
+            if !grouped[g] && tasks[g] == tasks[j] && same_conflicts(j, g) {
+                grouped[g] = true;
+                pairs.push((last, g));
+                last = g;
+            }
+        }
+    }
+    pairs
+}
+
+/// Checks that the position variables of the model fit within the limit.
+/// It is done before building the model, which could run out of memory otherwise.
+fn check_model_size(instance: &Instance, max_variables: usize) -> Result<()> {
+    let d = cast_usize(instance.deadline);
+    let count = instance.tasks.iter().try_fold(0_usize, |count, task| {
+        count.checked_add(d.checked_add(1)?.saturating_sub(cast_usize(task.time)))
+    });
+
+    match count {
+        Some(count) if count <= max_variables => Ok(()),
+        count => Err(anyhow::anyhow!(
+            "ILP2 needs {} position variables for {} tasks and deadline {}, the limit is {}",
+            count.map_or_else(|| "more than usize::MAX".into(), |count| count.to_string()),
+            instance.tasks.len(),
+            instance.deadline,
+            max_variables
+        )),
+    }
 }
 
 fn position_vars(model: &mut Model, tasks: &[Task], d: usize) -> Result<Vec<Vec<Var>>> {
@@ -145,10 +395,115 @@ fn c1_sum(d: usize, t: usize, vj: &[Var], task: &Task) -> Expr {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::core::Conflict;
     use crate::data::samples;
 
     #[test]
     fn test_ilp2() {
-        assert!(samples(usize::MAX, &mut ILP2).is_ok());
+        assert!(samples(usize::MAX, &mut ILP2::default()).is_ok());
+    }
+
+    #[test]
+    fn test_ilp2_symmetry_breaking() {
+        let mut ilp2 = ILP2::default().with_symmetry_breaking(true);
+        assert_eq!(ilp2.name(), "ILP2-symmetry-breaking");
+        assert!(samples(usize::MAX, &mut ilp2).is_ok());
+    }
+
+    #[test]
+    fn test_ilp2_scheduled_weight() {
+        let mut ilp2 = ILP2::default().with_objective(Objective::ScheduledWeight);
+        assert!(samples(usize::MAX, &mut ilp2).is_ok());
+    }
+
+    #[test]
+    fn estimate_should_come_with_its_schedule() -> Result<()> {
+        let file = std::fs::File::open("samples/3_50_0.in")?;
+        let instance: Instance = crate::data::deserialize(&mut std::io::BufReader::new(file))?;
+
+        let (estimate, schedule) = ILP2::default().estimate_with_schedule(&instance, 60.0)?;
+        assert!(schedule.verify());
+        assert_eq!(estimate, 50);
+        assert_eq!(schedule.calculate_score(), estimate);
+        Ok(())
+    }
+
+    #[test]
+    fn objectives_should_give_same_bound() -> Result<()> {
+        let file = std::fs::File::open("samples/3_50_0.in")?;
+        let instance: Instance = crate::data::deserialize(&mut std::io::BufReader::new(file))?;
+
+        let scheduled = ILP2::default().with_objective(Objective::ScheduledWeight);
+        assert_eq!(
+            scheduled.estimate_upper_bound(&instance, 60.0)?,
+            ILP2::default().estimate_upper_bound(&instance, 60.0)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn restricted_instances_should_not_be_supported() {
+        let task = |release, eligible| Task {
+            release,
+            eligible,
+            ..Task::new(1, 1)
+        };
+        let supports =
+            |task| ILP2::default().supports(&Instance::new_no_conflict(2, 4, vec![task]));
+
+        assert!(supports(task(0, None)));
+        assert!(!supports(task(1, None)));
+        assert!(!supports(task(0, Some(vec![0]))));
+    }
+
+    #[test]
+    fn huge_deadline_should_be_rejected() {
+        let task = Task::new(1, 1);
+        let instance = Instance::new_no_conflict(1, u64::MAX / 2, vec![task.clone(); 3]);
+
+        let error = ILP2::default().try_schedule(&instance).err();
+        assert!(error.is_some_and(|error| error.to_string().contains("position variables")));
+        assert!(check_model_size(&instance, usize::MAX).is_err());
+
+        let instance = Instance::new_no_conflict(1, u64::MAX, vec![task.clone()]);
+        assert!(check_model_size(&instance, usize::MAX).is_err());
+
+        let instance = Instance::new_no_conflict(1, 10, vec![Task { time: 2, ..task }; 3]);
+        assert!(check_model_size(&instance, 27).is_ok());
+        assert!(check_model_size(&instance, 26).is_err());
+    }
+
+    #[test]
+    fn interchangeable_pairs_should_chain_equal_tasks() {
+        let task = |time| Task::new(time, 1);
+        let tasks = vec![task(1), task(1), task(2), task(1), task(1)];
+        let conflicts = vec![Conflict::new(0, 2), Conflict::new(3, 2)];
+        let instance = Instance::new(1, 4, tasks, conflicts);
+
+        assert_eq!(interchangeable_pairs(&instance), [(0, 3), (1, 4)]);
+    }
+
+    #[test]
+    fn ilp2_with_start_should_reach_optimum() -> Result<()> {
+        for entry in std::fs::read_dir("samples")? {
+            let path = entry?.path();
+            let name = path.file_name().and_then(|name| name.to_str());
+            let optimum: u64 = name
+                .unwrap_or_default()
+                .split('_')
+                .nth(1)
+                .unwrap_or_default()
+                .parse()?;
+            let file = std::fs::File::open(&path)?;
+            let instance: Instance = crate::data::deserialize(&mut std::io::BufReader::new(file))?;
+
+            let start = crate::algo::List.schedule(&instance);
+            let schedule = ILP2::default().schedule_with_start(&instance, &start)?;
+
+            assert!(schedule.verify());
+            assert_eq!(schedule.calculate_score(), optimum);
+        }
+
+        Ok(())
     }
 }
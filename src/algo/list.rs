@@ -1,30 +1,58 @@
-use crate::core::{weighted_task_comparator, Instance, Schedule, ScheduleBuilder, TaskWithId};
+use crate::core::{
+    weighted_task_comparator, Decision, Instance, Reason, Schedule, ScheduleBuilder, ScheduleInfo,
+    TaskWithId,
+};
 
 /// Simple list scheduling algorithm.
 /// Returns an initial schedule, machine schedules and tardy tasks.
-pub(super) fn schedule(instance: &Instance) -> ScheduleBuilder {
+pub(super) fn schedule(instance: &Instance) -> ScheduleBuilder<'_> {
+    schedule_impl(instance, None)
+}
+
+fn schedule_impl<'a>(
+    instance: &'a Instance,
+    mut trace: Option<&mut Vec<Decision>>,
+) -> ScheduleBuilder<'a> {
     let mut schedule = ScheduleBuilder::new(instance);
     let mut machines = schedule.new_machine_free_times();
 
-    let mut tasks: Vec<TaskWithId> = instance.tasks.iter().copied().enumerate().collect();
+    let mut tasks: Vec<TaskWithId> = instance.tasks.iter().cloned().enumerate().collect();
     tasks.sort_unstable_by(weighted_task_comparator);
 
     for task in tasks {
-        let Some(mut machine) = machines.pop_first() else {
-            unreachable!("No available machines");
+        let Some(mut machine) = machines.pop_eligible(&task.1) else {
+            if let Some(trace) = trace.as_mut() {
+                trace.push(Decision::new(task.0, None, Reason::Ineligible));
+            }
+            schedule.tardy(task.0);
+            continue;
         };
 
-        let time = if schedule.in_conflict(task.0, machine.free) {
-            schedule.calculate_non_conflict_time(task.0, machine.free)
-        } else if machine.free + task.1.time <= instance.deadline {
-            Some(machine.free)
+        let start = machine.free.max(task.1.release);
+        let time = if schedule.in_conflict(task.0, start) {
+            schedule.calculate_non_conflict_time(task.0, start)
+        } else if task.1.fits_before(start, instance.deadline) {
+            Some(start)
         } else {
             None
         };
 
+        if let Some(trace) = trace.as_mut() {
+            let reason = match time {
+                Some(time) if time > start => Reason::Conflict,
+                Some(time) if time > machine.free => Reason::Release,
+                Some(_) => Reason::Free,
+                None if schedule.in_conflict(task.0, start) => Reason::Conflict,
+                None => Reason::Deadline,
+            };
+            let placement = time.map(|time| ScheduleInfo::new(time, machine.id));
+            trace.push(Decision::new(task.0, placement, reason));
+        }
+
         if let Some(time) = time {
             schedule.schedule(task.0, time, machine.id);
-            machine.free = time + task.1.time;
+            machine.free = task.1.completion(time);
+            machine.tasks += 1;
         } else {
             schedule.tardy(task.0);
         }
@@ -44,6 +72,12 @@ impl crate::core::Scheduler for List {
         schedule(instance).into()
     }
 
+    fn schedule_traced<'a>(&mut self, instance: &'a Instance) -> (Schedule<'a>, Vec<Decision>) {
+        let mut decisions = Vec::with_capacity(instance.tasks.len());
+        let schedule = schedule_impl(instance, Some(&mut decisions));
+        (schedule.into(), decisions)
+    }
+
     fn name(&self) -> &'static str {
         "List"
     }
@@ -56,10 +90,77 @@ static INSTANCE: fn() -> Box<dyn crate::core::Scheduler> = || Box::new(List);
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::core::{Conflict, Scheduler, Task};
     use crate::data::samples;
 
     #[test]
     fn test_list() {
         assert!(samples(0, &mut List).is_ok());
     }
+
+    #[test]
+    fn test_list_release() {
+        let tasks = vec![
+            Task {
+                release: 5,
+                ..Task::new(2, 10)
+            },
+            Task::new(2, 1),
+        ];
+        let instance = Instance::new_no_conflict(1, 10, tasks);
+        let schedule: Schedule = schedule(&instance).into();
+
+        assert!(schedule.verify());
+        assert_eq!(schedule.get_schedule(0).map(|info| info.start), Some(5));
+        assert_eq!(schedule.get_schedule(1).map(|info| info.start), Some(7));
+        assert_eq!(schedule.calculate_score(), 11);
+    }
+
+    #[test]
+    fn test_list_eligible() {
+        let task = |time, weight, eligible| Task {
+            eligible,
+            ..Task::new(time, weight)
+        };
+        let tasks = vec![
+            task(2, 10, None),
+            task(1, 5, None),
+            task(4, 8, Some(vec![0])),
+        ];
+        let instance = Instance::new_no_conflict(2, 10, tasks);
+        let mut schedule: Schedule = schedule(&instance).into();
+
+        assert!(schedule.verify());
+        assert_eq!(schedule.get_schedule(2), Some(&ScheduleInfo::new(2, 0)));
+        assert_eq!(schedule.calculate_score(), 23);
+
+        schedule.schedule(2, ScheduleInfo::new(1, 1));
+        assert!(!schedule.verify());
+    }
+
+    #[test]
+    fn test_list_traced() {
+        let task = Task::new(3, 1);
+        let conflicts = vec![Conflict::new(0, 1), Conflict::new(0, 2)];
+        let instance = Instance::new(2, 5, vec![task; 3], conflicts);
+        let (schedule, decisions) = List.schedule_traced(&instance);
+
+        assert!(schedule.verify());
+        assert_eq!(
+            decisions,
+            vec![
+                Decision::new(0, Some(ScheduleInfo::new(0, 0)), Reason::Free),
+                Decision::new(1, None, Reason::Conflict),
+                Decision::new(2, None, Reason::Conflict),
+            ]
+        );
+        assert_eq!(
+            decisions[0].to_string(),
+            "task 0: machine 0 at 0 (machine free)"
+        );
+        assert_eq!(
+            decisions[1].to_string(),
+            "task 1: tardy (conflicting tasks)"
+        );
+    }
 }
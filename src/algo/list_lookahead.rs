@@ -0,0 +1,118 @@
+use crate::core::{
+    weighted_task_comparator, Instance, Schedule, ScheduleBuilder, Scheduler, TaskWithId,
+};
+
+/// List scheduling algorithm with a one-step lookahead over conflicts.
+///
+/// Tasks are taken in the same order as by [`List`](super::List). Before a task is placed,
+/// it is checked whether the placement leaves no time for a heavier conflicting task later
+/// in the order. Such a task is deferred to the tardy tasks, which are placed at the end.
+#[derive(Clone, Debug, Default)]
+pub struct ListLookahead;
+
+impl Scheduler for ListLookahead {
+    fn schedule<'a>(&mut self, instance: &'a Instance) -> Schedule<'a> {
+        list_lookahead(instance).into()
+    }
+
+    fn name(&self) -> &'static str {
+        "ListLookahead"
+    }
+}
+
+#[allow(unsafe_code)]
+#[linkme::distributed_slice(super::SCHEDULERS)]
+static INSTANCE: fn() -> Box<dyn Scheduler> = || Box::new(ListLookahead);
+
+fn list_lookahead(instance: &Instance) -> ScheduleBuilder<'_> {
+    let mut schedule = ScheduleBuilder::new(instance);
+    let mut machines = schedule.new_machine_free_times();
+
+    let mut tasks: Vec<TaskWithId> = instance.tasks.iter().cloned().enumerate().collect();
+    tasks.sort_unstable_by(weighted_task_comparator);
+
+    let mut position = vec![0; tasks.len()];
+    for (index, &(id, _)) in tasks.iter().enumerate() {
+        position[id] = index;
+    }
+
+    for (id, task) in tasks {
+        let Some(mut machine) = machines.pop_eligible(&task) else {
+            schedule.tardy(id);
+            continue;
+        };
+
+        let start = machine.free.max(task.release);
+        let time = if schedule.in_conflict(id, start) {
+            schedule.calculate_non_conflict_time(id, start)
+        } else if task.fits_before(start, instance.deadline) {
+            Some(start)
+        } else {
+            None
+        };
+
+        let blocking = time.is_some_and(|time| {
+            let mut later = instance.graph.conflicts(id).iter();
+            later.any(|&other| {
+                position[other] > position[id]
+                    && instance.tasks[other].weight > task.weight
+                    && blocks(&schedule, id, time, other)
+            })
+        });
+
+        match time {
+            Some(time) if !blocking => {
+                schedule.schedule(id, time, machine.id);
+                machine.free = task.completion(time);
+                machine.tasks += 1;
+            }
+            _ => schedule.tardy(id),
+        }
+
+        machines.insert(machine);
+    }
+
+    // Deferred tasks get another chance after the end of every machine.
+    schedule.reorganize_schedule(|_, _| (vec![], vec![]));
+    schedule
+}
+
+/// Returns whether placing the task at `time` leaves no time for the unscheduled `other` task.
+/// Machines are not considered, only the conflicts of `other`.
+fn blocks(schedule: &ScheduleBuilder, task: usize, time: u64, other: usize) -> bool {
+    let Some(free) = schedule.calculate_non_conflict_time(other, 0) else {
+        return false;
+    };
+
+    let tasks = &schedule.instance().tasks;
+    let end = tasks[task].completion(time);
+    // Once the placement overlaps the first free time, every earlier start overlaps it too.
+    free < end
+        && time < tasks[other].completion(free)
+        && schedule.calculate_non_conflict_time(other, end).is_none()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algo::List;
+    use crate::core::{Conflict, Task};
+    use crate::data::samples;
+
+    #[test]
+    fn test_list_lookahead() {
+        assert!(samples(0, &mut ListLookahead).is_ok());
+    }
+
+    #[test]
+    fn lookahead_should_defer_blocking_task() {
+        let tasks = vec![Task::new(1, 2), Task::new(4, 4)];
+        let instance = Instance::new(2, 4, tasks, vec![Conflict::new(0, 1)]);
+
+        assert_eq!(List.schedule(&instance).calculate_score(), 2);
+
+        let schedule = ListLookahead.schedule(&instance);
+        assert!(schedule.verify());
+        assert_eq!(schedule.calculate_score(), 4);
+    }
+}
@@ -1,7 +1,7 @@
 use std::iter::{once, repeat};
 
 /// A weighted graph.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct Graph {
     max_weight: i128,
     edges: Vec<(usize, usize, i128)>,
@@ -10,6 +10,17 @@ pub struct Graph {
 }
 
 impl Graph {
+    /// Creates a graph with `n` vertices from the edges.
+    /// The layout is the same as of adding the edges one by one, if all `n` vertices are used.
+    pub fn from_edges(n: usize, edges: impl IntoIterator<Item = (usize, usize, i128)>) -> Self {
+        let mut graph = Self {
+            neighbors: vec![Vec::new(); n],
+            ..Self::default()
+        };
+        graph.add_edges(edges);
+        graph
+    }
+
     /// Adds an edge to the graph.
     pub fn add_edge(&mut self, from: usize, to: usize, weight: impl Into<i128>) {
         let weight = weight.into();
@@ -28,6 +39,32 @@ impl Graph {
         self.endpoints.push(to);
     }
 
+    /// Adds edges to the graph in order, as if by [`Graph::add_edge`] one by one.
+    /// The adjacency lists are resized once for all the edges.
+    pub fn add_edges<W: Into<i128>>(&mut self, edges: impl IntoIterator<Item = (usize, usize, W)>) {
+        let start = self.edges.len();
+        let edges = edges
+            .into_iter()
+            .map(|(from, to, weight)| (from, to, weight.into()));
+        self.edges.extend(edges);
+
+        let added = &self.edges[start..];
+        if let Some(max_vertex) = added.iter().map(|&(from, to, _)| from.max(to)).max() {
+            if max_vertex >= self.neighbors.len() {
+                self.neighbors.resize(max_vertex + 1, Vec::new());
+            }
+        }
+
+        self.endpoints.reserve(added.len() * 2);
+        for &(from, to, weight) in added {
+            self.max_weight = self.max_weight.max(weight);
+            self.neighbors[to].push(self.endpoints.len());
+            self.endpoints.push(from);
+            self.neighbors[from].push(self.endpoints.len());
+            self.endpoints.push(to);
+        }
+    }
+
     /// Returns whether the graph is empty (has no edges).
     pub fn is_empty(&self) -> bool {
         self.edges.is_empty()
@@ -38,6 +75,19 @@ impl Graph {
         self.neighbors.len()
     }
 
+    /// Returns the edges of the graph as `(from, to, weight)` in insertion order.
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn edges(&self) -> &[(usize, usize, i128)] {
+        &self.edges
+    }
+
+    /// Returns the endpoint indices of edges incident to the vertex.
+    /// Endpoint `p` belongs to edge `p / 2` and leads to the other vertex of that edge.
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn neighbors(&self, vertex: usize) -> &[usize] {
+        self.neighbors.get(vertex).map_or(&[], Vec::as_slice)
+    }
+
     /// Returns the max weight of edges in the graph.
     pub const fn max_weight(&self) -> i128 {
         self.max_weight
@@ -50,9 +100,20 @@ impl Graph {
 /// Arguments:
 /// - `graph`: The graph to find the matching in.
 /// - `max_card`: Whether to find the maximum cardinality matching or the maximum weight matching.
+#[cfg_attr(not(test), allow(dead_code))]
 pub fn gabow_algo(graph: &Graph, max_cardinality: bool) -> Vec<Option<usize>> {
+    gabow_algo_with_weight(graph, max_cardinality).0
+}
+
+/// Find the maximum weighted matching in a graph and its total weight.
+/// Has a time complexity of `O(n^3)`.
+///
+/// Arguments:
+/// - `graph`: The graph to find the matching in.
+/// - `max_card`: Whether to find the maximum cardinality matching or the maximum weight matching.
+pub fn gabow_algo_with_weight(graph: &Graph, max_cardinality: bool) -> (Vec<Option<usize>>, i128) {
     if graph.is_empty() {
-        return Vec::new();
+        return (Vec::new(), 0);
     }
 
     let n = graph.vertex_count();
@@ -98,7 +159,7 @@ struct Algorithm<'a> {
     queue: Vec<usize>,
 }
 
-impl<'a> Algorithm<'a> {
+impl Algorithm<'_> {
     fn slack(&self, k: usize) -> i128 {
         let edge = &self.graph.edges[k];
         self.dual_var[edge.0] + self.dual_var[edge.1] - 2 * edge.2
@@ -503,7 +564,7 @@ impl<'a> Algorithm<'a> {
         delta_type
     }
 
-    fn run(mut self, max_cardinality: bool) -> Vec<Option<usize>> {
+    fn run(mut self, max_cardinality: bool) -> (Vec<Option<usize>>, i128) {
         for _ in 0..self.graph.vertex_count() {
             self.label.fill(0);
             self.best_edge.fill(None);
@@ -601,13 +662,19 @@ impl<'a> Algorithm<'a> {
             }
         }
 
+        let mut weight = 0;
+
         for vertex in 0..self.graph.vertex_count() {
             if let Some(mate) = self.mate[vertex].as_mut() {
+                // Every matched edge is counted once, from its lower endpoint.
+                if vertex < self.graph.endpoints[*mate] {
+                    weight += self.graph.edges[*mate / 2].2;
+                }
                 *mate = self.graph.endpoints[*mate];
             }
         }
 
-        self.mate
+        (self.mate, weight)
     }
 }
 
@@ -625,7 +692,7 @@ enum DeltaType {
 
 type BlossomIterator<'a> = Box<dyn Iterator<Item = usize> + 'a>;
 
-fn blossom_leaves(b: usize, n: usize, children: &[Option<Vec<usize>>]) -> BlossomIterator {
+fn blossom_leaves(b: usize, n: usize, children: &[Option<Vec<usize>>]) -> BlossomIterator<'_> {
     if b < n {
         Box::new(once(b))
     } else {
@@ -680,6 +747,16 @@ mod tests {
         assert_eq!(gabow_algo(&graph![], false), mate![]);
     }
 
+    #[test]
+    fn test_accessors() {
+        let graph = graph![(0, 1, 3), (1, 2, 4)];
+        assert_eq!(graph.edges(), &[(0, 1, 3), (1, 2, 4)]);
+        assert_eq!(graph.neighbors(0), &[1]);
+        assert_eq!(graph.neighbors(1), &[0, 3]);
+        assert_eq!(graph.neighbors(2), &[2]);
+        assert!(graph.neighbors(3).is_empty());
+    }
+
     #[test]
     fn test_single_edge() {
         assert_eq!(gabow_algo(&graph![(0, 1, 1)], false), mate![1, 0]);
@@ -703,6 +780,18 @@ mod tests {
         assert_eq!(gabow_algo(&graph, true), mate![-, 2, 1, 4, 3]);
     }
 
+    #[test]
+    fn test_weight() {
+        assert_eq!(gabow_algo_with_weight(&graph![], false).1, 0);
+
+        let graph = graph![(1, 2, 5), (2, 3, 11), (3, 4, 5)];
+        assert_eq!(gabow_algo_with_weight(&graph, false).1, 11);
+        assert_eq!(gabow_algo_with_weight(&graph, true).1, 10);
+
+        let graph = graph![(0, 1, 1), (0, 1, 4)];
+        assert_eq!(gabow_algo_with_weight(&graph, false), (mate![1, 0], 4));
+    }
+
     #[test]
     fn test_negative() {
         let graph = graph![(1, 2, 2), (1, 3, -2), (2, 3, 1), (2, 4, -1), (3, 4, -6)];
@@ -710,6 +799,31 @@ mod tests {
         assert_eq!(gabow_algo(&graph, true), mate![-, 3, 4, 1, 2]);
     }
 
+    #[test]
+    fn add_edges_should_match_add_edge() {
+        let mut graph = graph![(0, 1, 2)];
+        graph.add_edges([(3, 1, 4), (2, 3, 7)]);
+        let expected = graph![(0, 1, 2), (3, 1, 4), (2, 3, 7)];
+
+        assert_eq!(graph.edges(), expected.edges());
+        assert_eq!(graph.max_weight(), expected.max_weight());
+        for vertex in 0..4 {
+            assert_eq!(graph.neighbors(vertex), expected.neighbors(vertex));
+        }
+    }
+
+    #[test]
+    fn from_edges_should_match_add_edge() {
+        let edges = [(0, 1, 2), (3, 1, 4), (2, 3, -7), (1, 2, 0)];
+        let mut expected = Graph::default();
+        for (from, to, weight) in edges {
+            expected.add_edge(from, to, weight);
+        }
+
+        assert_eq!(Graph::from_edges(4, edges), expected);
+        assert_eq!(Graph::from_edges(0, []), Graph::default());
+    }
+
     #[test]
     fn test_s_blossom() {
         let mut graph = graph![(1, 2, 8), (1, 3, 9), (2, 3, 10), (3, 4, 7)];
@@ -1,3 +1,6 @@
+#[cfg(feature = "lp")]
+mod bound;
+mod decomposed;
 mod genetic;
 #[cfg(feature = "gurobi")]
 mod gurobi;
@@ -6,22 +9,199 @@ mod ilp1;
 #[cfg(feature = "gurobi")]
 mod ilp2;
 mod list;
+mod list_lookahead;
 mod matching;
+mod multi_start;
+mod pipeline;
 mod polynomial_time;
+mod preemptive_list;
+mod sa;
+mod seeded;
+mod tabu;
 mod tresoldi;
 mod vns;
 
-pub use genetic::Genetic;
+#[cfg(feature = "lp")]
+pub use bound::fractional_bound;
+pub use decomposed::Decomposed;
+pub use genetic::{Crossover, Genetic, Selection};
 #[cfg(feature = "gurobi")]
 pub use ilp1::ILP1;
 #[cfg(feature = "gurobi")]
-pub use ilp2::ILP2;
+pub use ilp2::{Objective, ILP2};
 pub use list::List;
+pub use list_lookahead::ListLookahead;
+pub use multi_start::MultiStart;
+pub use pipeline::Pipeline;
 pub use polynomial_time::PolynomialTime;
+pub use preemptive_list::PreemptiveList;
+pub use sa::SimulatedAnnealing;
+pub use seeded::Seeded;
+pub use tabu::TabuSearch;
 pub use tresoldi::Tresoldi;
-pub use vns::VariableNeighborhoodSearch;
+pub use vns::{LocalSearchStrategy, VariableNeighborhoodSearch};
 
 use crate::core::Scheduler;
+use std::collections::HashSet;
+use std::sync::{LazyLock, Mutex};
 
 #[linkme::distributed_slice]
 pub static SCHEDULERS: [fn() -> Box<dyn Scheduler>];
+
+/// Returns a static copy of the name, every distinct name is leaked only once.
+fn intern(name: String) -> &'static str {
+    static NAMES: LazyLock<Mutex<HashSet<&'static str>>> = LazyLock::new(Mutex::default);
+
+    let mut names = NAMES
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    if let Some(&name) = names.get(name.as_str()) {
+        return name;
+    }
+    let name = Box::leak(name.into_boxed_str());
+    names.insert(name);
+    name
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::core::{Conflict, Instance, Task};
+    use crate::data::run_seeded;
+    use rand::{RngCore, SeedableRng};
+
+    /// Random number generator repeating the given values, which scripts the random decisions
+    /// of schedulers in tests. Uniform sampling rejects some values and draws again, so the
+    /// values must not all be rejected, e.g. `u32::MAX` alone never gives a value below 2.
+    #[derive(Clone, Debug)]
+    pub(super) struct ScriptedRng {
+        values: Vec<u32>,
+        index: usize,
+    }
+
+    impl ScriptedRng {
+        pub(super) fn new(values: Vec<u32>) -> Self {
+            Self { values, index: 0 }
+        }
+    }
+
+    impl RngCore for ScriptedRng {
+        fn next_u32(&mut self) -> u32 {
+            let value = self.values[self.index % self.values.len()];
+            self.index += 1;
+            value
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            (u64::from(self.next_u32()) << 32) | u64::from(self.next_u32())
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            for byte in dest {
+                *byte = self.next_u32().to_le_bytes()[0];
+            }
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    impl SeedableRng for ScriptedRng {
+        type Seed = [u8; 4];
+
+        fn from_seed(seed: Self::Seed) -> Self {
+            Self::new(vec![u32::from_le_bytes(seed)])
+        }
+    }
+
+    /// Asserts that the scheduler reaches at least `fraction` of the optimum on every sample.
+    /// Sample file names contain the optimal score.
+    fn assert_quality(scheduler: &mut dyn Scheduler, fraction: f64) -> anyhow::Result<()> {
+        let report = run_seeded("samples", 0, scheduler, 0)?;
+        assert!(!report.entries().is_empty());
+
+        for entry in report.entries() {
+            // The error is NaN for samples with zero optimum.
+            let error = if entry.error.is_nan() {
+                0.0
+            } else {
+                entry.error
+            };
+            assert!(
+                error <= 100.0 * (1.0 - fraction),
+                "{} reached only {:.2}% of the optimum on {}",
+                scheduler.name(),
+                100.0 - entry.error,
+                entry.name
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn schedulers_should_stay_close_to_optimum() -> anyhow::Result<()> {
+        assert_quality(&mut List, 0.8)?;
+        assert_quality(&mut ListLookahead, 0.8)?;
+        assert_quality(&mut PolynomialTime, 1.0)?;
+        assert_quality(&mut Genetic::new(0, 100), 0.85)?;
+        assert_quality(&mut Tresoldi::new(20, 0), 0.9)?;
+        assert_quality(&mut VariableNeighborhoodSearch::new(20, 0), 0.85)?;
+        assert_quality(&mut SimulatedAnnealing::new(10.0, 0.999, 2000, 0), 0.85)?;
+        assert_quality(&mut TabuSearch::new(50, 7), 0.85)
+    }
+
+    #[test]
+    fn schedulers_should_respect_restrictions() {
+        let task = |time, release, eligible| Task {
+            release,
+            eligible,
+            ..Task::new(time, 1)
+        };
+        let tasks = vec![
+            task(2, 0, Some(vec![1])),
+            task(2, 1, Some(vec![1])),
+            task(1, 3, None),
+            task(1, 0, Some(vec![0])),
+            task(2, 2, None),
+            task(1, 0, Some(vec![0, 1])),
+        ];
+        let conflicts = vec![Conflict::new(0, 3), Conflict::new(2, 4)];
+        let instance = Instance::new(2, 6, tasks, conflicts);
+
+        for scheduler in SCHEDULERS {
+            let mut scheduler = scheduler();
+            if scheduler.supports(&instance) {
+                scheduler.set_seed(0);
+                let schedule = scheduler.schedule(&instance);
+                let result = schedule.verify_detailed();
+                assert_eq!(result, Ok(()), "{} broke a restriction", scheduler.name());
+            }
+        }
+    }
+
+    #[test]
+    fn anytime_schedulers_should_respect_budget() -> anyhow::Result<()> {
+        let budget = std::time::Duration::from_millis(20);
+        let mut schedulers: [Box<dyn Scheduler>; 3] = [
+            Box::new(Genetic::new(0, usize::MAX)),
+            Box::new(Tresoldi::new(usize::MAX, 0)),
+            Box::new(VariableNeighborhoodSearch::new(usize::MAX, 0)),
+        ];
+
+        for entry in std::fs::read_dir("samples")? {
+            let mut reader = std::io::BufReader::new(std::fs::File::open(entry?.path())?);
+            let instance: crate::core::Instance = crate::data::deserialize(&mut reader)?;
+
+            // Unlimited iterations finish only because the budget is respected.
+            for scheduler in &mut schedulers {
+                let schedule = scheduler.schedule_with_budget(&instance, budget);
+                assert!(schedule.verify());
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+use super::intern;
+use crate::cast_u64;
+use crate::core::{Instance, Schedule, Scheduler};
+use std::time::Duration;
+
+/// Runs schedulers created from consecutive seeds and keeps the best schedule.
+///
+/// The factory creates a scheduler for each seed, starting with the base seed.
+/// The scheduler is run at least once.
+pub struct MultiStart<S> {
+    factory: Box<dyn Fn(u64) -> S>,
+    starts: usize,
+    seed: u64,
+    name: &'static str,
+}
+
+impl<S: Scheduler> MultiStart<S> {
+    /// Creates a new multi-start scheduler running `starts` schedulers from the factory.
+    #[must_use]
+    pub fn new(factory: impl Fn(u64) -> S + 'static, starts: usize, seed: u64) -> Self {
+        let inner = factory(seed);
+        Self {
+            name: intern(format!("MultiStart({})", inner.name())),
+            factory: Box::new(factory),
+            starts: starts.max(1),
+            seed,
+        }
+    }
+
+    /// Runs every start with `run` and returns the schedule with the highest score.
+    fn best<'a>(&self, mut run: impl FnMut(&mut S) -> Schedule<'a>) -> Schedule<'a> {
+        let mut seeds = (0..self.starts).map(|start| self.seed.wrapping_add(cast_u64(start)));
+        let mut best = seeds.next().map(|seed| run(&mut (self.factory)(seed)));
+
+        for seed in seeds {
+            let schedule = run(&mut (self.factory)(seed));
+            if best.as_ref().map_or(true, |best| {
+                schedule.calculate_score() > best.calculate_score()
+            }) {
+                best = Some(schedule);
+            }
+        }
+
+        best.unwrap_or_else(|| unreachable!("There is always at least one start"))
+    }
+}
+
+impl<S: Scheduler> Scheduler for MultiStart<S> {
+    fn schedule<'a>(&mut self, instance: &'a Instance) -> Schedule<'a> {
+        self.best(|scheduler| scheduler.schedule(instance))
+    }
+
+    /// The budget is split evenly between the starts.
+    fn schedule_with_budget<'a>(
+        &mut self,
+        instance: &'a Instance,
+        budget: Duration,
+    ) -> Schedule<'a> {
+        let budget = budget / u32::try_from(self.starts).unwrap_or(u32::MAX);
+        self.best(|scheduler| scheduler.schedule_with_budget(instance, budget))
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    fn is_randomized(&self) -> bool {
+        (self.factory)(self.seed).is_randomized()
+    }
+
+    fn supports(&self, instance: &Instance) -> bool {
+        (self.factory)(self.seed).supports(instance)
+    }
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algo::{PolynomialTime, Tresoldi};
+    use crate::data::samples;
+
+    #[test]
+    fn test_multi_start() {
+        let mut scheduler = MultiStart::new(|seed| Tresoldi::new(5, seed), 3, 0);
+        assert!(samples(0, &mut scheduler).is_ok());
+    }
+
+    #[test]
+    fn multi_start_should_forward_inner_properties() {
+        let task = |time| crate::core::Task::new(time, 1);
+        let instance = Instance::new_no_conflict(2, 5, vec![task(1), task(2)]);
+
+        let scheduler = MultiStart::new(|seed| Tresoldi::new(5, seed), 3, 0);
+        assert_eq!(scheduler.name(), "MultiStart(Tresoldi)");
+        assert!(scheduler.supports(&instance));
+
+        let scheduler = MultiStart::new(|_| PolynomialTime, 2, 0);
+        assert_eq!(scheduler.name(), "MultiStart(PolynomialTime)");
+        assert!(!scheduler.supports(&instance));
+    }
+
+    #[test]
+    fn multi_start_should_keep_best_schedule() -> anyhow::Result<()> {
+        let file = std::fs::File::open("samples/3_50_0.in")?;
+        let instance: Instance = crate::data::deserialize(&mut std::io::BufReader::new(file))?;
+
+        let best = MultiStart::new(|seed| Tresoldi::new(1, seed), 4, 7)
+            .schedule(&instance)
+            .calculate_score();
+        for seed in 7..11 {
+            let score = Tresoldi::new(1, seed).schedule(&instance).calculate_score();
+            assert!(best >= score);
+        }
+
+        Ok(())
+    }
+}
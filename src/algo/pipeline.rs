@@ -0,0 +1,91 @@
+use super::intern;
+use crate::core::{Improver, Instance, Schedule, ScheduleBuilder, Scheduler};
+use std::time::Duration;
+
+/// Runs the first scheduler and improves its schedule with the second one.
+pub struct Pipeline<A, B> {
+    first: A,
+    second: B,
+    name: &'static str,
+}
+
+impl<A: Scheduler, B: Improver> Pipeline<A, B> {
+    /// Creates a new pipeline of two schedulers.
+    #[must_use]
+    pub fn new(first: A, second: B) -> Self {
+        let name = intern(format!("Pipeline({}, {})", first.name(), second.name()));
+        Self {
+            first,
+            second,
+            name,
+        }
+    }
+}
+
+impl<A: Scheduler, B: Improver> Scheduler for Pipeline<A, B> {
+    fn schedule<'a>(&mut self, instance: &'a Instance) -> Schedule<'a> {
+        let schedule = self.first.schedule(instance);
+        let builder = ScheduleBuilder::from_schedule(instance, &schedule);
+        self.second.improve(builder)
+    }
+
+    /// The first scheduler runs to completion, the budget is given to the second one.
+    fn schedule_with_budget<'a>(
+        &mut self,
+        instance: &'a Instance,
+        budget: Duration,
+    ) -> Schedule<'a> {
+        let schedule = self.first.schedule(instance);
+        let builder = ScheduleBuilder::from_schedule(instance, &schedule);
+        self.second.improve_with_budget(builder, budget)
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.first.set_seed(seed);
+        self.second.set_seed(seed);
+    }
+
+    fn is_randomized(&self) -> bool {
+        self.first.is_randomized() || self.second.is_randomized()
+    }
+
+    fn supports(&self, instance: &Instance) -> bool {
+        self.first.supports(instance) && self.second.supports(instance)
+    }
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algo::{List, Tresoldi, VariableNeighborhoodSearch};
+    use crate::data::samples;
+
+    #[test]
+    fn test_pipeline() {
+        let mut pipeline = Pipeline::new(List, VariableNeighborhoodSearch::new(5, 0));
+        assert_eq!(pipeline.name(), "Pipeline(List, VNS)");
+        assert!(samples(0, &mut pipeline).is_ok());
+    }
+
+    #[test]
+    fn pipeline_should_not_worsen_first_schedule() -> anyhow::Result<()> {
+        for entry in std::fs::read_dir("samples")? {
+            let file = std::fs::File::open(entry?.path())?;
+            let instance: Instance = crate::data::deserialize(&mut std::io::BufReader::new(file))?;
+
+            let score = Tresoldi::new(2, 0).schedule(&instance).calculate_score();
+            let mut pipeline =
+                Pipeline::new(Tresoldi::new(2, 0), VariableNeighborhoodSearch::new(2, 0));
+            let schedule = pipeline.schedule(&instance);
+
+            assert!(schedule.verify());
+            assert!(schedule.calculate_score() >= score);
+        }
+
+        Ok(())
+    }
+}
@@ -1,12 +1,17 @@
-use super::matching::{gabow_algo, Graph};
-use crate::cast_usize;
+use super::matching::{gabow_algo_with_weight, Graph};
 use crate::core::{Instance, Schedule, ScheduleInfo, Scheduler};
+use crate::{cast_u64, cast_usize};
 use anyhow::anyhow;
+use rayon::prelude::*;
 
 /// Polynomial time algorithm for the problem.
+///
 /// It is based on the maximum weighted matching in general graphs.
 /// It solves the problem in `O(n^3)` time complexity using Gabow's algorithm.
 /// For more than two machines, it founds an approximate solution.
+/// An exact flow formulation does not exist there, as tasks sharing a slot must be pairwise
+/// non-conflicting, which a flow cannot express. Already for three machines, deciding whether
+/// all tasks fit is the NP-hard partition of the complement graph into triangles.
 ///
 /// # Panics
 /// - If the instance tasks have different processing times.
@@ -16,25 +21,60 @@ pub struct PolynomialTime;
 impl PolynomialTime {
     /// Estimate the upper bound of the instance.
     ///
+    /// The matching gives the optimum for two machines, which also bounds a single machine.
+    /// Any two of `m` machines keep at least `2 / m` of the score, so for more machines the
+    /// optimum is scaled by `m / 2`.
+    ///
     /// # Errors
     /// - If the instance tasks have different processing times.
     pub fn estimate_upper_bound(&mut self, instance: &Instance) -> anyhow::Result<u64> {
-        if instance.tasks.is_empty() {
-            return Ok(0);
-        }
+        self.estimate_with_schedule(instance)
+            .map(|(bound, _)| bound)
+    }
 
-        let time = instance.tasks[0].time;
-        if instance.tasks.iter().any(|task| task.time != time) {
+    /// Estimate the upper bound of the instance like [`Self::estimate_upper_bound`] and return
+    /// the schedule of the matching it is computed from.
+    /// The schedule uses two machines, so it is valid only if the instance is supported.
+    ///
+    /// # Errors
+    /// - If the instance tasks have different processing times.
+    pub fn estimate_with_schedule<'a>(
+        &mut self,
+        instance: &'a Instance,
+    ) -> anyhow::Result<(u64, Schedule<'a>)> {
+        if !instance.has_equal_times() {
             return Err(anyhow!("All tasks must have the same processing time"));
         }
 
-        let schedule = self.schedule(instance);
-        let score = schedule.calculate_score();
+        let (schedule, score) = polynomial_time_with_score(instance);
+        if instance.processors < 2 {
+            return Ok((score, schedule));
+        }
+
+        Ok((
+            score.saturating_mul(cast_u64(instance.processors)) / 2,
+            schedule,
+        ))
+    }
 
-        Ok(score * instance.processors as u64 / 2)
+    /// Returns whether the schedule of the instance is provably optimal.
+    ///
+    /// It holds for at most two machines and tasks with the same processing time,
+    /// no release times and no machine restrictions. Otherwise the result is an approximation.
+    #[must_use]
+    pub fn is_exact_for(&self, instance: &Instance) -> bool {
+        instance.processors <= 2 && is_unrestricted(instance)
     }
 }
 
+/// Returns whether the tasks have the same processing time, no release times and no machine
+/// restrictions, which the matching assumes.
+fn is_unrestricted(instance: &Instance) -> bool {
+    instance.has_equal_times()
+        && !instance.has_release_times()
+        && !instance.has_eligibility_restrictions()
+}
+
 impl Scheduler for PolynomialTime {
     fn schedule<'a>(&mut self, instance: &'a Instance) -> Schedule<'a> {
         polynomial_time(instance)
@@ -44,6 +84,11 @@ impl Scheduler for PolynomialTime {
         false
     }
 
+    /// Tasks are paired on two machines, so a single machine is not supported.
+    fn supports(&self, instance: &Instance) -> bool {
+        instance.processors >= 2 && is_unrestricted(instance)
+    }
+
     fn name(&self) -> &'static str {
         "PolynomialTime"
     }
@@ -53,9 +98,14 @@ impl Scheduler for PolynomialTime {
 #[linkme::distributed_slice(super::SCHEDULERS)]
 static INSTANCE: fn() -> Box<dyn Scheduler> = || Box::new(PolynomialTime);
 
-fn polynomial_time(instance: &Instance) -> Schedule {
+fn polynomial_time(instance: &Instance) -> Schedule<'_> {
+    polynomial_time_with_score(instance).0
+}
+
+/// Returns the schedule and its score, which is the weight of the matching.
+fn polynomial_time_with_score(instance: &Instance) -> (Schedule<'_>, u64) {
     if instance.tasks.is_empty() {
-        return Schedule::new(instance);
+        return (Schedule::new(instance), 0);
     }
 
     let time = instance.tasks[0].time;
@@ -65,32 +115,11 @@ fn polynomial_time(instance: &Instance) -> Schedule {
         "All tasks must have the same processing time"
     );
 
-    let mut graph = Graph::default();
-
-    for (first, task) in instance.tasks.iter().enumerate() {
-        for (second, other) in instance.tasks.iter().enumerate() {
-            if second > first && !instance.graph.are_conflicted(first, second) {
-                graph.add_edge(first, second, task.weight + other.weight);
-            }
-        }
-    }
-
+    let graph = build_graph(instance, time);
     let n = instance.tasks.len();
-    let d = instance.deadline / time;
-
-    for (i, task) in instance.tasks.iter().enumerate() {
-        graph.add_edge(i, n + i, task.weight);
-    }
 
-    if n > cast_usize(d) {
-        for q in 0..(n - cast_usize(d)) * 2 {
-            for i in 0..n * 2 {
-                graph.add_edge(i, n * 2 + q, 0);
-            }
-        }
-    }
-
-    let Some(matching): Option<Vec<_>> = gabow_algo(&graph, true).into_iter().collect() else {
+    let (matching, weight) = gabow_algo_with_weight(&graph, true);
+    let Some(matching): Option<Vec<_>> = matching.into_iter().collect() else {
         unreachable!("Algorithm should always return a perfect matching");
     };
 
@@ -107,7 +136,46 @@ fn polynomial_time(instance: &Instance) -> Schedule {
         }
     }
 
-    schedule
+    let score = u64::try_from(weight).unwrap_or_else(|_| unreachable!("Weights are positive"));
+    (schedule, score)
+}
+
+/// Builds the graph whose perfect matching gives the schedule.
+///
+/// Vertices `0..n` are tasks, connected when they do not conflict, so they can share a time slot.
+/// Vertex `n + i` lets task `i` use a slot alone. The remaining vertices are dummy
+/// vertices that absorb tasks and slots not used before the deadline.
+fn build_graph(instance: &Instance, time: u64) -> Graph {
+    let n = instance.tasks.len();
+    let d = instance.deadline / time;
+    let dummies = n.saturating_sub(cast_usize(d)) * 2;
+
+    // Conflict lookups dominate for large instances, the collected edges keep their order.
+    let tasks = &instance.tasks;
+    let edges: Vec<_> = (0..n)
+        .into_par_iter()
+        .flat_map_iter(|first| {
+            (first + 1..n)
+                .filter(move |&second| !instance.graph.are_conflicted(first, second))
+                .map(move |second| {
+                    let weight = tasks[first].weight + tasks[second].weight;
+                    (first, second, i128::from(weight))
+                })
+        })
+        .collect();
+    let mut graph = Graph::from_edges(n * 2 + dummies, edges);
+
+    for (i, task) in instance.tasks.iter().enumerate() {
+        graph.add_edge(i, n + i, task.weight);
+    }
+
+    for q in 0..dummies {
+        for i in 0..n * 2 {
+            graph.add_edge(i, n * 2 + q, 0);
+        }
+    }
+
+    graph
 }
 
 #[cfg(test)]
@@ -121,10 +189,101 @@ mod test {
         assert!(samples(2, &mut PolynomialTime).is_ok());
     }
 
+    #[test]
+    fn graph_should_connect_non_conflicting_tasks() {
+        let task = |weight| Task::new(1, weight);
+        let conflicts = vec![crate::core::Conflict::new(0, 1)];
+        let instance = Instance::new(2, 3, vec![task(1), task(2), task(3)], conflicts);
+
+        let graph = build_graph(&instance, 1);
+        let expected = [(0, 2, 4), (1, 2, 5), (0, 3, 1), (1, 4, 2), (2, 5, 3)];
+        assert_eq!(graph.edges(), &expected);
+        assert_eq!(graph.neighbors(0).len(), 2);
+    }
+
+    #[test]
+    fn matching_weight_should_equal_score() -> anyhow::Result<()> {
+        for name in ["2_12_0_unit.in", "2_60_0_unit.in"] {
+            let mut reader =
+                std::io::BufReader::new(std::fs::File::open(format!("samples/{name}"))?);
+            let instance: Instance = crate::data::deserialize(&mut reader)?;
+
+            let (schedule, score) = polynomial_time_with_score(&instance);
+            assert_eq!(schedule.calculate_score(), score);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn upper_bound_should_hold_for_single_machine() -> anyhow::Result<()> {
+        let task = |weight| Task::new(1, weight);
+        let tasks: Vec<_> = (1..=4).map(task).collect();
+        let instance = Instance::new_no_conflict(1, 3, tasks);
+
+        let score = crate::algo::List.schedule(&instance).calculate_score();
+        assert_eq!(score, 9);
+        assert_eq!(PolynomialTime.estimate_upper_bound(&instance)?, 10);
+        Ok(())
+    }
+
+    #[test]
+    fn estimate_should_come_with_its_schedule() -> anyhow::Result<()> {
+        for name in ["2_12_0_unit.in", "2_60_0_unit.in"] {
+            let mut reader =
+                std::io::BufReader::new(std::fs::File::open(format!("samples/{name}"))?);
+            let instance: Instance = crate::data::deserialize(&mut reader)?;
+
+            let (estimate, schedule) = PolynomialTime.estimate_with_schedule(&instance)?;
+            assert_eq!(estimate, PolynomialTime.estimate_upper_bound(&instance)?);
+            assert_eq!(
+                schedule.calculate_score(),
+                PolynomialTime.schedule(&instance).calculate_score()
+            );
+            assert!(schedule.calculate_score() <= estimate);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn exactness_should_depend_on_instance() {
+        let task = |time, release| Task {
+            release,
+            ..Task::new(time, 1)
+        };
+        let exact = |processors, tasks| {
+            PolynomialTime.is_exact_for(&Instance::new_no_conflict(processors, 4, tasks))
+        };
+
+        assert!(exact(2, vec![task(2, 0), task(2, 0)]));
+        assert!(exact(1, vec![]));
+        assert!(!exact(3, vec![task(2, 0), task(2, 0)]));
+        assert!(!exact(2, vec![task(2, 0), task(1, 0)]));
+        assert!(!exact(2, vec![task(2, 0), task(2, 1)]));
+    }
+
+    #[test]
+    fn supports_should_reject_restricted_instances() {
+        let task = |release, eligible| Task {
+            release,
+            eligible,
+            ..Task::new(1, 1)
+        };
+        let supports = |processors, tasks| {
+            PolynomialTime.supports(&Instance::new_no_conflict(processors, 4, tasks))
+        };
+
+        assert!(supports(2, vec![task(0, None), task(0, None)]));
+        assert!(supports(3, vec![task(0, None), task(0, None)]));
+        assert!(!supports(1, vec![task(0, None), task(0, None)]));
+        assert!(!supports(2, vec![task(0, None), task(1, None)]));
+        assert!(!supports(2, vec![task(0, None), task(0, Some(vec![1]))]));
+    }
+
     #[test]
     #[should_panic(expected = "All tasks must have the same processing time")]
     fn test_same_time() {
-        let tasks = vec![Task { weight: 1, time: 1 }, Task { weight: 1, time: 2 }];
+        let tasks = vec![Task::new(1, 1), Task::new(2, 1)];
         let _ = polynomial_time(&Instance::new_no_conflict(2, 3, tasks));
     }
 }
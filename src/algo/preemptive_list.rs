@@ -0,0 +1,150 @@
+use crate::core::{
+    weighted_task_comparator, Instance, PreemptiveSchedule, PreemptiveScheduleInfo, TaskWithId,
+};
+
+/// List scheduling algorithm for the preemptive model.
+///
+/// Tasks are taken in the same order as by [`List`](super::List) and split over the idle
+/// gaps of the eligible machine on which they finish first. Tasks which do not fit before
+/// the deadline are left unscheduled.
+#[derive(Clone, Debug, Default)]
+pub struct PreemptiveList;
+
+impl PreemptiveList {
+    /// Schedules the tasks of the given instance.
+    #[must_use]
+    pub fn schedule<'a>(&self, instance: &'a Instance) -> PreemptiveSchedule<'a> {
+        let mut schedule = PreemptiveSchedule::new(instance);
+        // Busy `(start, end)` intervals of every machine and every task.
+        let mut machines = vec![Vec::new(); instance.processors];
+        let mut busy = vec![Vec::new(); instance.tasks.len()];
+
+        let mut tasks: Vec<TaskWithId> = instance.tasks.iter().cloned().enumerate().collect();
+        tasks.sort_unstable_by(weighted_task_comparator);
+
+        for (id, task) in tasks {
+            let conflicts = instance.graph.conflicts(id).iter();
+            let conflicts: Vec<_> = conflicts.flat_map(|&other| &busy[other]).copied().collect();
+
+            let mut best: Option<(usize, Vec<(u64, u64)>)> = None;
+            for (machine, intervals) in machines.iter().enumerate() {
+                if !task.is_eligible(machine) {
+                    continue;
+                }
+                let blocked = [intervals.as_slice(), &conflicts].concat();
+                let Some(segments) = fill_gaps(blocked, task.release, instance.deadline, task.time)
+                else {
+                    continue;
+                };
+                if best
+                    .as_ref()
+                    .map_or(true, |(_, best)| end(best) > end(&segments))
+                {
+                    best = Some((machine, segments));
+                }
+            }
+
+            if let Some((machine, segments)) = best {
+                let intervals = segments
+                    .iter()
+                    .map(|&(start, length)| (start, start + length));
+                busy[id].extend(intervals.clone());
+                machines[machine].extend(intervals);
+                schedule.schedule(id, PreemptiveScheduleInfo::new(machine, segments));
+            }
+        }
+
+        schedule
+    }
+}
+
+/// Returns the time at which the last of ordered segments finishes.
+fn end(segments: &[(u64, u64)]) -> u64 {
+    segments.last().map_or(0, |&(start, length)| start + length)
+}
+
+/// Splits `time` units of work over the gaps between `blocked` intervals.
+/// Returns `(start, length)` segments between `release` and `deadline`, if the work fits.
+fn fill_gaps(
+    mut blocked: Vec<(u64, u64)>,
+    release: u64,
+    deadline: u64,
+    time: u64,
+) -> Option<Vec<(u64, u64)>> {
+    blocked.sort_unstable();
+    blocked.push((deadline, deadline));
+
+    let mut segments = Vec::new();
+    let mut free = release;
+    let mut left = time;
+
+    for (start, end) in blocked {
+        if left == 0 {
+            break;
+        }
+        let start = start.min(deadline);
+        if start > free {
+            let length = (start - free).min(left);
+            segments.push((free, length));
+            left -= length;
+        }
+        free = free.max(end);
+    }
+
+    (left == 0).then_some(segments)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::core::{Conflict, Task};
+
+    #[test]
+    fn preemptive_list_should_fill_gaps() {
+        let tasks = vec![
+            Task {
+                release: 2,
+                ..Task::new(1, 10)
+            },
+            Task::new(4, 1),
+        ];
+        let instance = Instance::new_no_conflict(1, 5, tasks);
+        let schedule = PreemptiveList.schedule(&instance);
+
+        assert!(schedule.verify());
+        assert_eq!(schedule.calculate_score(), 11);
+        assert_eq!(
+            schedule.get_schedule(1),
+            Some(&PreemptiveScheduleInfo::new(0, vec![(0, 2), (3, 2)]))
+        );
+    }
+
+    #[test]
+    fn preemptive_list_should_respect_conflicts() {
+        let task = Task::new(2, 1);
+        let conflicts = vec![Conflict::new(0, 1), Conflict::new(1, 2)];
+        let instance = Instance::new(3, 5, vec![task; 3], conflicts);
+        let schedule = PreemptiveList.schedule(&instance);
+
+        assert!(schedule.verify());
+        assert_eq!(schedule.calculate_score(), 3);
+    }
+
+    #[test]
+    fn preemptive_list_should_use_eligible_machines() {
+        let task = |eligible| Task {
+            eligible,
+            ..Task::new(2, 1)
+        };
+        let tasks = vec![task(None), task(Some(vec![0])), task(Some(vec![1]))];
+        let instance = Instance::new_no_conflict(2, 4, tasks);
+        let schedule = PreemptiveList.schedule(&instance);
+
+        assert!(schedule.verify());
+        assert_eq!(schedule.calculate_score(), 3);
+        for (id, task) in instance.tasks.iter().enumerate() {
+            let info = schedule.get_schedule(id);
+            assert!(info.is_some_and(|info| task.is_eligible(info.processor)));
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use super::vns::relocate_task;
+use crate::core::{Instance, Schedule, ScheduleBuilder, Scheduler};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Swaps positions of two tasks.
+/// If one of the tasks is tardy, it takes the place of the other one.
+fn swap_tasks(schedule: &mut ScheduleBuilder, first: usize, second: usize) {
+    let first_machine = schedule.get_schedule(first).map(|info| info.processor);
+    let second_machine = schedule.get_schedule(second).map(|info| info.processor);
+
+    schedule.reorganize_schedule(|machines, tardy_tasks| {
+        let position = |machines: &[Vec<usize>], machine: usize, task: usize| {
+            let position = machines[machine].iter().position(|&id| id == task);
+            position.unwrap_or_else(|| unreachable!("Scheduled task must be on its machine"))
+        };
+
+        match (first_machine, second_machine) {
+            (Some(first_machine), Some(second_machine)) => {
+                let i = position(machines, first_machine, first);
+                let j = position(machines, second_machine, second);
+                machines[first_machine][i] = second;
+                machines[second_machine][j] = first;
+
+                if first_machine == second_machine {
+                    (vec![(first_machine, i.min(j))], vec![])
+                } else {
+                    (vec![(first_machine, i), (second_machine, j)], vec![])
+                }
+            }
+            (Some(machine), None) | (None, Some(machine)) => {
+                let (scheduled, tardy) = match first_machine {
+                    Some(_) => (first, second),
+                    None => (second, first),
+                };
+
+                let i = position(machines, machine, scheduled);
+                machines[machine][i] = tardy;
+                tardy_tasks.retain(|&id| id != tardy);
+                tardy_tasks.push(scheduled);
+
+                (vec![(machine, i)], vec![scheduled])
+            }
+            (None, None) => (vec![], vec![]),
+        }
+    });
+}
+
+/// Performs the Simulated Annealing algorithm.
+/// Starts from the list schedule and applies one random move per iteration.
+/// Worse schedules are accepted with probability `exp(delta / temperature)`.
+#[derive(Clone, Debug)]
+pub struct SimulatedAnnealing {
+    temperature: f64,
+    cooling: f64,
+    iterations: usize,
+    rng: StdRng,
+}
+
+impl SimulatedAnnealing {
+    /// Creates a new instance of `SimulatedAnnealing`.
+    /// The temperature is multiplied by `cooling` after every iteration.
+    #[must_use]
+    pub fn new(temperature: f64, cooling: f64, iterations: usize, seed: u64) -> Self {
+        Self {
+            temperature,
+            cooling,
+            iterations,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Default for SimulatedAnnealing {
+    fn default() -> Self {
+        Self {
+            temperature: 10.0,
+            cooling: 0.999,
+            iterations: 5000,
+            rng: StdRng::from_rng(rand::thread_rng()).unwrap_or_else(|_| StdRng::seed_from_u64(0)),
+        }
+    }
+}
+
+impl Scheduler for SimulatedAnnealing {
+    fn schedule<'a>(&mut self, instance: &'a Instance) -> Schedule<'a> {
+        if instance.tasks.is_empty() {
+            return Schedule::new(instance);
+        }
+
+        let mut schedule = super::list::schedule(instance);
+        let mut score = schedule.calculate_score();
+        let mut best_schedule = schedule.clone();
+        let mut best_score = score;
+        let mut temperature = self.temperature;
+
+        for _ in 0..self.iterations {
+            let mut new_schedule = schedule.clone();
+            let task = self.rng.gen_range(0..instance.tasks.len());
+
+            if self.rng.gen_bool(0.5) {
+                relocate_task(&mut new_schedule, &mut self.rng, task);
+            } else {
+                let other = self.rng.gen_range(0..instance.tasks.len());
+                swap_tasks(&mut new_schedule, task, other);
+            }
+
+            let new_score = new_schedule.calculate_score();
+
+            #[allow(clippy::cast_precision_loss)]
+            let delta = new_score as f64 - score as f64;
+
+            if delta >= 0.0 || self.rng.gen::<f64>() < (delta / temperature).exp() {
+                schedule = new_schedule;
+                score = new_score;
+
+                if score > best_score {
+                    best_score = score;
+                    best_schedule = schedule.clone();
+                }
+            }
+
+            temperature *= self.cooling;
+        }
+
+        best_schedule.into()
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    fn is_randomized(&self) -> bool {
+        true
+    }
+
+    fn name(&self) -> &'static str {
+        "SA"
+    }
+}
+
+#[allow(unsafe_code)]
+#[linkme::distributed_slice(super::SCHEDULERS)]
+static INSTANCE: fn() -> Box<dyn Scheduler> = || Box::new(SimulatedAnnealing::default());
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::data::samples;
+
+    #[test]
+    fn test_sa() {
+        assert!(samples(0, &mut SimulatedAnnealing::new(10.0, 0.99, 500, 0)).is_ok());
+    }
+}
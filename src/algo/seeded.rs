@@ -0,0 +1,117 @@
+use super::{intern, Tresoldi, VariableNeighborhoodSearch};
+use crate::core::{Decision, Instance, Schedule, Scheduler};
+use std::time::Duration;
+
+/// Runs a randomized scheduler reseeded with a fixed seed before every instance.
+///
+/// Results do not depend on earlier runs or on the machine, so benchmark numbers are
+/// reproducible. The scheduler is named after the inner one with a `-seeded` suffix.
+pub struct Seeded<S> {
+    inner: S,
+    seed: u64,
+    name: &'static str,
+}
+
+impl<S: Scheduler> Seeded<S> {
+    /// Creates a new scheduler running `inner` with the given seed.
+    #[must_use]
+    pub fn new(inner: S, seed: u64) -> Self {
+        let name = intern(format!("{}-seeded", inner.name()));
+        Self { inner, seed, name }
+    }
+}
+
+impl<S: Scheduler> Scheduler for Seeded<S> {
+    fn schedule<'a>(&mut self, instance: &'a Instance) -> Schedule<'a> {
+        self.inner.set_seed(self.seed);
+        self.inner.schedule(instance)
+    }
+
+    fn try_schedule<'a>(&mut self, instance: &'a Instance) -> anyhow::Result<Schedule<'a>> {
+        self.inner.set_seed(self.seed);
+        self.inner.try_schedule(instance)
+    }
+
+    fn schedule_with_budget<'a>(
+        &mut self,
+        instance: &'a Instance,
+        budget: Duration,
+    ) -> Schedule<'a> {
+        self.inner.set_seed(self.seed);
+        self.inner.schedule_with_budget(instance, budget)
+    }
+
+    fn try_schedule_with_budget<'a>(
+        &mut self,
+        instance: &'a Instance,
+        budget: Duration,
+    ) -> anyhow::Result<Schedule<'a>> {
+        self.inner.set_seed(self.seed);
+        self.inner.try_schedule_with_budget(instance, budget)
+    }
+
+    fn schedule_traced<'a>(&mut self, instance: &'a Instance) -> (Schedule<'a>, Vec<Decision>) {
+        self.inner.set_seed(self.seed);
+        self.inner.schedule_traced(instance)
+    }
+
+    fn try_schedule_traced<'a>(
+        &mut self,
+        instance: &'a Instance,
+    ) -> anyhow::Result<(Schedule<'a>, Vec<Decision>)> {
+        self.inner.set_seed(self.seed);
+        self.inner.try_schedule_traced(instance)
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    fn is_randomized(&self) -> bool {
+        self.inner.is_randomized()
+    }
+
+    fn supports(&self, instance: &Instance) -> bool {
+        self.inner.supports(instance)
+    }
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+#[allow(unsafe_code)]
+#[linkme::distributed_slice(super::SCHEDULERS)]
+static VNS: fn() -> Box<dyn Scheduler> =
+    || Box::new(Seeded::new(VariableNeighborhoodSearch::default(), 0));
+
+#[allow(unsafe_code)]
+#[linkme::distributed_slice(super::SCHEDULERS)]
+static TRESOLDI: fn() -> Box<dyn Scheduler> = || Box::new(Seeded::new(Tresoldi::default(), 0));
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::data::{deserialize, samples};
+
+    #[test]
+    fn test_seeded() {
+        let mut seeded = Seeded::new(VariableNeighborhoodSearch::new(10, 0), 0);
+        assert_eq!(seeded.name(), "VNS-seeded");
+        assert!(samples(0, &mut seeded).is_ok());
+    }
+
+    #[test]
+    fn seeded_runs_should_repeat() -> anyhow::Result<()> {
+        let file = std::fs::File::open("samples/3_301_0.in")?;
+        let instance: Instance = deserialize(&mut std::io::BufReader::new(file))?;
+
+        let mut first = Seeded::new(Tresoldi::new(5, 1), 7);
+        let mut second = Seeded::new(Tresoldi::new(5, 2), 7);
+        let expected = first.schedule(&instance);
+
+        assert_eq!(first.schedule(&instance), expected);
+        assert_eq!(second.schedule(&instance), expected);
+        Ok(())
+    }
+}
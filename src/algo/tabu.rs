@@ -0,0 +1,109 @@
+use super::vns::NEIGHBORHOODS;
+use crate::core::{Instance, Schedule, Scheduler};
+use std::collections::VecDeque;
+
+/// Performs the Tabu Search algorithm over the VNS neighborhoods.
+///
+/// Every step moves to the best candidate that does not change a tabu `(machine, index)`
+/// position, even if it is worse than the current schedule.
+/// Positions changed by a move stay tabu for `tenure` steps,
+/// unless the move leads to a new best schedule (aspiration).
+#[derive(Clone, Debug)]
+pub struct TabuSearch {
+    steps: usize,
+    tenure: usize,
+}
+
+impl TabuSearch {
+    /// Creates a new instance of `TabuSearch`.
+    #[must_use]
+    pub const fn new(steps: usize, tenure: usize) -> Self {
+        Self { steps, tenure }
+    }
+}
+
+impl Default for TabuSearch {
+    fn default() -> Self {
+        Self::new(100, 7)
+    }
+}
+
+impl Scheduler for TabuSearch {
+    fn schedule<'a>(&mut self, instance: &'a Instance) -> Schedule<'a> {
+        let mut schedule = super::list::schedule(instance);
+        let mut best_schedule = schedule.clone();
+        let mut best_score = schedule.calculate_score();
+
+        // Every step adds at most two positions, so the list never exceeds `2 * tenure`.
+        let mut tabu: VecDeque<((usize, usize), usize)> = VecDeque::with_capacity(2 * self.tenure);
+
+        for step in 0..self.steps {
+            while tabu.front().is_some_and(|&(_, expires)| expires <= step) {
+                tabu.pop_front();
+            }
+
+            let mut best_candidate = None;
+
+            for factory in NEIGHBORHOODS {
+                let candidates = factory(&schedule).map(|next| next.apply(&schedule));
+                for (moved, candidate) in candidates {
+                    let score = candidate.calculate_score();
+                    let is_tabu = moved
+                        .iter()
+                        .any(|position| tabu.iter().any(|(tabu, _)| tabu == position));
+
+                    if (is_tabu && score <= best_score)
+                        || best_candidate
+                            .as_ref()
+                            .is_some_and(|&(best, _, _)| score <= best)
+                    {
+                        continue;
+                    }
+
+                    best_candidate = Some((score, moved, candidate));
+                }
+            }
+
+            let Some((score, moved, candidate)) = best_candidate else {
+                break;
+            };
+
+            for position in moved {
+                if tabu.len() == 2 * self.tenure {
+                    tabu.pop_front();
+                }
+                if self.tenure > 0 {
+                    tabu.push_back((position, step + self.tenure));
+                }
+            }
+
+            schedule = candidate;
+
+            if score > best_score {
+                best_score = score;
+                best_schedule = schedule.clone();
+            }
+        }
+
+        best_schedule.into()
+    }
+
+    fn name(&self) -> &'static str {
+        "Tabu"
+    }
+}
+
+#[allow(unsafe_code)]
+#[linkme::distributed_slice(super::SCHEDULERS)]
+static INSTANCE: fn() -> Box<dyn Scheduler> = || Box::new(TabuSearch::default());
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::data::samples;
+
+    #[test]
+    fn test_tabu() {
+        assert!(samples(0, &mut TabuSearch::new(20, 5)).is_ok());
+    }
+}
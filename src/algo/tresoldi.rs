@@ -2,7 +2,8 @@ use crate::core::{Instance, Schedule, ScheduleInfo, Scheduler, TaskWithId};
 use crate::{cast_u64, cast_usize};
 use ahash::HashMap;
 use rand::prelude::{SliceRandom, StdRng};
-use rand::{Rng, SeedableRng};
+use rand::{Rng, RngCore, SeedableRng};
+use std::time::{Duration, Instant};
 
 struct ScheduleBuilder<'a> {
     instance: &'a Instance,
@@ -46,7 +47,7 @@ impl<'a> ScheduleBuilder<'a> {
                             && self.check_time(time, machine, task)
                             && self.check_conflicts(task, time)
                         {
-                            for instant in time..(time + task.1.time) {
+                            for instant in time..task.1.completion(time) {
                                 self.matrix[cast_usize(instant)][machine] = Some(task.0);
                             }
                             self.score += task.1.weight;
@@ -64,24 +65,28 @@ impl<'a> ScheduleBuilder<'a> {
 
     fn local_search(&mut self) -> bool {
         let mut change = false;
+        // Tasks are taken out, as the loop updates the rest of the state.
+        let tasks = std::mem::take(&mut self.tasks);
 
-        for &old in &self.tasks {
+        for old in &tasks {
             if let Some((machine, time)) = self.scheduled.get(&old.0).copied() {
-                for &task in &self.tasks {
+                for task in &tasks {
                     if self.scheduled.contains_key(&task.0) {
                         continue;
                     }
 
                     if (task.1.weight > old.1.weight
                         || (task.1.weight == old.1.weight && task.1.time < old.1.time))
-                        && self.check_hole(&old, &task)
-                        && self.check_conflicts(&task, time)
+                        && task.1.release <= time
+                        && task.1.is_eligible(machine)
+                        && self.check_hole(old, task)
+                        && self.check_conflicts(task, time)
                     {
-                        for instant in time..(time + old.1.time) {
+                        for instant in time..old.1.completion(time) {
                             self.matrix[cast_usize(instant)][machine] = None;
                         }
 
-                        for instant in time..(time + task.1.time) {
+                        for instant in time..task.1.completion(time) {
                             self.matrix[cast_usize(instant)][machine] = Some(task.0);
                         }
 
@@ -95,25 +100,31 @@ impl<'a> ScheduleBuilder<'a> {
             }
         }
 
+        self.tasks = tasks;
         change
     }
 
     fn compact(&mut self) -> bool {
         let mut change = false;
+        let tasks = std::mem::take(&mut self.tasks);
 
-        for &task in &self.tasks {
+        for task in &tasks {
             if let Some((machine, time)) = self.scheduled.get(&task.0).copied() {
                 let mut best_machine = machine;
                 let mut best_time = time;
 
-                for machine in 0..self.matrix[0].len() {
+                let eligible = (0..self.matrix[0].len()).filter(|&m| task.1.is_eligible(m));
+                for machine in eligible {
                     let mut free = 0;
 
-                    for time in 0..best_time + task.1.time - 1 {
+                    for time in 0..task.1.completion(best_time) - 1 {
                         if self.matrix[cast_usize(time)][machine].is_none() {
                             free += 1;
 
-                            if free == task.1.time && self.check_conflicts(&task, time + 1 - free) {
+                            if free == task.1.time
+                                && time + 1 - free >= task.1.release
+                                && self.check_conflicts(task, time + 1 - free)
+                            {
                                 best_time = time - free + 1;
                                 best_machine = machine;
                             }
@@ -124,11 +135,11 @@ impl<'a> ScheduleBuilder<'a> {
                 }
 
                 if best_time < time {
-                    for instant in time..(time + task.1.time) {
+                    for instant in time..task.1.completion(time) {
                         self.matrix[cast_usize(instant)][machine] = None;
                     }
 
-                    for instant in best_time..(best_time + task.1.time) {
+                    for instant in best_time..task.1.completion(best_time) {
                         self.matrix[cast_usize(instant)][best_machine] = Some(task.0);
                     }
 
@@ -138,15 +149,19 @@ impl<'a> ScheduleBuilder<'a> {
             }
         }
 
+        self.tasks = tasks;
         change
     }
 
     fn check_time(&self, time: u64, machine: usize, task: &TaskWithId) -> bool {
-        if time + task.1.time > cast_u64(self.matrix.len()) {
+        if time < task.1.release
+            || !task.1.is_eligible(machine)
+            || !task.1.fits_before(time, cast_u64(self.matrix.len()))
+        {
             return false;
         }
 
-        for instant in time..(time + task.1.time) {
+        for instant in time..task.1.completion(time) {
             if self.matrix[cast_usize(instant)][machine].is_some() {
                 return false;
             }
@@ -158,8 +173,8 @@ impl<'a> ScheduleBuilder<'a> {
     fn check_conflicts(&self, task: &TaskWithId, time: u64) -> bool {
         for &conflict in self.instance.graph.conflicts(task.0) {
             if let Some(&(_, other_time)) = self.scheduled.get(&conflict) {
-                let other = self.instance.tasks[conflict];
-                if time < other_time + other.time && other_time < time + task.1.time {
+                let other = &self.instance.tasks[conflict];
+                if time < other.completion(other_time) && other_time < task.1.completion(time) {
                     return false;
                 }
             }
@@ -177,12 +192,12 @@ impl<'a> ScheduleBuilder<'a> {
             unreachable!("Task must be scheduled");
         };
 
-        if time + new_task.1.time > cast_u64(self.matrix.len()) {
+        if !new_task.1.fits_before(time, cast_u64(self.matrix.len())) {
             return false;
         }
 
-        let start = cast_usize(time + task.1.time);
-        let end = cast_usize(time + new_task.1.time);
+        let start = cast_usize(task.1.completion(time));
+        let end = cast_usize(new_task.1.completion(time));
         for instant in &self.matrix[start..end] {
             if instant[machine].is_some() {
                 return false;
@@ -206,10 +221,21 @@ impl<'a> From<ScheduleBuilder<'a>> for Schedule<'a> {
 }
 
 /// Tresoldi's algorithm.
+///
+/// Every iteration repeats greedy insertion, local search and compaction of a random solution
+/// until none of them changes it. Compaction can be disabled and local search passes capped,
+/// e.g. compaction never helps on unit-time instances. The search can stop early when restarts
+/// stop improving the best solution.
+///
+/// The random number generator can be replaced with [`Tresoldi::with_rng`], e.g. to script
+/// the random solutions in tests.
 #[derive(Clone, Debug)]
-pub struct Tresoldi {
+pub struct Tresoldi<R = StdRng> {
     iterations: usize,
-    rng: StdRng,
+    patience: Option<usize>,
+    compact: bool,
+    local_search_passes: Option<usize>,
+    rng: R,
 }
 
 impl Tresoldi {
@@ -218,35 +244,85 @@ impl Tresoldi {
     pub fn new(iterations: usize, seed: u64) -> Self {
         Self {
             iterations,
+            patience: None,
+            compact: true,
+            local_search_passes: None,
             rng: StdRng::seed_from_u64(seed),
         }
     }
 }
 
-#[allow(unsafe_code)]
-#[linkme::distributed_slice(super::SCHEDULERS)]
-static INSTANCE: fn() -> Box<dyn Scheduler> = || Box::new(Tresoldi::default());
-
-impl Default for Tresoldi {
-    fn default() -> Self {
-        Self {
-            iterations: 200,
-            rng: StdRng::from_rng(rand::thread_rng()).unwrap_or_else(|_| StdRng::seed_from_u64(0)),
+impl<R: RngCore> Tresoldi<R> {
+    /// Replaces the random number generator, other settings are kept.
+    #[must_use]
+    pub fn with_rng<T: RngCore>(self, rng: T) -> Tresoldi<T> {
+        Tresoldi {
+            iterations: self.iterations,
+            patience: self.patience,
+            compact: self.compact,
+            local_search_passes: self.local_search_passes,
+            rng,
         }
     }
-}
 
-impl Scheduler for Tresoldi {
-    fn schedule<'a>(&mut self, instance: &'a Instance) -> Schedule<'a> {
+    /// Stops the search after the given number of consecutive restarts without improvement.
+    /// The number of iterations or the budget still caps the search. By default the search
+    /// never stops early, like with patience equal to the number of iterations.
+    /// The first restart always runs, so zero patience stops right after it.
+    #[must_use]
+    pub const fn with_patience(mut self, patience: usize) -> Self {
+        self.patience = Some(patience);
+        self
+    }
+
+    /// Sets whether the compaction phase is performed.
+    #[must_use]
+    pub const fn with_compact(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        self
+    }
+
+    /// Limits the number of local search passes per iteration.
+    /// By default local search runs until the solution stops changing.
+    #[must_use]
+    pub const fn with_local_search_passes(mut self, passes: usize) -> Self {
+        self.local_search_passes = Some(passes);
+        self
+    }
+
+    /// Restarts the search for the given number of iterations,
+    /// or until the deadline if it is set. At least one iteration is performed before the deadline.
+    /// It stops earlier once the patience runs out.
+    fn search<'a>(&mut self, instance: &'a Instance, deadline: Option<Instant>) -> Schedule<'a> {
         let mut best_solution = ScheduleBuilder::empty(instance);
+        let mut stagnation = 0;
+
+        for iteration in 0.. {
+            let finished = deadline.map_or(iteration >= self.iterations, |deadline| {
+                iteration > 0 && Instant::now() >= deadline
+            });
+            let patient = iteration == 0 || self.patience.map_or(true, |limit| stagnation < limit);
+            if finished || !patient {
+                break;
+            }
 
-        for _ in 0..self.iterations {
             let mut solution = ScheduleBuilder::random(instance, &mut self.rng);
+            let mut passes = 0;
 
             loop {
                 let mut change = solution.greedy_insert();
-                change |= solution.local_search();
-                change |= solution.compact();
+
+                if self
+                    .local_search_passes
+                    .map_or(true, |limit| passes < limit)
+                {
+                    change |= solution.local_search();
+                    passes += 1;
+                }
+
+                if self.compact {
+                    change |= solution.compact();
+                }
 
                 if !change {
                     break;
@@ -255,24 +331,148 @@ impl Scheduler for Tresoldi {
 
             if solution.score > best_solution.score {
                 best_solution = solution;
+                stagnation = 0;
+            } else {
+                stagnation += 1;
             }
         }
 
         best_solution.into()
     }
+}
+
+#[allow(unsafe_code)]
+#[linkme::distributed_slice(super::SCHEDULERS)]
+static INSTANCE: fn() -> Box<dyn Scheduler> = || Box::new(Tresoldi::default());
+
+impl Default for Tresoldi {
+    fn default() -> Self {
+        Self {
+            iterations: 200,
+            patience: None,
+            compact: true,
+            local_search_passes: None,
+            rng: StdRng::from_rng(rand::thread_rng()).unwrap_or_else(|_| StdRng::seed_from_u64(0)),
+        }
+    }
+}
+
+impl<R: RngCore + SeedableRng> Scheduler for Tresoldi<R> {
+    fn schedule<'a>(&mut self, instance: &'a Instance) -> Schedule<'a> {
+        self.search(instance, None)
+    }
+
+    fn schedule_with_budget<'a>(
+        &mut self,
+        instance: &'a Instance,
+        budget: Duration,
+    ) -> Schedule<'a> {
+        self.search(instance, Some(Instant::now() + budget))
+    }
 
     fn name(&self) -> &'static str {
         "Tresoldi"
     }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.rng = R::seed_from_u64(seed);
+    }
+
+    fn is_randomized(&self) -> bool {
+        true
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::algo::test::ScriptedRng;
+    use crate::core::Task;
     use crate::data::samples;
 
     #[test]
     fn test_tresoldi() {
         assert!(samples(0, &mut Tresoldi::new(10, 0)).is_ok());
     }
+
+    #[test]
+    fn test_tresoldi_without_compact() {
+        let mut tresoldi = Tresoldi::new(10, 0)
+            .with_compact(false)
+            .with_local_search_passes(2);
+        assert!(samples(0, &mut tresoldi).is_ok());
+    }
+
+    #[test]
+    fn random_solution_should_follow_rng() {
+        let task = Task::new(1, 1);
+        let instance = Instance::new_no_conflict(1, 1, vec![task; 2]);
+        let scheduled = |values| {
+            let mut tresoldi = Tresoldi::new(1, 0).with_rng(ScriptedRng::new(values));
+            let schedule = tresoldi.schedule(&instance);
+            (0..2).find(|&task| schedule.get_schedule(task).is_some())
+        };
+
+        // Shuffling swaps the tasks when drawing zero, so the other one is inserted first.
+        assert_eq!(scheduled(vec![0]), Some(1));
+        assert_eq!(scheduled(vec![1 << 31]), Some(0));
+    }
+
+    #[test]
+    fn tasks_should_start_after_release() {
+        let task = |weight, release| Task {
+            release,
+            ..Task::new(2, weight)
+        };
+        let tasks = vec![task(3, 2), task(1, 0), task(2, 1)];
+        let instance = Instance::new_no_conflict(1, 6, tasks);
+
+        for seed in 0..10 {
+            let schedule = Tresoldi::new(5, seed).schedule(&instance);
+            assert!(schedule.verify());
+        }
+    }
+
+    #[test]
+    fn tasks_should_run_on_eligible_machines() {
+        let task = |weight, eligible| Task {
+            eligible,
+            ..Task::new(2, weight)
+        };
+        let tasks = vec![
+            task(3, Some(vec![1])),
+            task(1, None),
+            task(2, Some(vec![1])),
+            task(2, Some(vec![0])),
+        ];
+        let instance = Instance::new_no_conflict(2, 4, tasks);
+
+        for seed in 0..10 {
+            let schedule = Tresoldi::new(5, seed).schedule(&instance);
+            assert!(schedule.verify());
+        }
+    }
+
+    #[test]
+    fn patience_should_stop_stagnating_search() -> anyhow::Result<()> {
+        let file = std::fs::File::open("samples/3_301_0.in")?;
+        let instance: Instance = crate::data::deserialize(&mut std::io::BufReader::new(file))?;
+
+        let expected = Tresoldi::new(20, 3).schedule(&instance);
+        let schedule = Tresoldi::new(20, 3).with_patience(20).schedule(&instance);
+        assert_eq!(schedule, expected);
+
+        // Unlimited iterations finish only because the patience runs out.
+        let schedule = Tresoldi::new(usize::MAX, 3)
+            .with_patience(5)
+            .schedule(&instance);
+        assert!(schedule.verify());
+
+        // The first restart runs even without patience.
+        let expected = Tresoldi::new(1, 3).schedule(&instance);
+        let schedule = Tresoldi::new(20, 3).with_patience(0).schedule(&instance);
+        assert!(schedule.calculate_score() > 0);
+        assert_eq!(schedule, expected);
+        Ok(())
+    }
 }
@@ -1,11 +1,83 @@
-use crate::core::{Instance, Schedule, ScheduleBuilder, Scheduler};
+use crate::core::{Improver, Instance, Schedule, ScheduleBuilder, Scheduler, TardyOrder};
 use rand::rngs::StdRng;
-use rand::{Rng, SeedableRng};
+use rand::{Rng, RngCore, SeedableRng};
+use rayon::prelude::*;
+use std::time::{Duration, Instant};
 
-type Neighborhood<'a, 'b> = dyn Iterator<Item = ScheduleBuilder<'a>> + 'b;
+/// Candidate produced by a neighborhood.
+/// Contains the `(machine, index)` positions changed by the move and the new schedule.
+pub(super) type Candidate<'a> = (Vec<(usize, usize)>, ScheduleBuilder<'a>);
+
+pub(super) type Neighborhood<'b> = dyn Iterator<Item = Move> + 'b;
+
+type NeighborhoodFactory = for<'a, 'b> fn(&'b ScheduleBuilder<'a>) -> Box<Neighborhood<'b>>;
+
+type NeighborhoodSampler =
+    for<'a> fn(&ScheduleBuilder<'a>, &mut dyn RngCore) -> Option<Candidate<'a>>;
+
+/// Builds the candidate of a move on the `(first, second)` machines with positions `i` and `j`.
+type Apply = for<'a> fn(&ScheduleBuilder<'a>, (usize, usize), usize, usize) -> Candidate<'a>;
+
+/// A move enumerated by a neighborhood.
+/// Enumerating moves is cheap, the candidate schedule is built only when the move is applied.
+#[derive(Clone, Copy)]
+pub(super) struct Move {
+    apply: Apply,
+    machines: (usize, usize),
+    i: usize,
+    j: usize,
+}
+
+impl Move {
+    const fn new(apply: Apply, machines: (usize, usize), i: usize, j: usize) -> Self {
+        Self {
+            apply,
+            machines,
+            i,
+            j,
+        }
+    }
+
+    /// Builds the candidate of the move from the schedule it was enumerated from.
+    pub(super) fn apply<'a>(self, schedule: &ScheduleBuilder<'a>) -> Candidate<'a> {
+        (self.apply)(schedule, self.machines, self.i, self.j)
+    }
+}
+
+/// Returns a random number from `0..upper`, or `None` if the range is empty.
+fn random_below(rng: &mut dyn RngCore, upper: usize) -> Option<usize> {
+    (upper > 0).then(|| rng.gen_range(0..upper))
+}
+
+/// Returns two random machines `first < second`, or `None` if there are less than two machines.
+fn random_machine_pair(
+    schedule: &ScheduleBuilder,
+    rng: &mut dyn RngCore,
+) -> Option<(usize, usize)> {
+    let first = random_below(rng, schedule.machines_len().saturating_sub(1))?;
+    let second = rng.gen_range(first + 1..schedule.machines_len());
+    Some((first, second))
+}
+
+/// Swaps tasks `i` and `j` on the machine.
+fn swap_single<'a>(
+    schedule: &ScheduleBuilder<'a>,
+    (machine, _): (usize, usize),
+    i: usize,
+    j: usize,
+) -> Candidate<'a> {
+    let mut builder = schedule.clone();
+
+    builder.reorganize_schedule(|machines, _| {
+        machines[machine].swap(i, j);
+        (vec![(machine, i)], vec![])
+    });
+
+    (vec![(machine, i), (machine, j)], builder)
+}
 
 /// Neighborhood that swaps two tasks on the same machine.
-pub struct SwapSingleMachine<'a, 'b> {
+struct SwapSingleMachine<'a, 'b> {
     schedule: &'b ScheduleBuilder<'a>,
     machine: usize,
     i: usize,
@@ -13,7 +85,7 @@ pub struct SwapSingleMachine<'a, 'b> {
 }
 
 /// Creates a new instance of `SwapSingleMachine` neighborhood.
-fn swap_single_machine<'a, 'b>(schedule: &'b ScheduleBuilder<'a>) -> Box<Neighborhood<'a, 'b>> {
+fn swap_single_machine<'b>(schedule: &'b ScheduleBuilder<'_>) -> Box<Neighborhood<'b>> {
     Box::new(SwapSingleMachine {
         schedule,
         machine: 0,
@@ -22,23 +94,28 @@ fn swap_single_machine<'a, 'b>(schedule: &'b ScheduleBuilder<'a>) -> Box<Neighbo
     })
 }
 
-impl<'a, 'b> Iterator for SwapSingleMachine<'a, 'b> {
-    type Item = ScheduleBuilder<'a>;
+/// Samples a random move from `SwapSingleMachine` neighborhood.
+fn sample_swap_single_machine<'a>(
+    schedule: &ScheduleBuilder<'a>,
+    rng: &mut dyn RngCore,
+) -> Option<Candidate<'a>> {
+    let machine = random_below(rng, schedule.machines_len())?;
+    let i = random_below(rng, schedule.machine_tasks_len(machine).saturating_sub(1))?;
+    let j = rng.gen_range(i + 1..schedule.machine_tasks_len(machine));
+    Some(swap_single(schedule, (machine, machine), i, j))
+}
+
+impl Iterator for SwapSingleMachine<'_, '_> {
+    type Item = Move;
 
     fn next(&mut self) -> Option<Self::Item> {
         while self.machine < self.schedule.machines_len() {
             while self.i + 1 < self.schedule.machine_tasks_len(self.machine) {
                 if self.j < self.schedule.machine_tasks_len(self.machine) {
-                    let mut builder = self.schedule.clone();
-
-                    builder.reorganize_schedule(|machines, _| {
-                        machines[self.machine].swap(self.i, self.j);
-                        (vec![(self.machine, self.i)], vec![])
-                    });
-
+                    let machines = (self.machine, self.machine);
+                    let candidate = Move::new(swap_single, machines, self.i, self.j);
                     self.j += 1;
-
-                    return Some(builder);
+                    return Some(candidate);
                 }
                 self.i += 1;
             }
@@ -48,6 +125,19 @@ impl<'a, 'b> Iterator for SwapSingleMachine<'a, 'b> {
     }
 }
 
+/// Moves task `i` to position `j` on the machine.
+fn move_single<'a>(
+    schedule: &ScheduleBuilder<'a>,
+    (machine, _): (usize, usize),
+    i: usize,
+    j: usize,
+) -> Candidate<'a> {
+    let mut builder = schedule.clone();
+    builder.move_task(builder.machine_task(machine, i), machine, j);
+
+    (vec![(machine, i), (machine, j)], builder)
+}
+
 /// Neighborhood that moves task on the same machine.
 struct MoveSingleMachine<'a, 'b> {
     schedule: &'b ScheduleBuilder<'a>,
@@ -57,7 +147,7 @@ struct MoveSingleMachine<'a, 'b> {
 }
 
 /// Creates a new instance of `MoveSingleMachine` neighborhood.
-fn move_single_machine<'a, 'b>(schedule: &'b ScheduleBuilder<'a>) -> Box<Neighborhood<'a, 'b>> {
+fn move_single_machine<'b>(schedule: &'b ScheduleBuilder<'_>) -> Box<Neighborhood<'b>> {
     Box::new(MoveSingleMachine {
         schedule,
         machine: 0,
@@ -66,24 +156,28 @@ fn move_single_machine<'a, 'b>(schedule: &'b ScheduleBuilder<'a>) -> Box<Neighbo
     })
 }
 
-impl<'a, 'b> Iterator for MoveSingleMachine<'a, 'b> {
-    type Item = ScheduleBuilder<'a>;
+/// Samples a random move from `MoveSingleMachine` neighborhood.
+fn sample_move_single_machine<'a>(
+    schedule: &ScheduleBuilder<'a>,
+    rng: &mut dyn RngCore,
+) -> Option<Candidate<'a>> {
+    let machine = random_below(rng, schedule.machines_len())?;
+    let i = random_below(rng, schedule.machine_tasks_len(machine).saturating_sub(1))?;
+    let j = rng.gen_range(i + 1..schedule.machine_tasks_len(machine));
+    Some(move_single(schedule, (machine, machine), i, j))
+}
+
+impl Iterator for MoveSingleMachine<'_, '_> {
+    type Item = Move;
 
     fn next(&mut self) -> Option<Self::Item> {
         while self.machine < self.schedule.machines_len() {
             while self.i + 1 < self.schedule.machine_tasks_len(self.machine) {
                 if self.j < self.schedule.machine_tasks_len(self.machine) {
-                    let mut builder = self.schedule.clone();
-
-                    builder.reorganize_schedule(|machines, _| {
-                        let task = machines[self.machine].remove(self.i);
-                        machines[self.machine].insert(self.j, task);
-                        (vec![(self.machine, self.i.min(self.j))], vec![])
-                    });
-
+                    let machines = (self.machine, self.machine);
+                    let candidate = Move::new(move_single, machines, self.i, self.j);
                     self.j += 1;
-
-                    return Some(builder);
+                    return Some(candidate);
                 }
                 self.i += 1;
             }
@@ -93,6 +187,26 @@ impl<'a, 'b> Iterator for MoveSingleMachine<'a, 'b> {
     }
 }
 
+/// Swaps task `i` of the `first` machine with task `j` of the `second` machine.
+fn swap_two<'a>(
+    schedule: &ScheduleBuilder<'a>,
+    (first, second): (usize, usize),
+    i: usize,
+    j: usize,
+) -> Candidate<'a> {
+    let mut builder = schedule.clone();
+
+    builder.reorganize_schedule(|machines, _| {
+        let value = machines[first][i];
+        machines[first][i] = machines[second][j];
+        machines[second][j] = value;
+
+        (vec![(first, i), (second, j)], vec![])
+    });
+
+    (vec![(first, i), (second, j)], builder)
+}
+
 /// Neighborhood that swaps tasks on different machines.
 struct SwapTwoMachines<'a, 'b> {
     schedule: &'b ScheduleBuilder<'a>,
@@ -103,7 +217,7 @@ struct SwapTwoMachines<'a, 'b> {
 }
 
 /// Creates a new instance of `SwapTwoMachines` neighborhood.
-fn swap_two_machines<'a, 'b>(schedule: &'b ScheduleBuilder<'a>) -> Box<Neighborhood<'a, 'b>> {
+fn swap_two_machines<'b>(schedule: &'b ScheduleBuilder<'_>) -> Box<Neighborhood<'b>> {
     Box::new(SwapTwoMachines {
         schedule,
         first: 0,
@@ -113,27 +227,29 @@ fn swap_two_machines<'a, 'b>(schedule: &'b ScheduleBuilder<'a>) -> Box<Neighborh
     })
 }
 
-impl<'a, 'b> Iterator for SwapTwoMachines<'a, 'b> {
-    type Item = ScheduleBuilder<'a>;
+/// Samples a random move from `SwapTwoMachines` neighborhood.
+fn sample_swap_two_machines<'a>(
+    schedule: &ScheduleBuilder<'a>,
+    rng: &mut dyn RngCore,
+) -> Option<Candidate<'a>> {
+    let (first, second) = random_machine_pair(schedule, rng)?;
+    let i = random_below(rng, schedule.machine_tasks_len(first))?;
+    let j = random_below(rng, schedule.machine_tasks_len(second))?;
+    Some(swap_two(schedule, (first, second), i, j))
+}
+
+impl Iterator for SwapTwoMachines<'_, '_> {
+    type Item = Move;
 
     fn next(&mut self) -> Option<Self::Item> {
         while self.first + 1 < self.schedule.machines_len() {
             while self.second < self.schedule.machines_len() {
                 while self.i < self.schedule.machine_tasks_len(self.first) {
                     if self.j < self.schedule.machine_tasks_len(self.second) {
-                        let mut builder = self.schedule.clone();
-
-                        builder.reorganize_schedule(|machines, _| {
-                            let value = machines[self.first][self.i];
-                            machines[self.first][self.i] = machines[self.second][self.j];
-                            machines[self.second][self.j] = value;
-
-                            (vec![(self.first, self.i), (self.second, self.j)], vec![])
-                        });
-
+                        let machines = (self.first, self.second);
+                        let candidate = Move::new(swap_two, machines, self.i, self.j);
                         self.j += 1;
-
-                        return Some(builder);
+                        return Some(candidate);
                     }
                     self.i += 1;
                 }
@@ -145,6 +261,19 @@ impl<'a, 'b> Iterator for SwapTwoMachines<'a, 'b> {
     }
 }
 
+/// Moves task `i` of the `first` machine to position `j` of the `second` machine.
+fn move_two<'a>(
+    schedule: &ScheduleBuilder<'a>,
+    (first, second): (usize, usize),
+    i: usize,
+    j: usize,
+) -> Candidate<'a> {
+    let mut builder = schedule.clone();
+    builder.move_task(builder.machine_task(first, i), second, j);
+
+    (vec![(first, i), (second, j)], builder)
+}
+
 /// Neighborhood that moves task on different machine.
 struct MoveTwoMachines<'a, 'b> {
     schedule: &'b ScheduleBuilder<'a>,
@@ -155,7 +284,7 @@ struct MoveTwoMachines<'a, 'b> {
 }
 
 /// Creates a new instance of `MoveTwoMachines` neighborhood.
-fn move_two_machines<'a, 'b>(schedule: &'b ScheduleBuilder<'a>) -> Box<Neighborhood<'a, 'b>> {
+fn move_two_machines<'b>(schedule: &'b ScheduleBuilder<'_>) -> Box<Neighborhood<'b>> {
     Box::new(MoveTwoMachines {
         schedule,
         first: 0,
@@ -165,26 +294,29 @@ fn move_two_machines<'a, 'b>(schedule: &'b ScheduleBuilder<'a>) -> Box<Neighborh
     })
 }
 
-impl<'a, 'b> Iterator for MoveTwoMachines<'a, 'b> {
-    type Item = ScheduleBuilder<'a>;
+/// Samples a random move from `MoveTwoMachines` neighborhood.
+fn sample_move_two_machines<'a>(
+    schedule: &ScheduleBuilder<'a>,
+    rng: &mut dyn RngCore,
+) -> Option<Candidate<'a>> {
+    let (first, second) = random_machine_pair(schedule, rng)?;
+    let i = random_below(rng, schedule.machine_tasks_len(first))?;
+    let j = rng.gen_range(0..=schedule.machine_tasks_len(second));
+    Some(move_two(schedule, (first, second), i, j))
+}
+
+impl Iterator for MoveTwoMachines<'_, '_> {
+    type Item = Move;
 
     fn next(&mut self) -> Option<Self::Item> {
         while self.first + 1 < self.schedule.machines_len() {
             while self.second < self.schedule.machines_len() {
                 while self.i < self.schedule.machine_tasks_len(self.first) {
                     if self.j <= self.schedule.machine_tasks_len(self.second) {
-                        let mut builder = self.schedule.clone();
-
-                        builder.reorganize_schedule(|machines, _| {
-                            let value = machines[self.first].remove(self.i);
-                            machines[self.second].insert(self.j, value);
-
-                            (vec![(self.first, self.i), (self.second, self.j)], vec![])
-                        });
-
+                        let machines = (self.first, self.second);
+                        let candidate = Move::new(move_two, machines, self.i, self.j);
                         self.j += 1;
-
-                        return Some(builder);
+                        return Some(candidate);
                     }
                     self.i += 1;
                 }
@@ -196,6 +328,24 @@ impl<'a, 'b> Iterator for MoveTwoMachines<'a, 'b> {
     }
 }
 
+/// Swaps task `i` of the machine with tardy task `j`.
+fn replace_tardy<'a>(
+    schedule: &ScheduleBuilder<'a>,
+    (machine, _): (usize, usize),
+    i: usize,
+    j: usize,
+) -> Candidate<'a> {
+    let mut builder = schedule.clone();
+
+    builder.reorganize_schedule(|machines, tardy_tasks| {
+        std::mem::swap(&mut machines[machine][i], &mut tardy_tasks[j]);
+
+        (vec![(machine, i)], vec![tardy_tasks[j]])
+    });
+
+    (vec![(machine, i)], builder)
+}
+
 /// Neighborhood that replaces task with a tardy task.
 struct ReplaceWithTardy<'a, 'b> {
     schedule: &'b ScheduleBuilder<'a>,
@@ -205,7 +355,7 @@ struct ReplaceWithTardy<'a, 'b> {
 }
 
 /// Creates a new instance of `ReplaceWithTardy` neighborhood.
-fn replace_with_tardy<'a, 'b>(schedule: &'b ScheduleBuilder<'a>) -> Box<Neighborhood<'a, 'b>> {
+fn replace_with_tardy<'b>(schedule: &'b ScheduleBuilder<'_>) -> Box<Neighborhood<'b>> {
     Box::new(ReplaceWithTardy {
         schedule,
         machine: 0,
@@ -214,27 +364,28 @@ fn replace_with_tardy<'a, 'b>(schedule: &'b ScheduleBuilder<'a>) -> Box<Neighbor
     })
 }
 
-impl<'a, 'b> Iterator for ReplaceWithTardy<'a, 'b> {
-    type Item = ScheduleBuilder<'a>;
+/// Samples a random move from `ReplaceWithTardy` neighborhood.
+fn sample_replace_with_tardy<'a>(
+    schedule: &ScheduleBuilder<'a>,
+    rng: &mut dyn RngCore,
+) -> Option<Candidate<'a>> {
+    let machine = random_below(rng, schedule.machines_len())?;
+    let i = random_below(rng, schedule.machine_tasks_len(machine))?;
+    let j = random_below(rng, schedule.tardy_len())?;
+    Some(replace_tardy(schedule, (machine, machine), i, j))
+}
+
+impl Iterator for ReplaceWithTardy<'_, '_> {
+    type Item = Move;
 
     fn next(&mut self) -> Option<Self::Item> {
         while self.machine < self.schedule.machines_len() {
             while self.i < self.schedule.machine_tasks_len(self.machine) {
                 if self.j < self.schedule.tardy_len() {
-                    let mut builder = self.schedule.clone();
-
-                    builder.reorganize_schedule(|machines, tardy_tasks| {
-                        std::mem::swap(
-                            &mut machines[self.machine][self.i],
-                            &mut tardy_tasks[self.j],
-                        );
-
-                        (vec![(self.machine, self.i)], vec![tardy_tasks[self.j]])
-                    });
-
+                    let machines = (self.machine, self.machine);
+                    let candidate = Move::new(replace_tardy, machines, self.i, self.j);
                     self.j += 1;
-
-                    return Some(builder);
+                    return Some(candidate);
                 }
                 self.i += 1;
             }
@@ -244,6 +395,19 @@ impl<'a, 'b> Iterator for ReplaceWithTardy<'a, 'b> {
     }
 }
 
+/// Inserts tardy task `j` at position `i` of the machine.
+fn insert_tardy<'a>(
+    schedule: &ScheduleBuilder<'a>,
+    (machine, _): (usize, usize),
+    i: usize,
+    j: usize,
+) -> Candidate<'a> {
+    let mut builder = schedule.clone();
+    builder.move_task(builder.tardy_task(j), machine, i);
+
+    (vec![(machine, i)], builder)
+}
+
 /// Neighborhood that adds a tardy task.
 struct AddTardy<'a, 'b> {
     schedule: &'b ScheduleBuilder<'a>,
@@ -253,7 +417,7 @@ struct AddTardy<'a, 'b> {
 }
 
 /// Creates a new instance of `AddTardy` neighborhood.
-fn add_tardy<'a, 'b>(schedule: &'b ScheduleBuilder<'a>) -> Box<Neighborhood<'a, 'b>> {
+fn add_tardy<'b>(schedule: &'b ScheduleBuilder<'_>) -> Box<Neighborhood<'b>> {
     Box::new(AddTardy {
         schedule,
         machine: 0,
@@ -262,25 +426,28 @@ fn add_tardy<'a, 'b>(schedule: &'b ScheduleBuilder<'a>) -> Box<Neighborhood<'a,
     })
 }
 
-impl<'a, 'b> Iterator for AddTardy<'a, 'b> {
-    type Item = ScheduleBuilder<'a>;
+/// Samples a random move from `AddTardy` neighborhood.
+fn sample_add_tardy<'a>(
+    schedule: &ScheduleBuilder<'a>,
+    rng: &mut dyn RngCore,
+) -> Option<Candidate<'a>> {
+    let machine = random_below(rng, schedule.machines_len())?;
+    let i = rng.gen_range(0..=schedule.machine_tasks_len(machine));
+    let j = random_below(rng, schedule.tardy_len())?;
+    Some(insert_tardy(schedule, (machine, machine), i, j))
+}
+
+impl Iterator for AddTardy<'_, '_> {
+    type Item = Move;
 
     fn next(&mut self) -> Option<Self::Item> {
         while self.machine < self.schedule.machines_len() {
             while self.i <= self.schedule.machine_tasks_len(self.machine) {
                 if self.j < self.schedule.tardy_len() {
-                    let mut builder = self.schedule.clone();
-
-                    builder.reorganize_schedule(|machines, tardy_tasks| {
-                        machines[self.machine].insert(self.i, tardy_tasks[self.j]);
-                        tardy_tasks.remove(self.j);
-
-                        (vec![(self.machine, self.i)], vec![])
-                    });
-
+                    let machines = (self.machine, self.machine);
+                    let candidate = Move::new(insert_tardy, machines, self.i, self.j);
                     self.j += 1;
-
-                    return Some(builder);
+                    return Some(candidate);
                 }
                 self.i += 1;
             }
@@ -290,29 +457,221 @@ impl<'a, 'b> Iterator for AddTardy<'a, 'b> {
     }
 }
 
-fn neighborhood_search(mut schedule: ScheduleBuilder) -> ScheduleBuilder {
-    let factories = [
-        swap_single_machine,
-        move_single_machine,
-        swap_two_machines,
-        move_two_machines,
-        replace_with_tardy,
-        add_tardy,
-    ];
+/// Returns the positions of scheduled tasks conflicting with tardy task `j`, in order.
+fn ejectable_positions(schedule: &ScheduleBuilder, j: usize) -> Vec<(usize, usize)> {
+    let task = schedule.tardy_task(j);
+    let conflicts = schedule.instance().graph.conflicts(task).iter();
+    let mut positions: Vec<_> = conflicts
+        .filter_map(|&other| schedule.position(other))
+        .collect();
+    positions.sort_unstable();
+    positions
+}
 
-    let mut k = 0;
+/// Puts tardy task `j` in place of task `i` of the first machine and reinserts the ejected
+/// task on the second machine, before the first task starting at or after the earliest time
+/// the ejected task is free of conflicts. Tasks pushed past the deadline become tardy.
+fn eject_and_reinsert<'a>(
+    schedule: &ScheduleBuilder<'a>,
+    (machine, target): (usize, usize),
+    i: usize,
+    j: usize,
+) -> Candidate<'a> {
+    let mut builder = schedule.clone();
+    let ejected = builder.machine_task(machine, i);
+
+    builder.reorganize_schedule(|machines, tardy_tasks| {
+        machines[machine][i] = tardy_tasks.remove(j);
+
+        (vec![(machine, i)], vec![ejected])
+    });
+
+    let start = builder.calculate_non_conflict_time(ejected, 0);
+    let start = start.unwrap_or_else(|| builder.instance().deadline);
+    let position = (0..builder.machine_tasks_len(target))
+        .take_while(|&index| {
+            let task = builder.machine_task(target, index);
+            builder
+                .get_schedule(task)
+                .is_some_and(|info| info.start < start)
+        })
+        .count();
+    builder.move_task(ejected, target, position);
+
+    (vec![(machine, i), (target, position)], builder)
+}
 
-    while k < factories.len() {
-        let mut best_score = schedule.calculate_score();
-        let mut best_schedule = None;
+/// Neighborhood that ejects a scheduled task conflicting with a tardy task.
+/// The tardy task takes its place and the ejected task is reinserted on another machine,
+/// which helps when a heavy task is blocked by a task that could run elsewhere.
+struct EjectChain<'a, 'b> {
+    schedule: &'b ScheduleBuilder<'a>,
+    j: usize,
+    positions: std::vec::IntoIter<(usize, usize)>,
+    position: Option<(usize, usize)>,
+    target: usize,
+}
+
+/// Creates a new instance of `EjectChain` neighborhood.
+fn eject_chain<'b>(schedule: &'b ScheduleBuilder<'_>) -> Box<Neighborhood<'b>> {
+    Box::new(EjectChain {
+        schedule,
+        j: 0,
+        positions: Vec::new().into_iter(),
+        position: None,
+        target: 0,
+    })
+}
+
+/// Samples a random move from `EjectChain` neighborhood.
+fn sample_eject_chain<'a>(
+    schedule: &ScheduleBuilder<'a>,
+    rng: &mut dyn RngCore,
+) -> Option<Candidate<'a>> {
+    let j = random_below(rng, schedule.tardy_len())?;
+    let positions = ejectable_positions(schedule, j);
+    let (machine, i) = positions[random_below(rng, positions.len())?];
+    // Any machine other than the one of the ejected task.
+    let target = random_below(rng, schedule.machines_len() - 1)?;
+    let target = if target >= machine {
+        target + 1
+    } else {
+        target
+    };
+    Some(eject_and_reinsert(schedule, (machine, target), i, j))
+}
+
+impl Iterator for EjectChain<'_, '_> {
+    type Item = Move;
 
-        for schedule in factories[k](&schedule) {
-            let score = schedule.calculate_score();
-            if score > best_score {
-                best_score = score;
-                best_schedule = Some(schedule);
+    fn next(&mut self) -> Option<Self::Item> {
+        let instance = self.schedule.instance();
+        loop {
+            if let Some((machine, i)) = self.position {
+                let ejected = &instance.tasks[self.schedule.machine_task(machine, i)];
+                while self.target < self.schedule.machines_len() {
+                    let target = self.target;
+                    self.target += 1;
+                    if target != machine && ejected.is_eligible(target) {
+                        let machines = (machine, target);
+                        return Some(Move::new(eject_and_reinsert, machines, i, self.j - 1));
+                    }
+                }
+            }
+            if let Some(position) = self.positions.next() {
+                self.position = Some(position);
+                self.target = 0;
+                continue;
             }
+            if self.j >= self.schedule.tardy_len() {
+                return None;
+            }
+            self.positions = ejectable_positions(self.schedule, self.j).into_iter();
+            self.position = None;
+            self.j += 1;
         }
+    }
+}
+
+/// All neighborhoods in the order they are searched.
+pub(super) const NEIGHBORHOODS: [NeighborhoodFactory; 7] = [
+    swap_single_machine,
+    move_single_machine,
+    swap_two_machines,
+    move_two_machines,
+    replace_with_tardy,
+    add_tardy,
+    eject_chain,
+];
+
+/// Random samplers of the neighborhoods, in the same order as `NEIGHBORHOODS`.
+const SAMPLERS: [NeighborhoodSampler; 7] = [
+    sample_swap_single_machine,
+    sample_move_single_machine,
+    sample_swap_two_machines,
+    sample_move_two_machines,
+    sample_replace_with_tardy,
+    sample_add_tardy,
+    sample_eject_chain,
+];
+
+/// Returns the best candidate that improves the score of the schedule.
+fn best_improvement<'a>(
+    schedule: &ScheduleBuilder<'a>,
+    candidates: impl Iterator<Item = Candidate<'a>>,
+) -> Option<ScheduleBuilder<'a>> {
+    let mut best_score = schedule.calculate_score();
+    let mut best_schedule = None;
+
+    for (_, schedule) in candidates {
+        let score = schedule.calculate_score();
+        if score > best_score {
+            best_score = score;
+            best_schedule = Some(schedule);
+        }
+    }
+
+    best_schedule
+}
+
+/// Returns the first candidate that improves the score of the schedule.
+fn first_improvement<'a>(
+    schedule: &ScheduleBuilder<'a>,
+    mut candidates: impl Iterator<Item = Candidate<'a>>,
+) -> Option<ScheduleBuilder<'a>> {
+    let score = schedule.calculate_score();
+    let improving = candidates.find(|(_, candidate)| candidate.calculate_score() > score);
+    improving.map(|(_, candidate)| candidate)
+}
+
+/// Returns the best move that improves the score of the schedule.
+/// Candidates are built in parallel. Of equally good candidates the first move is taken,
+/// so the result is the same as of [`best_improvement`] over the applied moves.
+fn best_move<'a>(schedule: &ScheduleBuilder<'a>, moves: Vec<Move>) -> Option<ScheduleBuilder<'a>> {
+    let score = schedule.calculate_score();
+    let candidates = moves
+        .into_par_iter()
+        .enumerate()
+        .filter_map(|(index, next)| {
+            let (_, candidate) = next.apply(schedule);
+            let candidate_score = candidate.calculate_score();
+            (candidate_score > score).then_some((candidate_score, index, candidate))
+        });
+
+    let best = candidates.max_by(|first, second| {
+        let first_key = (first.0, std::cmp::Reverse(first.1));
+        first_key.cmp(&(second.0, std::cmp::Reverse(second.1)))
+    });
+    best.map(|(_, _, candidate)| candidate)
+}
+
+/// Performs the local search over all neighborhoods.
+/// With `sampling` set, only the given number of random moves is tried from each neighborhood.
+fn neighborhood_search<'a>(
+    mut schedule: ScheduleBuilder<'a>,
+    strategy: LocalSearchStrategy,
+    mut sampling: Option<(usize, &mut dyn RngCore)>,
+) -> ScheduleBuilder<'a> {
+    let mut k = 0;
+
+    while k < NEIGHBORHOODS.len() {
+        let best_schedule = match (sampling.as_mut(), strategy) {
+            (Some((size, rng)), LocalSearchStrategy::BestImprovement) => {
+                let candidates = (0..*size).filter_map(|_| SAMPLERS[k](&schedule, rng));
+                best_improvement(&schedule, candidates)
+            }
+            (Some((size, rng)), LocalSearchStrategy::FirstImprovement) => {
+                let candidates = (0..*size).filter_map(|_| SAMPLERS[k](&schedule, rng));
+                first_improvement(&schedule, candidates)
+            }
+            (None, LocalSearchStrategy::BestImprovement) => {
+                best_move(&schedule, NEIGHBORHOODS[k](&schedule).collect())
+            }
+            (None, LocalSearchStrategy::FirstImprovement) => {
+                let moves = NEIGHBORHOODS[k](&schedule);
+                first_improvement(&schedule, moves.map(|next| next.apply(&schedule)))
+            }
+        };
 
         if let Some(best_schedule) = best_schedule {
             schedule = best_schedule;
@@ -325,12 +684,83 @@ fn neighborhood_search(mut schedule: ScheduleBuilder) -> ScheduleBuilder {
     schedule
 }
 
+/// Moves the task to a random position on a random machine.
+/// Tardy tasks are moved from the tardy list.
+pub(super) fn relocate_task(schedule: &mut ScheduleBuilder, rng: &mut impl Rng, task: usize) {
+    let task_machine = schedule.get_schedule(task).map(|info| info.processor);
+
+    schedule.reorganize_schedule(|machines, tardy_tasks| {
+        let mut machine_fixings = Vec::with_capacity(2);
+
+        match task_machine {
+            Some(machine) => {
+                if let Some(pos) = machines[machine].iter().position(|&id| id == task) {
+                    machine_fixings.push((machine, pos));
+                }
+                machines[machine].retain(|&id| id != task);
+            }
+            None => tardy_tasks.retain(|&id| id != task),
+        }
+
+        let new_machine = rng.gen_range(0..machines.len());
+        let new_position = rng.gen_range(0..=machines[new_machine].len());
+        machines[new_machine].insert(new_position, task);
+
+        match task_machine.filter(|&machine| machine == new_machine) {
+            Some(_) => machine_fixings[0].1 = new_position.min(machine_fixings[0].1),
+            None => machine_fixings.push((new_machine, new_position)),
+        }
+
+        (machine_fixings, vec![])
+    });
+}
+
+/// Limits the number of shaking iterations of `VariableNeighborhoodSearch`.
+#[derive(Clone, Copy, Debug)]
+enum Budget {
+    /// Performs the given number of iterations.
+    Iterations(usize),
+    /// Performs iterations until the time limit is exceeded.
+    TimeLimit(Duration),
+}
+
+impl Budget {
+    /// Returns whether no more iterations should be started.
+    fn is_exhausted(self, iteration: usize, start: Instant) -> bool {
+        match self {
+            Self::Iterations(iterations) => iteration >= iterations,
+            Self::TimeLimit(limit) => start.elapsed() >= limit,
+        }
+    }
+}
+
+/// Strategy of accepting moves in the local search of `VariableNeighborhoodSearch`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum LocalSearchStrategy {
+    /// The whole neighborhood is searched and the best improving move is accepted.
+    #[default]
+    BestImprovement,
+    /// The first improving move is accepted, which makes each step faster on large instances.
+    FirstImprovement,
+}
+
 /// Performs the Variable Neighborhood Search algorithm.
 /// It is done inside iterations of the Local Search algorithm.
+///
+/// By default every neighborhood is enumerated exhaustively, which takes `O(m^2 n^2)`
+/// candidates for the two machine neighborhoods. With `sample_size` set, only that many
+/// random moves are tried from each neighborhood. It scales to thousands of tasks,
+/// but the local search may stop before reaching a local optimum.
+///
+/// The random number generator can be replaced with [`VariableNeighborhoodSearch::with_rng`].
 #[derive(Clone, Debug)]
-pub struct VariableNeighborhoodSearch {
-    iterations: usize,
-    rng: StdRng,
+pub struct VariableNeighborhoodSearch<R = StdRng> {
+    budget: Budget,
+    sample_size: Option<usize>,
+    shake_strength: f64,
+    strategy: LocalSearchStrategy,
+    tardy_order: TardyOrder,
+    rng: R,
 }
 
 impl VariableNeighborhoodSearch {
@@ -338,64 +768,114 @@ impl VariableNeighborhoodSearch {
     #[must_use]
     pub fn new(iterations: usize, seed: u64) -> Self {
         Self {
-            iterations,
+            budget: Budget::Iterations(iterations),
+            sample_size: None,
+            shake_strength: 0.05,
+            strategy: LocalSearchStrategy::BestImprovement,
+            tardy_order: TardyOrder::Weighted,
             rng: StdRng::seed_from_u64(seed),
         }
     }
-}
 
-impl Default for VariableNeighborhoodSearch {
-    fn default() -> Self {
+    /// Creates a new instance of `VariableNeighborhoodSearch` that samples `sample_size`
+    /// random moves from each neighborhood instead of enumerating it.
+    #[must_use]
+    pub fn new_sampled(iterations: usize, sample_size: usize, seed: u64) -> Self {
         Self {
-            iterations: 200,
-            rng: StdRng::from_rng(rand::thread_rng()).unwrap_or_else(|_| StdRng::seed_from_u64(0)),
+            budget: Budget::Iterations(iterations),
+            sample_size: Some(sample_size),
+            shake_strength: 0.05,
+            strategy: LocalSearchStrategy::BestImprovement,
+            tardy_order: TardyOrder::Weighted,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Creates a new instance of `VariableNeighborhoodSearch` that shakes the schedule
+    /// until `duration` elapses instead of a fixed number of iterations.
+    /// The initial local search always completes, so the time limit may be exceeded.
+    #[must_use]
+    pub fn with_time_limit(duration: Duration, seed: u64) -> Self {
+        Self {
+            budget: Budget::TimeLimit(duration),
+            sample_size: None,
+            shake_strength: 0.05,
+            strategy: LocalSearchStrategy::BestImprovement,
+            tardy_order: TardyOrder::Weighted,
+            rng: StdRng::seed_from_u64(seed),
         }
     }
 }
 
-impl Scheduler for VariableNeighborhoodSearch {
-    fn schedule<'a>(&mut self, instance: &'a Instance) -> Schedule<'a> {
+impl<R: RngCore> VariableNeighborhoodSearch<R> {
+    /// Replaces the random number generator, other settings are kept.
+    #[must_use]
+    pub fn with_rng<T: RngCore>(self, rng: T) -> VariableNeighborhoodSearch<T> {
+        VariableNeighborhoodSearch {
+            budget: self.budget,
+            sample_size: self.sample_size,
+            shake_strength: self.shake_strength,
+            strategy: self.strategy,
+            tardy_order: self.tardy_order,
+            rng,
+        }
+    }
+
+    /// Sets the fraction of tasks moved randomly when shaking the schedule.
+    /// At least one task is always moved.
+    #[must_use]
+    pub const fn with_shake_strength(mut self, shake_strength: f64) -> Self {
+        self.shake_strength = shake_strength;
+        self
+    }
+
+    /// Sets the strategy of accepting moves in the local search.
+    #[must_use]
+    pub const fn with_strategy(mut self, strategy: LocalSearchStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Sets the order in which tardy tasks are placed again after the moves.
+    #[must_use]
+    pub const fn with_tardy_order(mut self, order: TardyOrder) -> Self {
+        self.tardy_order = order;
+        self
+    }
+
+    /// Performs the search until the budget is exhausted.
+    fn search<'a>(&mut self, initial: ScheduleBuilder<'a>, budget: Budget) -> Schedule<'a> {
+        let initial = initial.with_tardy_order(self.tardy_order);
+        let instance = initial.instance();
         if instance.tasks.is_empty() {
-            return Schedule::new(instance);
+            return initial.into();
         }
 
-        let mut schedule = neighborhood_search(super::list::schedule(instance));
+        let start = Instant::now();
+        let rng: &mut dyn RngCore = &mut self.rng;
+        let sampling = self.sample_size.map(|size| (size, rng));
+        let mut schedule = neighborhood_search(initial, self.strategy, sampling);
         let mut best_score = schedule.calculate_score();
 
-        for _ in 0..self.iterations {
-            let mut new_schedule = schedule.clone();
-
-            for _ in 0..(instance.tasks.len() / 20).max(1) {
-                let task = self.rng.gen_range(0..instance.tasks.len());
-                let task_machine = new_schedule.get_schedule(task).map(|info| info.processor);
-
-                new_schedule.reorganize_schedule(|machines, tardy_tasks| {
-                    let mut machine_fixings = Vec::with_capacity(2);
-
-                    match task_machine {
-                        Some(machine) => {
-                            if let Some(pos) = machines[machine].iter().position(|&id| id == task) {
-                                machine_fixings.push((machine, pos));
-                            }
-                            machines[machine].retain(|&id| id != task);
-                        }
-                        None => tardy_tasks.retain(|&id| id != task),
-                    }
+        #[allow(clippy::cast_precision_loss)]
+        let shake_moves = (instance.tasks.len() as f64 * self.shake_strength).ceil();
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let shake_moves = (shake_moves as usize).max(1);
 
-                    let new_machine = self.rng.gen_range(0..instance.processors);
-                    let new_position = self.rng.gen_range(0..=machines[new_machine].len());
-                    machines[new_machine].insert(new_position, task);
+        let mut iteration = 0;
 
-                    match task_machine.filter(|&machine| machine == new_machine) {
-                        Some(_) => machine_fixings[0].1 = new_position.min(machine_fixings[0].1),
-                        None => machine_fixings.push((new_machine, new_position)),
-                    }
+        while !budget.is_exhausted(iteration, start) {
+            iteration += 1;
+            let mut new_schedule = schedule.clone();
 
-                    (machine_fixings, vec![])
-                });
+            for _ in 0..shake_moves {
+                let task = self.rng.gen_range(0..instance.tasks.len());
+                relocate_task(&mut new_schedule, &mut self.rng, task);
             }
 
-            let new_schedule = neighborhood_search(new_schedule);
+            let rng: &mut dyn RngCore = &mut self.rng;
+            let sampling = self.sample_size.map(|size| (size, rng));
+            let new_schedule = neighborhood_search(new_schedule, self.strategy, sampling);
             let new_score = new_schedule.calculate_score();
 
             if new_score > best_score {
@@ -406,10 +886,59 @@ impl Scheduler for VariableNeighborhoodSearch {
 
         schedule.into()
     }
+}
+
+impl Default for VariableNeighborhoodSearch {
+    fn default() -> Self {
+        Self {
+            budget: Budget::Iterations(200),
+            sample_size: None,
+            shake_strength: 0.05,
+            strategy: LocalSearchStrategy::BestImprovement,
+            tardy_order: TardyOrder::Weighted,
+            rng: StdRng::from_rng(rand::thread_rng()).unwrap_or_else(|_| StdRng::seed_from_u64(0)),
+        }
+    }
+}
+
+impl<R: RngCore + SeedableRng> Scheduler for VariableNeighborhoodSearch<R> {
+    fn schedule<'a>(&mut self, instance: &'a Instance) -> Schedule<'a> {
+        self.search(super::list::schedule(instance), self.budget)
+    }
+
+    fn schedule_with_budget<'a>(
+        &mut self,
+        instance: &'a Instance,
+        budget: Duration,
+    ) -> Schedule<'a> {
+        self.search(super::list::schedule(instance), Budget::TimeLimit(budget))
+    }
 
     fn name(&self) -> &'static str {
         "VNS"
     }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.rng = R::seed_from_u64(seed);
+    }
+
+    fn is_randomized(&self) -> bool {
+        true
+    }
+}
+
+impl<R: RngCore + SeedableRng> Improver for VariableNeighborhoodSearch<R> {
+    fn improve<'a>(&mut self, schedule: ScheduleBuilder<'a>) -> Schedule<'a> {
+        self.search(schedule, self.budget)
+    }
+
+    fn improve_with_budget<'a>(
+        &mut self,
+        schedule: ScheduleBuilder<'a>,
+        budget: Duration,
+    ) -> Schedule<'a> {
+        self.search(schedule, Budget::TimeLimit(budget))
+    }
 }
 
 #[allow(unsafe_code)]
@@ -419,11 +948,131 @@ static INSTANCE: fn() -> Box<dyn Scheduler> = || Box::new(VariableNeighborhoodSe
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::algo::test::ScriptedRng;
+    use crate::core::Task;
     use crate::data::samples;
 
+    #[test]
+    fn relocation_should_follow_rng() {
+        let task = Task::new(1, 1);
+        let instance = Instance::new_no_conflict(2, 3, vec![task; 3]);
+        let mut schedule = ScheduleBuilder::new(&instance);
+        schedule.schedule(0, 0, 0);
+        schedule.schedule(1, 0, 1);
+        schedule.schedule(2, 1, 0);
+
+        // Every index is drawn from two values, the first pair picks the second machine.
+        let mut rng = ScriptedRng::new(vec![1 << 31, 1 << 31, 0, 0]);
+        relocate_task(&mut schedule, &mut rng, 2);
+        assert_eq!(schedule.position(2), Some((1, 0)));
+        assert_eq!(schedule.position(1), Some((1, 1)));
+        assert_eq!(schedule.calculate_score(), 3);
+
+        let mut vns = VariableNeighborhoodSearch::new(5, 0).with_rng(ScriptedRng::new(vec![0]));
+        assert!(vns.schedule(&instance).verify());
+    }
+
     #[test]
     fn test_vns() {
         let mut vns = VariableNeighborhoodSearch::new(10, 0);
         assert!(samples(0, &mut vns).is_ok());
     }
+
+    #[test]
+    fn test_vns_first_improvement() {
+        let strategy = LocalSearchStrategy::FirstImprovement;
+        let mut vns = VariableNeighborhoodSearch::new(10, 0).with_strategy(strategy);
+        assert!(samples(0, &mut vns).is_ok());
+
+        let mut vns = VariableNeighborhoodSearch::new_sampled(10, 20, 0).with_strategy(strategy);
+        assert!(samples(0, &mut vns).is_ok());
+    }
+
+    #[test]
+    fn test_vns_shake_strength() {
+        let mut vns = VariableNeighborhoodSearch::new(10, 0).with_shake_strength(0.3);
+        assert!(samples(0, &mut vns).is_ok());
+    }
+
+    #[test]
+    fn eject_chain_should_reinsert_ejected_task_on_other_machine() {
+        let task = |weight| crate::core::Task::new(2, weight);
+        // Tardy task 4 conflicts with task 0, which fits on machine 1 only before task 3.
+        let tasks = vec![task(5), task(5), task(5), task(1), task(3)];
+        let conflicts = vec![crate::core::Conflict::new(0, 4)];
+        let instance = Instance::new(2, 4, tasks, conflicts);
+
+        let mut schedule = ScheduleBuilder::new(&instance);
+        schedule.schedule(0, 0, 0);
+        schedule.schedule(1, 2, 0);
+        schedule.schedule(2, 0, 1);
+        schedule.schedule(3, 2, 1);
+        schedule.tardy(4);
+
+        let moves = eject_chain(&schedule);
+        let candidates: Vec<_> = moves.map(|next| next.apply(&schedule)).collect();
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].0, vec![(0, 0), (1, 1)]);
+
+        let candidate = &candidates[0].1;
+        assert_eq!(candidate.calculate_score(), 18);
+        assert_eq!(candidate.position(0), Some((1, 1)));
+        assert_eq!(candidate.position(4), Some((0, 0)));
+        assert_eq!(candidate.position(3), None);
+
+        // Replacing the task without reinserting it loses its weight.
+        let (_, replaced) = replace_tardy(&schedule, (0, 0), 0, 0);
+        assert_eq!(replaced.calculate_score(), 14);
+    }
+
+    #[test]
+    fn best_move_should_match_sequential_search() -> anyhow::Result<()> {
+        for entry in std::fs::read_dir("samples")? {
+            let path = entry?.path();
+            let mut reader = std::io::BufReader::new(std::fs::File::open(path)?);
+            let instance: Instance = crate::data::deserialize(&mut reader)?;
+            let schedule = super::super::list::schedule(&instance);
+
+            for factory in NEIGHBORHOODS {
+                let moves = factory(&schedule).map(|next| next.apply(&schedule));
+                let sequential = best_improvement(&schedule, moves).map(Schedule::from);
+                let parallel = best_move(&schedule, factory(&schedule).collect());
+                assert_eq!(parallel.map(Schedule::from), sequential);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_vns_time_limit() {
+        let mut vns = VariableNeighborhoodSearch::with_time_limit(Duration::from_millis(50), 0);
+        assert!(samples(0, &mut vns).is_ok());
+
+        let mut vns = VariableNeighborhoodSearch::with_time_limit(Duration::ZERO, 0);
+        assert!(samples(0, &mut vns).is_ok());
+    }
+
+    #[test]
+    fn test_vns_sampled() -> anyhow::Result<()> {
+        let mut vns = VariableNeighborhoodSearch::new_sampled(10, 20, 0);
+        assert!(samples(0, &mut vns).is_ok());
+
+        for entry in std::fs::read_dir("samples")? {
+            let path = entry?.path();
+            let mut reader = std::io::BufReader::new(std::fs::File::open(path)?);
+            let instance: Instance = crate::data::deserialize(&mut reader)?;
+
+            let initial = super::super::list::schedule(&instance);
+            let initial_score = initial.calculate_score();
+            let mut rng = StdRng::seed_from_u64(0);
+            let strategy = LocalSearchStrategy::BestImprovement;
+            let schedule = neighborhood_search(initial, strategy, Some((20, &mut rng)));
+
+            assert!(schedule.calculate_score() >= initial_score);
+            assert!(Schedule::from(schedule).verify());
+        }
+
+        Ok(())
+    }
 }
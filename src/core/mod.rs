@@ -1,21 +1,119 @@
+mod preemptive;
 mod problem;
 mod solution;
+mod trace;
 mod util;
 
+pub use preemptive::*;
 pub use problem::*;
 pub use solution::*;
+pub use trace::*;
 pub use util::*;
 
+use std::time::Duration;
+
 /// Schedules the tasks of an instance.
 pub trait Scheduler {
     /// Schedules the tasks of the given instance.
     fn schedule<'a>(&mut self, instance: &'a Instance) -> Schedule<'a>;
 
+    /// Schedules the tasks of the given instance, returning an error if the scheduler fails.
+    /// Schedulers relying on external solvers override it, others never fail by default.
+    ///
+    /// # Errors
+    /// - If the scheduler fails to produce a schedule.
+    fn try_schedule<'a>(&mut self, instance: &'a Instance) -> anyhow::Result<Schedule<'a>> {
+        Ok(self.schedule(instance))
+    }
+
+    /// Schedules the tasks of the given instance and reports the placement decisions.
+    /// By default decisions are read from the final schedule without reasons.
+    fn schedule_traced<'a>(&mut self, instance: &'a Instance) -> (Schedule<'a>, Vec<Decision>) {
+        let schedule = self.schedule(instance);
+        let decisions = Decision::from_schedule(&schedule);
+        (schedule, decisions)
+    }
+
+    /// Schedules the tasks of the given instance and reports the placement decisions,
+    /// returning an error if the scheduler fails.
+    /// Schedulers overriding [`Scheduler::try_schedule`] override it as well.
+    ///
+    /// # Errors
+    /// - If the scheduler fails to produce a schedule.
+    fn try_schedule_traced<'a>(
+        &mut self,
+        instance: &'a Instance,
+    ) -> anyhow::Result<(Schedule<'a>, Vec<Decision>)> {
+        Ok(self.schedule_traced(instance))
+    }
+
+    /// Schedules the tasks of the given instance, running for about `budget`.
+    /// Anytime schedulers keep improving the schedule until the budget is spent,
+    /// others ignore it by default.
+    fn schedule_with_budget<'a>(
+        &mut self,
+        instance: &'a Instance,
+        _budget: Duration,
+    ) -> Schedule<'a> {
+        self.schedule(instance)
+    }
+
+    /// Schedules the tasks of the given instance, running for about `budget`,
+    /// returning an error if the scheduler fails.
+    /// Schedulers overriding [`Scheduler::try_schedule`] override it as well.
+    ///
+    /// # Errors
+    /// - If the scheduler fails to produce a schedule.
+    fn try_schedule_with_budget<'a>(
+        &mut self,
+        instance: &'a Instance,
+        budget: Duration,
+    ) -> anyhow::Result<Schedule<'a>> {
+        Ok(self.schedule_with_budget(instance, budget))
+    }
+
+    /// Reseeds the random number generator of the scheduler.
+    /// Deterministic schedulers ignore it.
+    fn set_seed(&mut self, _seed: u64) {}
+
+    /// Returns whether the schedule depends on the seed of the scheduler.
+    /// Randomized schedulers override it, so deterministic ones are not run repeatedly.
+    fn is_randomized(&self) -> bool {
+        false
+    }
+
     /// Returns whether the scheduler handles non-unit tasks.
+    #[deprecated(note = "use `Scheduler::supports`, which checks the instance")]
     fn non_unit(&self) -> bool {
         true
     }
 
+    /// Returns whether the scheduler can schedule the instance.
+    /// Schedulers restricted to some instances override it. By default every instance is
+    /// supported, unless the scheduler still declares it does not handle non-unit tasks.
+    fn supports(&self, instance: &Instance) -> bool {
+        #[allow(deprecated)]
+        let non_unit = self.non_unit();
+        non_unit || instance.has_equal_times()
+    }
+
     /// Returns the name of the scheduler.
     fn name(&self) -> &'static str;
 }
+
+/// Improves a schedule produced by another scheduler.
+/// Local search algorithms implement it, so they can polish the result of any scheduler.
+pub trait Improver: Scheduler {
+    /// Improves the given schedule.
+    fn improve<'a>(&mut self, schedule: ScheduleBuilder<'a>) -> Schedule<'a>;
+
+    /// Improves the given schedule, running for about `budget`.
+    /// By default the budget is ignored.
+    fn improve_with_budget<'a>(
+        &mut self,
+        schedule: ScheduleBuilder<'a>,
+        _budget: Duration,
+    ) -> Schedule<'a> {
+        self.improve(schedule)
+    }
+}
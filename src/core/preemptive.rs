@@ -0,0 +1,256 @@
+use super::{Instance, Schedule};
+use serde::{Deserialize, Serialize};
+
+/// Schedule info for a preemptive task.
+/// Contains the processor and the `(start, length)` segments of the task in order of start time.
+#[non_exhaustive]
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Serialize, PartialEq)]
+pub struct PreemptiveScheduleInfo {
+    pub processor: usize,
+    pub segments: Vec<(u64, u64)>,
+}
+
+impl PreemptiveScheduleInfo {
+    /// Creates new preemptive schedule info.
+    #[must_use]
+    pub const fn new(processor: usize, segments: Vec<(u64, u64)>) -> Self {
+        Self {
+            processor,
+            segments,
+        }
+    }
+
+    /// Returns the time at which the last segment finishes.
+    #[must_use]
+    pub fn completion(&self) -> u64 {
+        let ends = self.segments.iter().map(|&(start, length)| start + length);
+        ends.max().unwrap_or_default()
+    }
+}
+
+/// A preemptive schedule. Tasks may be split into segments on the same machine.
+#[derive(Clone, Debug, Eq, Serialize, PartialEq)]
+pub struct PreemptiveSchedule<'a> {
+    #[serde(skip)]
+    instance: &'a Instance,
+    schedule: Vec<Option<PreemptiveScheduleInfo>>,
+}
+
+impl<'a> PreemptiveSchedule<'a> {
+    /// Creates a new preemptive schedule.
+    #[must_use]
+    pub fn new(instance: &'a Instance) -> Self {
+        PreemptiveSchedule {
+            instance,
+            schedule: vec![None; instance.tasks.len()],
+        }
+    }
+
+    /// Returns the scheduled instance.
+    #[must_use]
+    pub const fn instance(&self) -> &'a Instance {
+        self.instance
+    }
+
+    /// Schedule info for a task.
+    pub fn schedule(&mut self, task: usize, schedule_info: PreemptiveScheduleInfo) {
+        self.schedule[task] = Some(schedule_info);
+    }
+
+    /// Removes the schedule info for a task.
+    pub fn remove_schedule(&mut self, task: usize) {
+        self.schedule[task] = None;
+    }
+
+    /// Get the schedule info for a task.
+    #[must_use]
+    pub fn get_schedule(&self, task: usize) -> Option<&PreemptiveScheduleInfo> {
+        self.schedule[task].as_ref()
+    }
+
+    /// Calculates the score of the schedule.
+    #[must_use]
+    pub fn calculate_score(&self) -> u64 {
+        let mut score = 0;
+        for (info, task) in self.schedule.iter().zip(&self.instance.tasks) {
+            if let Some(info) = info {
+                if info.completion() <= self.instance.deadline {
+                    score += task.weight;
+                }
+            }
+        }
+        score
+    }
+
+    /// Checks if schedule is valid.
+    ///
+    /// Tasks must run on eligible machines. Segments of a task must be non-empty, ordered,
+    /// sum up to its processing time and lie between its release time and the deadline.
+    /// No two segments on a machine may overlap, and neither may segments of conflicting tasks.
+    #[must_use]
+    pub fn verify(&self) -> bool {
+        let mut machines = vec![Vec::new(); self.instance.processors];
+
+        for (id, info) in self.schedule.iter().enumerate() {
+            let Some(info) = info else {
+                continue;
+            };
+            let task = &self.instance.tasks[id];
+            let Some(machine) = machines.get_mut(info.processor) else {
+                return false;
+            };
+            if !task.is_eligible(info.processor) {
+                return false;
+            }
+
+            let mut free = task.release;
+            let mut total = 0;
+            for &(start, length) in &info.segments {
+                if length == 0 || start < free {
+                    return false;
+                }
+                free = start + length;
+                total += length;
+                machine.push((start, free));
+            }
+            if total != task.time || free > self.instance.deadline {
+                return false;
+            }
+        }
+
+        for mut machine in machines {
+            machine.sort_unstable();
+            if machine.windows(2).any(|pair| pair[1].0 < pair[0].1) {
+                return false;
+            }
+        }
+
+        for (id, info) in self.schedule.iter().enumerate() {
+            let Some(info) = info else {
+                continue;
+            };
+            let conflicts = self.instance.graph.conflicts(id).iter();
+            let conflicts = conflicts.filter(|&&other| other > id);
+            let mut others = conflicts.filter_map(|&other| self.schedule[other].as_ref());
+            if others.any(|other| overlap(&info.segments, &other.segments)) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// Checks if any segments of two ordered lists overlap.
+fn overlap(first: &[(u64, u64)], second: &[(u64, u64)]) -> bool {
+    let (mut i, mut j) = (0, 0);
+    while i < first.len() && j < second.len() {
+        let (a, b) = (first[i], second[j]);
+        if a.0 < b.0 + b.1 && b.0 < a.0 + a.1 {
+            return true;
+        }
+        if a.0 + a.1 <= b.0 + b.1 {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    false
+}
+
+impl<'a> From<Schedule<'a>> for PreemptiveSchedule<'a> {
+    /// Every task of a non-preemptive schedule becomes a single segment.
+    /// Tasks finishing after the deadline are left unscheduled.
+    fn from(value: Schedule<'a>) -> Self {
+        let instance = value.instance();
+        let mut schedule = PreemptiveSchedule::new(instance);
+        for (id, task) in instance.tasks.iter().enumerate() {
+            if let Some(info) = value.get_schedule(id) {
+                if task.fits_before(info.start, instance.deadline) {
+                    let segments = vec![(info.start, task.time)];
+                    schedule.schedule(id, PreemptiveScheduleInfo::new(info.processor, segments));
+                }
+            }
+        }
+        schedule
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::core::{Conflict, ScheduleInfo, Task};
+
+    fn task(time: u64) -> Task {
+        Task::new(time, 1)
+    }
+
+    #[test]
+    fn conflicting_segments_should_be_rejected() {
+        let instance = Instance::new(2, 10, vec![task(4), task(2)], vec![Conflict::new(0, 1)]);
+
+        let mut schedule = PreemptiveSchedule::new(&instance);
+        schedule.schedule(0, PreemptiveScheduleInfo::new(0, vec![(0, 2), (4, 2)]));
+        schedule.schedule(1, PreemptiveScheduleInfo::new(1, vec![(2, 2)]));
+        assert!(schedule.verify());
+        assert_eq!(schedule.calculate_score(), 2);
+
+        schedule.schedule(1, PreemptiveScheduleInfo::new(1, vec![(3, 2)]));
+        assert!(!schedule.verify());
+    }
+
+    #[test]
+    fn invalid_segments_should_be_rejected() {
+        let instance = Instance::new_no_conflict(1, 6, vec![task(4), task(2)]);
+
+        let mut schedule = PreemptiveSchedule::new(&instance);
+        schedule.schedule(0, PreemptiveScheduleInfo::new(0, vec![(0, 1), (3, 3)]));
+        schedule.schedule(1, PreemptiveScheduleInfo::new(0, vec![(1, 2)]));
+        assert!(schedule.verify());
+
+        // Segments do not sum up to the processing time.
+        schedule.schedule(0, PreemptiveScheduleInfo::new(0, vec![(0, 1), (3, 2)]));
+        assert!(!schedule.verify());
+
+        // Segments overlap another task on the machine.
+        schedule.schedule(0, PreemptiveScheduleInfo::new(0, vec![(0, 2), (3, 2)]));
+        assert!(!schedule.verify());
+
+        // The last segment finishes after the deadline.
+        schedule.schedule(0, PreemptiveScheduleInfo::new(0, vec![(0, 1), (4, 3)]));
+        assert!(!schedule.verify());
+    }
+
+    #[test]
+    fn ineligible_machine_should_be_rejected() {
+        let restricted = Task {
+            eligible: Some(vec![1]),
+            ..task(2)
+        };
+        let instance = Instance::new_no_conflict(2, 10, vec![restricted]);
+
+        let mut schedule = PreemptiveSchedule::new(&instance);
+        schedule.schedule(0, PreemptiveScheduleInfo::new(1, vec![(0, 1), (2, 1)]));
+        assert!(schedule.verify());
+
+        schedule.schedule(0, PreemptiveScheduleInfo::new(0, vec![(0, 1), (2, 1)]));
+        assert!(!schedule.verify());
+    }
+
+    #[test]
+    fn schedule_should_convert_to_single_segments() {
+        let instance = Instance::new_no_conflict(2, 10, vec![task(4), task(2)]);
+
+        let mut schedule = Schedule::new(&instance);
+        schedule.schedule(0, ScheduleInfo::new(1, 0));
+        schedule.schedule(1, ScheduleInfo::new(0, 1));
+        let preemptive = PreemptiveSchedule::from(schedule);
+
+        assert!(preemptive.verify());
+        assert_eq!(preemptive.calculate_score(), 2);
+        assert_eq!(
+            preemptive.get_schedule(0),
+            Some(&PreemptiveScheduleInfo::new(0, vec![(1, 4)]))
+        );
+    }
+}
@@ -1,31 +1,221 @@
+use super::weight_density_comparator;
+use crate::cast_u64;
 use ahash::{HashSet, HashSetExt};
-use serde::{Deserialize, Serialize};
+use serde::de::{SeqAccess, Visitor};
+use serde::ser::{SerializeStruct, SerializeTuple};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
 use std::sync::LazyLock;
+use thiserror::Error;
 
-/// A task. Contains the processing time and weight of the task.
-#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Serialize, PartialEq)]
+/// A task. Contains the processing time, weight and release time of the task.
+/// The release time is the earliest time the task can start, it defaults to 0.
+///
+/// Eligible processors restrict the machines the task may run on, `None` allows all of them.
+/// In the custom format they are written as one comma-separated field after the release time,
+/// in JSON as an array.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq)]
 pub struct Task {
     pub time: u64,
     pub weight: u64,
+    #[serde(default, deserialize_with = "deserialize_release")]
+    pub release: u64,
+    #[serde(default, deserialize_with = "deserialize_eligible")]
+    pub eligible: Option<Vec<usize>>,
+}
+
+impl Task {
+    /// Creates a task available from the start on every processor.
+    /// Other fields can be set with the struct update syntax.
+    #[must_use]
+    pub const fn new(time: u64, weight: u64) -> Self {
+        Self {
+            time,
+            weight,
+            release: 0,
+            eligible: None,
+        }
+    }
+
+    /// Returns whether the task may run on the processor.
+    #[must_use]
+    pub fn is_eligible(&self, processor: usize) -> bool {
+        self.eligible
+            .as_ref()
+            .map_or(true, |eligible| eligible.contains(&processor))
+    }
+
+    /// Returns the time the task completes when started at `start`.
+    /// It saturates at `u64::MAX` for huge start times.
+    #[must_use]
+    pub const fn completion(&self, start: u64) -> u64 {
+        start.saturating_add(self.time)
+    }
+
+    /// Returns whether the task started at `start` completes by the deadline.
+    /// A task may complete exactly at the deadline, one completing after `u64::MAX` never fits.
+    #[must_use]
+    pub const fn fits_before(&self, start: u64, deadline: u64) -> bool {
+        match start.checked_add(self.time) {
+            Some(end) => end <= deadline,
+            None => false,
+        }
+    }
+}
+
+impl Serialize for Task {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // Fields are positional in the custom format, so the release time is written
+        // whenever eligible processors follow it.
+        let release = self.release != 0 || self.eligible.is_some();
+        let len = 2 + usize::from(release) + usize::from(self.eligible.is_some());
+        let readable = serializer.is_human_readable();
+        let mut task = serializer.serialize_struct("Task", len)?;
+        task.serialize_field("time", &self.time)?;
+        task.serialize_field("weight", &self.weight)?;
+        if release {
+            task.serialize_field("release", &self.release)?;
+        }
+        match &self.eligible {
+            Some(eligible) if readable => task.serialize_field("eligible", eligible)?,
+            Some(eligible) => {
+                let eligible: Vec<_> = eligible.iter().map(ToString::to_string).collect();
+                task.serialize_field("eligible", &eligible.join(","))?;
+            }
+            None => {}
+        }
+        task.end()
+    }
+}
+
+/// Release time is optional, missing value means the task is available from the start.
+fn deserialize_release<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    Option::<u64>::deserialize(deserializer).map(Option::unwrap_or_default)
+}
+
+/// Eligible processors are optional, see [`Eligible`] for their format.
+fn deserialize_eligible<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Vec<usize>>, D::Error> {
+    let eligible = Option::<Eligible>::deserialize(deserializer)?;
+    Ok(eligible.map(|Eligible(eligible)| eligible))
+}
+
+/// Eligible processors of a task. The custom format stores them as a comma-separated list,
+/// human-readable formats like JSON as an array, a comma-separated string is accepted too.
+struct Eligible(Vec<usize>);
+
+impl<'de> Deserialize<'de> for Eligible {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct EligibleVisitor;
+
+        impl<'de> Visitor<'de> for EligibleVisitor {
+            type Value = Eligible;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("processor indices")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Eligible, E> {
+                let eligible: Result<_, _> = value.split(',').map(str::parse).collect();
+                eligible.map(Eligible).map_err(E::custom)
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Eligible, A::Error> {
+                let mut eligible = Vec::new();
+                while let Some(processor) = seq.next_element()? {
+                    eligible.push(processor);
+                }
+                Ok(Eligible(eligible))
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(EligibleVisitor)
+        } else {
+            deserializer.deserialize_string(EligibleVisitor)
+        }
+    }
 }
 
 /// A conflict between two tasks described by their indices.
-#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Serialize, PartialEq)]
-pub struct Conflict(usize, usize);
+///
+/// Hard conflicts forbid the tasks to overlap. Soft conflicts have a penalty instead,
+/// which is subtracted by [`Schedule::calculate_score_with_penalties`] if the tasks overlap.
+/// The penalty is serialized as an optional third value, so hard conflicts keep their format.
+/// Task indices are stored in increasing order, so `(a, b)` and `(b, a)` are equal conflicts.
+///
+/// [`Schedule::calculate_score_with_penalties`]: super::Schedule::calculate_score_with_penalties
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Conflict(usize, usize, Option<u64>);
 
 impl Conflict {
-    /// Creates a new conflict between two tasks.
+    /// Creates a new hard conflict between two tasks.
     #[must_use]
     pub const fn new(first: usize, second: usize) -> Self {
-        Self(first, second)
+        Self::normalized(first, second, None)
+    }
+
+    /// Creates a new soft conflict between two tasks, overlapping them costs `penalty`.
+    #[must_use]
+    pub const fn soft(first: usize, second: usize, penalty: u64) -> Self {
+        Self::normalized(first, second, Some(penalty))
+    }
+
+    /// Creates a conflict with task indices in increasing order.
+    const fn normalized(first: usize, second: usize, penalty: Option<u64>) -> Self {
+        if first <= second {
+            Self(first, second, penalty)
+        } else {
+            Self(second, first, penalty)
+        }
+    }
+}
+
+impl Serialize for Conflict {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut tuple = serializer.serialize_tuple(if self.2.is_some() { 3 } else { 2 })?;
+        tuple.serialize_element(&self.0)?;
+        tuple.serialize_element(&self.1)?;
+        if let Some(penalty) = self.2 {
+            tuple.serialize_element(&penalty)?;
+        }
+        tuple.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Conflict {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ConflictVisitor;
+
+        impl<'de> Visitor<'de> for ConflictVisitor {
+            type Value = Conflict;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("two task indices and an optional penalty")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Conflict, A::Error> {
+                let missing = || serde::de::Error::invalid_length(0, &self);
+                let first = seq.next_element()?.ok_or_else(missing)?;
+                let second = seq.next_element()?.ok_or_else(missing)?;
+                // The penalty is missing at the end of a line or a JSON array.
+                let penalty = seq.next_element::<Option<u64>>()?.flatten();
+                Ok(Conflict::normalized(first, second, penalty))
+            }
+        }
+
+        deserializer.deserialize_tuple(3, ConflictVisitor)
     }
 }
 
 /// A conflict graph. Contains an edge for every pair of tasks that conflict.
+/// Soft conflicts are kept apart from the edges, so schedulers are free to overlap them.
 #[derive(Clone, Debug, Deserialize, Eq, Serialize, PartialEq)]
 #[serde(from = "Vec<Conflict>", into = "Vec<Conflict>")]
 pub struct ConflictGraph {
     edges: Vec<HashSet<usize>>,
+    penalties: BTreeMap<(usize, usize), u64>,
 }
 
 impl ConflictGraph {
@@ -34,7 +224,7 @@ impl ConflictGraph {
     pub fn are_conflicted(&self, first: usize, second: usize) -> bool {
         self.edges
             .get(first)
-            .map_or(false, |conflicts| conflicts.contains(&second))
+            .is_some_and(|conflicts| conflicts.contains(&second))
     }
 
     /// Returns the conflicts of the given task.
@@ -44,14 +234,185 @@ impl ConflictGraph {
 
         self.edges.get(task).unwrap_or(&EMPTY)
     }
+
+    /// Returns the hard conflicts as `(first, second)` with `first < second`, each pair once.
+    pub fn pairs(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let edges = self.edges.iter().enumerate();
+        edges.flat_map(|(from, adjacent)| {
+            let adjacent = adjacent.iter().filter(move |&&to| to > from);
+            adjacent.map(move |&to| (from, to))
+        })
+    }
+
+    /// Returns the soft conflicts as `(first, second, penalty)` with `first < second`.
+    pub fn soft_conflicts(&self) -> impl Iterator<Item = (usize, usize, u64)> + '_ {
+        let penalties = self.penalties.iter();
+        penalties.map(|(&(first, second), &penalty)| (first, second, penalty))
+    }
+
+    /// Returns the number of tasks conflicting with the given task.
+    #[must_use]
+    pub fn degree(&self, task: usize) -> usize {
+        self.conflicts(task).len()
+    }
+
+    /// Returns the number of conflicts in the graph.
+    #[must_use]
+    pub fn edge_count(&self) -> usize {
+        self.edges.iter().map(HashSet::len).sum::<usize>() / 2
+    }
+
+    /// Returns the ratio of conflicts to all possible pairs of `tasks` tasks.
+    /// Graphs with less than two tasks have density 0.
+    #[must_use]
+    pub fn density(&self, tasks: usize) -> f64 {
+        let pairs = tasks * tasks.saturating_sub(1) / 2;
+        if pairs == 0 {
+            return 0.0;
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        let density = self.edge_count() as f64 / pairs as f64;
+        density
+    }
+
+    /// Groups tasks into connected components of the graph using union-find.
+    /// Isolated tasks form singleton components. Components are ordered by their
+    /// smallest task and tasks inside a component are sorted.
+    #[must_use]
+    pub fn connected_components(&self, task_count: usize) -> Vec<Vec<usize>> {
+        fn find(parent: &mut [usize], mut task: usize) -> usize {
+            while parent[task] != task {
+                parent[task] = parent[parent[task]];
+                task = parent[task];
+            }
+            task
+        }
+
+        let mut parent: Vec<_> = (0..task_count).collect();
+        let mut size = vec![1; task_count];
+
+        for (from_vertex, adjacent_vertices) in self.edges.iter().enumerate().take(task_count) {
+            for &to_vertex in adjacent_vertices.iter().filter(|&&to| to < task_count) {
+                let mut first = find(&mut parent, from_vertex);
+                let mut second = find(&mut parent, to_vertex);
+
+                if first != second {
+                    if size[first] < size[second] {
+                        std::mem::swap(&mut first, &mut second);
+                    }
+                    parent[second] = first;
+                    size[first] += size[second];
+                }
+            }
+        }
+
+        let mut components: Vec<Vec<usize>> = Vec::new();
+        let mut component_of_root: Vec<Option<usize>> = vec![None; task_count];
+
+        for task in 0..task_count {
+            let root = find(&mut parent, task);
+            if let Some(component) = component_of_root[root] {
+                components[component].push(task);
+            } else {
+                component_of_root[root] = Some(components.len());
+                components.push(vec![task]);
+            }
+        }
+
+        components
+    }
+
+    /// Estimates the chromatic number of the graph using greedy coloring.
+    /// Tasks are colored in order of decreasing degree (Welsh-Powell).
+    /// The result is an upper bound, not the exact chromatic number.
+    #[must_use]
+    pub fn greedy_coloring(&self, tasks: usize) -> usize {
+        let mut order: Vec<_> = (0..tasks).collect();
+        order.sort_by_key(|&task| std::cmp::Reverse(self.conflicts(task).len()));
+
+        let mut colors = vec![None; tasks];
+        let mut used = 0;
+
+        for task in order {
+            let taken: HashSet<usize> = self
+                .conflicts(task)
+                .iter()
+                .filter_map(|&other| colors.get(other).copied().flatten())
+                .collect();
+            let color = (0..=taken.len()).find(|color| !taken.contains(color));
+            let color = color.unwrap_or_default();
+            colors[task] = Some(color);
+            used = used.max(color + 1);
+        }
+
+        used
+    }
+
+    /// Finds a clique using a greedy heuristic over tasks ordered by decreasing degree.
+    /// The size of the clique is a lower bound of the chromatic number.
+    #[must_use]
+    pub fn greedy_clique(&self, tasks: usize) -> Vec<usize> {
+        let mut order: Vec<_> = (0..tasks).collect();
+        order.sort_by_key(|&task| std::cmp::Reverse(self.conflicts(task).len()));
+
+        let mut clique: Vec<usize> = Vec::new();
+        for task in order {
+            if clique.iter().all(|&other| self.are_conflicted(task, other)) {
+                clique.push(task);
+            }
+        }
+
+        clique
+    }
+
+    /// Returns the total processing time of a heavy set of pairwise conflicting tasks.
+    ///
+    /// Such tasks run one after another, so the time is a lower bound of the makespan.
+    /// A path of conflicts is not enough, its non-adjacent tasks may overlap. The set is
+    /// grown greedily from every task by adding the longest tasks conflicting with all so far.
+    #[must_use]
+    pub fn max_conflict_chain_time(&self, tasks: &[Task]) -> u64 {
+        let chain_time = |seed| {
+            let chain = self.heavy_clique(seed, tasks);
+            chain.iter().map(|&task| tasks[task].time).sum()
+        };
+
+        (0..tasks.len()).map(chain_time).max().unwrap_or_default()
+    }
+
+    /// Grows a clique from the seed task by adding the longest tasks conflicting with all so far.
+    fn heavy_clique(&self, seed: usize, tasks: &[Task]) -> Vec<usize> {
+        let mut candidates: Vec<_> = self.conflicts(seed).iter().copied().collect();
+        candidates.retain(|&task| task < tasks.len());
+        candidates.sort_unstable_by_key(|&task| (std::cmp::Reverse(tasks[task].time), task));
+
+        let mut clique = vec![seed];
+        for task in candidates {
+            if clique.iter().all(|&other| self.are_conflicted(task, other)) {
+                clique.push(task);
+            }
+        }
+        clique
+    }
 }
 
 impl From<Vec<Conflict>> for ConflictGraph {
     fn from(conflicts: Vec<Conflict>) -> Self {
         let mut edges = Vec::new();
+        let mut penalties = BTreeMap::new();
 
-        for conflict in conflicts {
-            while edges.len() <= conflict.0.max(conflict.1) {
+        // A task never overlaps itself, so self-conflicts are skipped.
+        for conflict in conflicts
+            .into_iter()
+            .filter(|conflict| conflict.0 != conflict.1)
+        {
+            if let Some(penalty) = conflict.2 {
+                penalties.insert((conflict.0, conflict.1), penalty);
+                continue;
+            }
+
+            while edges.len() <= conflict.1 {
                 edges.push(HashSet::new());
             }
 
@@ -59,29 +420,81 @@ impl From<Vec<Conflict>> for ConflictGraph {
             edges[conflict.1].insert(conflict.0);
         }
 
-        Self { edges }
+        Self { edges, penalties }
     }
 }
 
 impl From<ConflictGraph> for Vec<Conflict> {
     fn from(conflicts: ConflictGraph) -> Self {
-        let mut result = Self::new();
+        let pairs = conflicts.pairs();
+        let mut result: Self = pairs
+            .map(|(first, second)| Conflict::new(first, second))
+            .collect();
 
-        for (from_vertex, adjacent_vertices) in conflicts.edges.into_iter().enumerate() {
-            for to_vertex in adjacent_vertices {
-                if to_vertex > from_vertex {
-                    result.push(Conflict(from_vertex, to_vertex));
-                }
-            }
-        }
+        let penalties = conflicts.penalties.into_iter();
+        result.extend(
+            penalties.map(|((first, second), penalty)| Conflict::soft(first, second, penalty)),
+        );
 
         result
     }
 }
 
+/// FNV-1a hash of 64-bit words, fed in little-endian byte order.
+///
+/// Unlike [`std::hash::DefaultHasher`], its results do not change between Rust releases
+/// or platforms, so hashes and seeds derived from them are reproducible.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct StableHasher(u64);
+
+impl StableHasher {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    /// Creates a new hasher.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(Self::OFFSET)
+    }
+
+    /// Adds a word to the hash.
+    pub fn write(&mut self, value: u64) {
+        for byte in value.to_le_bytes() {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(Self::PRIME);
+        }
+    }
+
+    /// Returns the hash of the written words.
+    #[must_use]
+    pub const fn finish(self) -> u64 {
+        self.0
+    }
+}
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An error of an invalid instance.
+#[derive(Clone, Copy, Debug, Eq, Error, PartialEq)]
+pub enum InstanceError {
+    #[error("conflict refers to task {0}, but there are only {1} tasks")]
+    ConflictOutOfRange(usize, usize),
+    #[error("deadline must be positive")]
+    ZeroDeadline,
+    #[error("number of processors must be positive")]
+    ZeroProcessors,
+    #[error("task {0} is eligible for processor {1}, but there are only {2} processors")]
+    EligibleOutOfRange(usize, usize, usize),
+}
+
 /// An instance of the scheduling problem.
+/// Deserialized instances are validated with [`Instance::validate`].
 #[non_exhaustive]
 #[derive(Clone, Debug, Deserialize, Eq, Serialize, PartialEq)]
+#[serde(try_from = "InstanceData")]
 pub struct Instance {
     pub processors: usize,
     pub deadline: u64,
@@ -89,6 +502,30 @@ pub struct Instance {
     pub graph: ConflictGraph,
 }
 
+/// Instance before validation.
+#[derive(Deserialize)]
+struct InstanceData {
+    processors: usize,
+    deadline: u64,
+    tasks: Vec<Task>,
+    graph: ConflictGraph,
+}
+
+impl TryFrom<InstanceData> for Instance {
+    type Error = InstanceError;
+
+    fn try_from(data: InstanceData) -> Result<Self, Self::Error> {
+        let instance = Self {
+            processors: data.processors,
+            deadline: data.deadline,
+            tasks: data.tasks,
+            graph: data.graph,
+        };
+        instance.validate()?;
+        Ok(instance)
+    }
+}
+
 impl Instance {
     /// Creates a new instance of the scheduling problem without conflicts.
     #[must_use]
@@ -97,7 +534,10 @@ impl Instance {
             processors,
             deadline,
             tasks,
-            graph: ConflictGraph { edges: Vec::new() },
+            graph: ConflictGraph {
+                edges: Vec::new(),
+                penalties: BTreeMap::new(),
+            },
         }
     }
 
@@ -116,19 +556,342 @@ impl Instance {
             graph: ConflictGraph::from(conflicts),
         }
     }
+
+    /// Checks that the instance is well-formed.
+    ///
+    /// # Errors
+    /// - If a conflict refers to a task that does not exist.
+    /// - If the deadline or the number of processors is zero.
+    /// - If a task is eligible for a processor that does not exist.
+    pub fn validate(&self) -> Result<(), InstanceError> {
+        let tasks = self.tasks.len();
+
+        for (from_vertex, adjacent_vertices) in self.graph.edges.iter().enumerate() {
+            if from_vertex >= tasks && !adjacent_vertices.is_empty() {
+                return Err(InstanceError::ConflictOutOfRange(from_vertex, tasks));
+            }
+        }
+
+        if let Some((_, second, _)) = self
+            .graph
+            .soft_conflicts()
+            .find(|&(_, second, _)| second >= tasks)
+        {
+            return Err(InstanceError::ConflictOutOfRange(second, tasks));
+        }
+
+        if self.deadline == 0 {
+            return Err(InstanceError::ZeroDeadline);
+        }
+
+        if self.processors == 0 {
+            return Err(InstanceError::ZeroProcessors);
+        }
+
+        for (id, task) in self.tasks.iter().enumerate() {
+            let eligible = task.eligible.iter().flatten();
+            if let Some(&processor) = eligible.into_iter().find(|&&p| p >= self.processors) {
+                return Err(InstanceError::EligibleOutOfRange(
+                    id,
+                    processor,
+                    self.processors,
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Builds an instance over the given tasks only, keeping the deadline and processors.
+    /// Tasks are renumbered in the given order and conflicts with other tasks are dropped.
+    /// Returns the instance with the original index of every new task, to lift a solution back.
+    /// Repeated tasks are kept once.
+    ///
+    /// # Panics
+    /// - If a task index is out of range.
+    #[must_use]
+    pub fn subinstance(&self, tasks: &[usize]) -> (Self, Vec<usize>) {
+        let mut parts = self.subinstances(&[tasks]);
+        parts
+            .pop()
+            .unwrap_or_else(|| unreachable!("One part gives one instance"))
+    }
+
+    /// Builds an instance over every part of the tasks, like [`Self::subinstance`].
+    /// The conflicts are distributed to the parts in a single pass, so splitting the tasks into
+    /// many parts takes linear time. Tasks repeated in several parts are kept in the first one.
+    ///
+    /// # Panics
+    /// - If a task index is out of range.
+    #[must_use]
+    pub fn subinstances<P: AsRef<[usize]>>(&self, parts: &[P]) -> Vec<(Self, Vec<usize>)> {
+        let mut new_index = vec![None; self.tasks.len()];
+        let mut originals = Vec::with_capacity(parts.len());
+        for (part, tasks) in parts.iter().enumerate() {
+            let mut original = Vec::with_capacity(tasks.as_ref().len());
+            for &task in tasks.as_ref() {
+                if new_index[task].is_none() {
+                    new_index[task] = Some((part, original.len()));
+                    original.push(task);
+                }
+            }
+            originals.push(original);
+        }
+
+        // Conflicts are kept only if both tasks belong to the same part.
+        let pair = |first: usize, second: usize| {
+            let ((part, first), (other, second)) = (new_index[first]?, new_index[second]?);
+            (part == other).then_some((part, first, second))
+        };
+        let mut conflicts = vec![Vec::new(); parts.len()];
+        for (first, second) in self.graph.pairs() {
+            if let Some((part, first, second)) = pair(first, second) {
+                conflicts[part].push(Conflict::new(first, second));
+            }
+        }
+        for (first, second, penalty) in self.graph.soft_conflicts() {
+            if let Some((part, first, second)) = pair(first, second) {
+                conflicts[part].push(Conflict::soft(first, second, penalty));
+            }
+        }
+
+        let parts = originals.into_iter().zip(conflicts);
+        parts
+            .map(|(original, conflicts)| {
+                let tasks = original.iter().map(|&task| self.tasks[task].clone());
+                let instance =
+                    Self::new(self.processors, self.deadline, tasks.collect(), conflicts);
+                (instance, original)
+            })
+            .collect()
+    }
+
+    /// Computes a hash of the instance content.
+    /// It is computed with [`StableHasher`], so it is stable between runs, Rust releases and
+    /// platforms, and can be used to derive per-instance seeds.
+    #[must_use]
+    pub fn content_hash(&self) -> u64 {
+        let mut conflicts = Vec::new();
+        for (from_vertex, adjacent_vertices) in self.graph.edges.iter().enumerate() {
+            let adjacent = adjacent_vertices.iter().filter(|&&to| to > from_vertex);
+            conflicts.extend(adjacent.map(|&to| (from_vertex, to)));
+        }
+        conflicts.sort_unstable();
+
+        let mut hasher = StableHasher::new();
+        hasher.write(cast_u64(self.processors));
+        hasher.write(self.deadline);
+        hasher.write(cast_u64(self.tasks.len()));
+        for task in &self.tasks {
+            hasher.write(task.time);
+            hasher.write(task.weight);
+            hasher.write(task.release);
+        }
+        hasher.write(cast_u64(conflicts.len()));
+        for (from, to) in conflicts {
+            hasher.write(cast_u64(from));
+            hasher.write(cast_u64(to));
+        }
+        // Instances without eligibility restrictions keep their hashes and derived seeds.
+        if self.tasks.iter().any(|task| task.eligible.is_some()) {
+            for task in &self.tasks {
+                let eligible = task.eligible.as_deref();
+                hasher.write(eligible.map_or(0, |machines| cast_u64(machines.len()) + 1));
+                for &machine in eligible.unwrap_or_default() {
+                    hasher.write(cast_u64(machine));
+                }
+            }
+        }
+        // Instances without soft conflicts keep their hashes and derived seeds.
+        if !self.graph.penalties.is_empty() {
+            hasher.write(cast_u64(self.graph.penalties.len()));
+            for (&(from, to), &penalty) in &self.graph.penalties {
+                hasher.write(cast_u64(from));
+                hasher.write(cast_u64(to));
+                hasher.write(penalty);
+            }
+        }
+        hasher.finish()
+    }
+
+    /// Returns the total processing time available on all machines before the deadline.
+    /// It saturates at `u64::MAX` for huge deadlines.
+    #[must_use]
+    pub fn total_capacity(&self) -> u64 {
+        crate::cast_u64(self.processors).saturating_mul(self.deadline)
+    }
+
+    /// Returns the total processing time of all tasks.
+    /// It saturates at `u64::MAX`, like [`Self::total_capacity`].
+    #[must_use]
+    pub fn total_demand(&self) -> u64 {
+        let times = self.tasks.iter().map(|task| task.time);
+        times.fold(0, u64::saturating_add)
+    }
+
+    /// Returns the total weight of all tasks.
+    /// It saturates at `u64::MAX`, like [`Self::total_demand`].
+    #[must_use]
+    pub fn total_weight(&self) -> u64 {
+        let weights = self.tasks.iter().map(|task| task.weight);
+        weights.fold(0, u64::saturating_add)
+    }
+
+    /// Returns whether all tasks have the same processing time.
+    #[must_use]
+    pub fn has_equal_times(&self) -> bool {
+        let time = self.tasks.first().map(|task| task.time);
+        self.tasks.iter().all(|task| Some(task.time) == time)
+    }
+
+    /// Returns whether any task has a release time after the start of the schedule.
+    #[must_use]
+    pub fn has_release_times(&self) -> bool {
+        self.tasks.iter().any(|task| task.release > 0)
+    }
+
+    /// Returns whether any task may run only on some of the processors.
+    #[must_use]
+    pub fn has_eligibility_restrictions(&self) -> bool {
+        self.tasks.iter().any(|task| task.eligible.is_some())
+    }
+
+    /// Returns a summary of the instance.
+    #[must_use]
+    pub fn stats(&self) -> InstanceStats {
+        let times = self.tasks.iter().map(|task| task.time);
+        InstanceStats {
+            tasks: self.tasks.len(),
+            processors: self.processors,
+            deadline: self.deadline,
+            total_weight: self.total_weight(),
+            total_time: self.total_demand(),
+            total_capacity: self.total_capacity(),
+            fits_by_capacity: self.fits_by_capacity(),
+            max_time: times.clone().max().unwrap_or_default(),
+            min_time: times.min().unwrap_or_default(),
+            conflict_edges: self.graph.edge_count(),
+            conflict_density: self.graph.density(self.tasks.len()),
+            is_unit: self.has_equal_times(),
+        }
+    }
+
+    /// Returns whether all tasks could fit before the deadline if there were no conflicts.
+    /// It is a necessary, but not sufficient condition for scheduling all tasks.
+    #[must_use]
+    pub fn fits_by_capacity(&self) -> bool {
+        self.total_demand() <= self.total_capacity()
+    }
+
+    /// Returns whether the deadline is provably too small to schedule all tasks.
+    /// Tasks of a clique in the conflict graph cannot overlap, so their processing
+    /// times must fit before the deadline. For unit instances it means that the
+    /// deadline must allow at least as many time slots as the chromatic number.
+    #[must_use]
+    pub fn is_deadline_too_small(&self) -> bool {
+        let clique = self.graph.greedy_clique(self.tasks.len());
+        let times = clique.iter().map(|&task| self.tasks[task].time);
+        times.fold(0, u64::saturating_add) > self.deadline
+    }
+}
+
+/// A summary of an instance, returned by [`Instance::stats`].
+/// Times of an instance without tasks are 0.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct InstanceStats {
+    pub tasks: usize,
+    pub processors: usize,
+    pub deadline: u64,
+    pub total_weight: u64,
+    pub total_time: u64,
+    /// Processing time available on all machines, see [`Instance::total_capacity`].
+    pub total_capacity: u64,
+    /// Whether the total time fits in the capacity, see [`Instance::fits_by_capacity`].
+    pub fits_by_capacity: bool,
+    pub max_time: u64,
+    pub min_time: u64,
+    pub conflict_edges: usize,
+    pub conflict_density: f64,
+    /// Whether all tasks have the same processing time.
+    pub is_unit: bool,
+}
+
+/// Returns a lower bound of the weight of tardy tasks in any schedule.
+///
+/// Tasks of a clique cannot overlap, so those finishing on time share a single timeline up to
+/// the deadline, whatever the number of processors. For greedy cliques the bound is the weight
+/// left out by the fractional knapsack of their tasks into the deadline. The score of any
+/// schedule is at most [`Instance::total_weight`] minus the bound.
+#[must_use]
+pub fn clique_tardy_bound(instance: &Instance) -> u64 {
+    let tasks = &instance.tasks;
+    let bound = |seed| {
+        let mut clique = instance.graph.heavy_clique(seed, tasks);
+        // The most valuable tasks per unit of time are kept first.
+        clique.sort_unstable_by(|&a, &b| weight_density_comparator(&tasks[a], &tasks[b]));
+
+        let weights = clique.iter().map(|&task| tasks[task].weight);
+        let total = weights.fold(0, u64::saturating_add);
+        let kept = fractional_knapsack(clique.iter().map(|&task| &tasks[task]), instance.deadline);
+        total.saturating_sub(kept)
+    };
+
+    (0..tasks.len()).map(bound).max().unwrap_or_default()
+}
+
+/// Returns an upper bound of the score of any schedule, ignoring conflicts.
+///
+/// Tasks on time fill at most `processors * deadline` units of processing time, so the bound is
+/// the fractional knapsack of tasks into this capacity. Tasks that cannot finish by the deadline
+/// after their release are left out. Unlike the matching bound, it applies to any instance.
+#[must_use]
+pub fn capacity_upper_bound(instance: &Instance) -> u64 {
+    let tasks = &instance.tasks;
+    let mut fitting: Vec<_> = (0..tasks.len())
+        .filter(|&task| tasks[task].fits_before(tasks[task].release, instance.deadline))
+        .collect();
+    fitting.sort_unstable_by(|&a, &b| weight_density_comparator(&tasks[a], &tasks[b]));
+
+    let capacity = cast_u64(instance.processors).saturating_mul(instance.deadline);
+    fractional_knapsack(fitting.iter().map(|&task| &tasks[task]), capacity)
+}
+
+/// Returns the weight of tasks packed into `capacity` units of time, splitting the first task
+/// that does not fit. Tasks must be ordered by weight per unit of time.
+fn fractional_knapsack<'t>(tasks: impl IntoIterator<Item = &'t Task>, capacity: u64) -> u64 {
+    let (mut left, mut kept) = (capacity, 0_u64);
+    for task in tasks {
+        if task.time > left {
+            let part = u128::from(task.weight) * u128::from(left) / u128::from(task.time);
+            let part = u64::try_from(part).unwrap_or_else(|_| unreachable!("Part of a weight"));
+            kept = kept.saturating_add(part);
+            break;
+        }
+        left -= task.time;
+        kept = kept.saturating_add(task.weight);
+    }
+    kept
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::core::Scheduler;
 
     #[test]
     fn instance_should_serialize() -> anyhow::Result<()> {
         let instance = Instance {
             processors: 2,
             deadline: 10,
-            tasks: vec![Task { time: 1, weight: 1 }, Task { time: 2, weight: 2 }],
-            graph: ConflictGraph::from(vec![Conflict(0, 1)]),
+            tasks: vec![
+                Task::new(1, 1),
+                Task {
+                    release: 3,
+                    ..Task::new(2, 2)
+                },
+            ],
+            graph: ConflictGraph::from(vec![Conflict::new(0, 1)]),
         };
 
         let serialized = crate::data::to_string(&instance)?;
@@ -139,4 +902,458 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn clique_should_need_all_colors() {
+        let conflicts = (0..4)
+            .flat_map(|i| (i + 1..4).map(move |j| Conflict::new(i, j)))
+            .collect();
+        let task = Task::new(2, 1);
+        let instance = Instance::new(2, 7, vec![task.clone(); 4], conflicts);
+
+        assert_eq!(instance.graph.greedy_coloring(4), 4);
+        assert_eq!(instance.graph.greedy_clique(4).len(), 4);
+        assert!(instance.is_deadline_too_small());
+
+        let instance = Instance::new_no_conflict(2, 7, vec![task; 4]);
+        assert_eq!(instance.graph.greedy_coloring(4), 1);
+        assert!(!instance.is_deadline_too_small());
+
+        let huge = Task::new(u64::MAX, 1);
+        let instance = Instance::new(2, 7, vec![huge; 2], vec![Conflict::new(0, 1)]);
+        assert!(instance.is_deadline_too_small());
+    }
+
+    #[test]
+    fn capacity_should_bound_demand() {
+        let task = |time| Task::new(time, 1);
+
+        let instance = Instance::new_no_conflict(2, 5, vec![task(3), task(4), task(3)]);
+        assert_eq!(instance.total_capacity(), 10);
+        assert_eq!(instance.total_demand(), 10);
+        assert!(instance.fits_by_capacity());
+
+        let instance = Instance::new_no_conflict(2, 5, vec![task(3), task(4), task(4)]);
+        assert_eq!(instance.total_demand(), 11);
+        assert!(!instance.fits_by_capacity());
+        let instance = Instance::new_no_conflict(3, u64::MAX, vec![task(u64::MAX), task(1)]);
+        assert_eq!(instance.total_capacity(), u64::MAX);
+        assert_eq!(instance.total_demand(), u64::MAX);
+        assert!(instance.fits_by_capacity());
+    }
+
+    #[test]
+    fn total_weight_should_sum_weights() {
+        let task = |weight| Task::new(1, weight);
+
+        let instance = Instance::new_no_conflict(1, 5, vec![task(3), task(4), task(5)]);
+        assert_eq!(instance.total_weight(), 12);
+        assert_eq!(Instance::new_no_conflict(1, 5, vec![]).total_weight(), 0);
+    }
+
+    #[test]
+    fn fits_before_should_not_overflow() {
+        let task = Task::new(3, 1);
+
+        assert!(task.fits_before(2, 5));
+        assert!(!task.fits_before(3, 5));
+        assert!(!task.fits_before(u64::MAX - 1, u64::MAX));
+        assert_eq!(task.completion(u64::MAX - 1), u64::MAX);
+    }
+
+    #[test]
+    fn total_weight_should_saturate() {
+        let task = |weight| Task::new(1, weight);
+
+        let instance = Instance::new_no_conflict(1, 5, vec![task(u64::MAX), task(1)]);
+        assert_eq!(instance.total_weight(), u64::MAX);
+        assert_eq!(instance.stats().total_weight, u64::MAX);
+    }
+
+    #[test]
+    fn equal_times_should_be_detected() {
+        let task = |time| Task::new(time, 1);
+
+        assert!(Instance::new_no_conflict(1, 5, vec![task(2), task(2)]).has_equal_times());
+        assert!(Instance::new_no_conflict(1, 5, vec![]).has_equal_times());
+        assert!(!Instance::new_no_conflict(1, 5, vec![task(2), task(1)]).has_equal_times());
+    }
+
+    #[test]
+    fn graph_metrics_should_count_conflicts() {
+        let graph = ConflictGraph::from(vec![
+            Conflict::new(0, 1),
+            Conflict::new(1, 2),
+            Conflict::new(2, 1),
+        ]);
+
+        assert_eq!(graph.degree(1), 2);
+        assert_eq!(graph.degree(0), 1);
+        assert_eq!(graph.degree(5), 0);
+        assert_eq!(graph.edge_count(), 2);
+        assert!((graph.density(3) - 2.0 / 3.0).abs() < f64::EPSILON);
+        assert!((graph.density(5) - 0.2).abs() < f64::EPSILON);
+        assert!(graph.density(1).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn conflict_chain_should_only_sum_pairwise_conflicts() {
+        let tasks: Vec<_> = [3, 3, 3, 5]
+            .into_iter()
+            .map(|time| Task::new(time, 1))
+            .collect();
+
+        // Tasks 0 and 2 of the path may overlap, so only one edge of it counts.
+        let path = ConflictGraph::from(vec![Conflict::new(0, 1), Conflict::new(1, 2)]);
+        assert_eq!(path.max_conflict_chain_time(&tasks), 6);
+
+        let triangle = ConflictGraph::from(vec![
+            Conflict::new(0, 1),
+            Conflict::new(1, 2),
+            Conflict::new(0, 2),
+            Conflict::new(2, 3),
+        ]);
+        assert_eq!(triangle.max_conflict_chain_time(&tasks), 9);
+        let empty = ConflictGraph::from(vec![]);
+        assert_eq!(empty.max_conflict_chain_time(&tasks), 5);
+        assert_eq!(empty.max_conflict_chain_time(&[]), 0);
+    }
+
+    #[test]
+    fn clique_bound_should_count_tasks_not_fitting() -> anyhow::Result<()> {
+        let task = |weight| Task::new(2, weight);
+        let tasks = vec![task(1), task(2), task(3)];
+        let conflicts = vec![
+            Conflict::new(0, 1),
+            Conflict::new(1, 2),
+            Conflict::new(0, 2),
+        ];
+        assert_eq!(
+            clique_tardy_bound(&Instance::new(3, 4, tasks.clone(), conflicts)),
+            1
+        );
+        assert_eq!(
+            clique_tardy_bound(&Instance::new_no_conflict(3, 4, tasks)),
+            0
+        );
+
+        for entry in std::fs::read_dir("samples")? {
+            let path = entry?.path();
+            let name = path.file_name().and_then(|name| name.to_str());
+            let optimum: u64 = name
+                .unwrap_or_default()
+                .split('_')
+                .nth(1)
+                .unwrap_or_default()
+                .parse()?;
+            let file = std::fs::File::open(&path)?;
+            let instance: Instance = crate::data::deserialize(&mut std::io::BufReader::new(file))?;
+            assert!(instance.total_weight() - clique_tardy_bound(&instance) >= optimum);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn capacity_bound_should_split_last_task() {
+        let task = |time, weight, release| Task {
+            release,
+            ..Task::new(time, weight)
+        };
+        let tasks = vec![task(2, 6, 0), task(4, 4, 0), task(3, 9, 0), task(1, 100, 9)];
+        let instance = Instance::new(2, 4, tasks, vec![Conflict::new(0, 2)]);
+
+        // The last task is released too late to finish. Conflicts are ignored, so the denser
+        // tasks 0 and 2 fill 5 units of time and 3 of 4 units of task 1 fit.
+        assert_eq!(capacity_upper_bound(&instance), 18);
+        assert!(
+            capacity_upper_bound(&instance)
+                >= crate::algo::List.schedule(&instance).calculate_score()
+        );
+    }
+
+    #[test]
+    fn bounds_should_saturate_huge_weights() {
+        let task = |weight| Task::new(1, weight);
+        let tasks = vec![task(u64::MAX), task(u64::MAX), task(u64::MAX)];
+        let instance = Instance::new(2, 4, tasks, Vec::new());
+
+        assert_eq!(capacity_upper_bound(&instance), u64::MAX);
+    }
+
+    #[test]
+    fn stats_should_summarize_instance() -> anyhow::Result<()> {
+        let tasks = vec![Task::new(2, 1), Task::new(5, 3), Task::new(3, 2)];
+        let instance = Instance::new(2, 6, tasks, vec![Conflict::new(0, 1)]);
+
+        let stats = instance.stats();
+        assert_eq!((stats.tasks, stats.processors, stats.deadline), (3, 2, 6));
+        assert_eq!((stats.total_weight, stats.total_time), (6, 10));
+        assert_eq!(stats.total_capacity, 12);
+        assert!(stats.fits_by_capacity);
+        assert_eq!((stats.min_time, stats.max_time), (2, 5));
+        assert_eq!(stats.conflict_edges, 1);
+        assert!((stats.conflict_density - 1.0 / 3.0).abs() < f64::EPSILON);
+        assert!(!stats.is_unit);
+
+        let json = serde_json::to_string(&stats)?;
+        assert!(json.contains("\"total_time\":10"));
+        assert!(json.contains("\"is_unit\":false"));
+
+        let empty = Instance::new_no_conflict(1, 1, vec![]).stats();
+        assert_eq!((empty.min_time, empty.max_time), (0, 0));
+        assert!(empty.is_unit);
+        Ok(())
+    }
+
+    #[test]
+    fn task_should_fit_until_deadline() {
+        let task = Task::new(3, 1);
+        assert_eq!(task.completion(2), 5);
+        assert!(task.fits_before(2, 5));
+        assert!(!task.fits_before(3, 5));
+    }
+
+    #[test]
+    fn duplicate_and_self_conflicts_should_be_ignored() {
+        assert_eq!(Conflict::new(3, 1), Conflict::new(1, 3));
+        assert_eq!(Conflict::soft(3, 1, 2), Conflict::soft(1, 3, 2));
+
+        let graph = ConflictGraph::from(vec![
+            Conflict::new(0, 1),
+            Conflict::new(1, 0),
+            Conflict::new(2, 2),
+            Conflict::soft(3, 3, 1),
+            Conflict::new(1, 2),
+        ]);
+        assert_eq!(graph.edge_count(), 2);
+        assert!(!graph.are_conflicted(2, 2));
+        assert_eq!(graph.soft_conflicts().count(), 0);
+        assert_eq!(
+            Vec::from(graph),
+            vec![Conflict::new(0, 1), Conflict::new(1, 2)]
+        );
+    }
+
+    #[test]
+    fn pairs_should_list_each_conflict_once() {
+        let graph = ConflictGraph::from(vec![
+            Conflict::new(2, 0),
+            Conflict::new(0, 1),
+            Conflict::new(1, 0),
+            Conflict::soft(1, 2, 3),
+        ]);
+
+        let mut pairs: Vec<_> = graph.pairs().collect();
+        pairs.sort_unstable();
+        assert_eq!(pairs, vec![(0, 1), (0, 2)]);
+        assert_eq!(pairs.len(), graph.edge_count());
+    }
+
+    #[test]
+    fn self_conflict_should_not_block_task() {
+        let task = Task::new(2, 1);
+        let instance = Instance::new(1, 4, vec![task.clone(), task], vec![Conflict::new(0, 0)]);
+        let schedule = crate::algo::List.schedule(&instance);
+        assert_eq!(schedule.calculate_score(), 2);
+    }
+
+    #[test]
+    fn subinstance_should_remap_conflicts() {
+        let task = |weight| Task::new(1, weight);
+        let tasks = (0..5).map(task).collect();
+        let conflicts = vec![
+            Conflict::new(0, 1),
+            Conflict::new(1, 3),
+            Conflict::new(3, 4),
+            Conflict::soft(3, 1, 7),
+        ];
+        let instance = Instance::new(2, 3, tasks, conflicts);
+
+        let (sub, original) = instance.subinstance(&[4, 3, 1, 3]);
+        assert_eq!(original, vec![4, 3, 1]);
+        assert_eq!(sub.processors, 2);
+        assert_eq!(sub.deadline, 3);
+        let weights: Vec<_> = sub.tasks.iter().map(|task| task.weight).collect();
+        assert_eq!(weights, vec![4, 3, 1]);
+        assert_eq!(
+            Vec::from(sub.graph),
+            vec![
+                Conflict::new(0, 1),
+                Conflict::new(1, 2),
+                Conflict::soft(1, 2, 7)
+            ]
+        );
+    }
+
+    #[test]
+    fn subinstances_should_split_conflicts_between_parts() {
+        let task = |weight| Task::new(1, weight);
+        let tasks = (0..6).map(task).collect();
+        let conflicts = vec![
+            Conflict::new(0, 2),
+            Conflict::new(1, 3),
+            Conflict::new(2, 5),
+            Conflict::soft(1, 3, 4),
+            Conflict::new(4, 5),
+        ];
+        let instance = Instance::new(2, 3, tasks, conflicts);
+
+        let parts = instance.subinstances(&[vec![0, 2, 5], vec![3, 1, 2], vec![4]]);
+        assert_eq!(parts.len(), 3);
+        for (tasks, (sub, original)) in [vec![0, 2, 5], vec![3, 1], vec![4]].iter().zip(&parts) {
+            assert_eq!(original, tasks);
+            assert_eq!(sub, &instance.subinstance(tasks).0);
+        }
+        assert_eq!(
+            Vec::from(parts[1].0.graph.clone()),
+            vec![Conflict::new(0, 1), Conflict::soft(0, 1, 4)]
+        );
+    }
+
+    #[test]
+    fn components_should_group_conflicting_tasks() {
+        let conflicts = vec![
+            Conflict::new(0, 3),
+            Conflict::new(3, 5),
+            Conflict::new(1, 2),
+        ];
+        let graph = ConflictGraph::from(conflicts);
+
+        assert_eq!(
+            graph.connected_components(7),
+            vec![vec![0, 3, 5], vec![1, 2], vec![4], vec![6]]
+        );
+        assert!(graph.connected_components(0).is_empty());
+    }
+
+    #[test]
+    fn content_hash_should_ignore_conflict_order() {
+        let tasks = vec![Task::new(1, 1); 3];
+        let first = Instance::new(
+            2,
+            10,
+            tasks.clone(),
+            vec![Conflict::new(0, 1), Conflict::new(1, 2)],
+        );
+        let second = Instance::new(
+            2,
+            10,
+            tasks.clone(),
+            vec![Conflict::new(2, 1), Conflict::new(1, 0)],
+        );
+        let third = Instance::new(2, 10, tasks, vec![Conflict::new(0, 1)]);
+
+        assert_eq!(first.content_hash(), second.content_hash());
+        assert_ne!(first.content_hash(), third.content_hash());
+    }
+
+    #[test]
+    fn content_hash_should_be_stable() {
+        let mut hasher = StableHasher::new();
+        hasher.write(0);
+        assert_eq!(hasher.finish(), 0xa8c7_f832_281a_39c5);
+
+        let task = Task::new(1, 1);
+        let instance = Instance::new(2, 10, vec![task; 3], vec![Conflict::new(0, 1)]);
+        assert_eq!(instance.content_hash(), 0x819c_4e31_68d7_570e);
+    }
+
+    #[test]
+    fn invalid_instance_should_be_rejected() {
+        // Errors are prefixed with the position where the deserializer stopped.
+        let parse = |input: &str, expected: InstanceError| {
+            let mut reader = std::io::Cursor::new(input.to_owned());
+            let result = crate::data::deserialize::<_, Instance>(&mut reader);
+            result.is_err_and(|err| err.to_string().ends_with(&expected.to_string()))
+        };
+
+        let mut reader = std::io::Cursor::new("1 10\n4 2\n3 1\n\n0 1\n\n");
+        assert!(crate::data::deserialize::<_, Instance>(&mut reader).is_ok());
+        assert!(parse(
+            "1 10\n4 2\n3 1\n\n0 2\n\n",
+            InstanceError::ConflictOutOfRange(2, 2)
+        ));
+        assert!(parse("1 0\n4 2\n\n\n", InstanceError::ZeroDeadline));
+    }
+
+    #[test]
+    fn zero_processors_should_be_rejected() {
+        let input = "0 10\n4 2\n3 1\n\n\n";
+        let mut reader = std::io::Cursor::new(input);
+        let result = crate::data::deserialize::<_, Instance>(&mut reader);
+        let expected = InstanceError::ZeroProcessors.to_string();
+        assert!(result.is_err_and(|err| err.to_string().ends_with(&expected)));
+
+        let json = r#"{"processors":0,"deadline":10,"tasks":[{"time":4,"weight":2}],"graph":[]}"#;
+        assert!(crate::data::deserialize_json::<Instance>(json.as_bytes()).is_err());
+
+        let mut reader = std::io::Cursor::new(input);
+        let mut list = crate::algo::List;
+        assert!(crate::run_reader(&mut list, &mut reader, &mut Vec::new(), false, false).is_err());
+    }
+
+    #[test]
+    fn task_release_should_default_to_zero() -> anyhow::Result<()> {
+        let mut reader = std::io::Cursor::new("1 10\n4 2\n3 1 2\n\n0 1\n\n");
+        let instance: Instance = crate::data::deserialize(&mut reader)?;
+
+        assert_eq!(instance.tasks[0].release, 0);
+        assert_eq!(instance.tasks[1].release, 2);
+        assert_eq!(
+            crate::data::to_string(&instance)?,
+            "1 10\n4 2\n3 1 2\n\n0 1\n\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn soft_conflicts_should_round_trip() -> anyhow::Result<()> {
+        let input = "1 10\n4 2\n3 1\n2 1\n\n0 1\n1 2 5\n\n";
+        let mut reader = std::io::Cursor::new(input);
+        let instance: Instance = crate::data::deserialize(&mut reader)?;
+
+        assert!(instance.graph.conflicts(0).contains(&1));
+        assert!(!instance.graph.conflicts(1).contains(&2));
+        assert_eq!(
+            instance.graph.soft_conflicts().collect::<Vec<_>>(),
+            [(1, 2, 5)]
+        );
+        assert_eq!(crate::data::to_string(&instance)?, input);
+
+        let json = serde_json::to_string(&instance)?;
+        assert_eq!(serde_json::from_str::<Instance>(&json)?, instance);
+
+        Ok(())
+    }
+
+    #[test]
+    fn task_eligible_should_default_to_none() -> anyhow::Result<()> {
+        let input = "2 10\n3 1 0 1\n4 2 5 0,1\n2 1\n\n\n";
+        let mut reader = std::io::Cursor::new(input);
+        let instance: Instance = crate::data::deserialize(&mut reader)?;
+
+        assert_eq!(instance.tasks[0].eligible, Some(vec![1]));
+        assert_eq!(instance.tasks[1].eligible, Some(vec![0, 1]));
+        assert_eq!(instance.tasks[2].eligible, None);
+        assert!(!instance.tasks[0].is_eligible(0));
+        assert!(instance.tasks[2].is_eligible(0));
+        assert_eq!(crate::data::to_string(&instance)?, input);
+
+        let json = serde_json::to_string(&instance)?;
+        assert!(json.contains("\"eligible\":[0,1]"));
+        assert_eq!(serde_json::from_str::<Instance>(&json)?, instance);
+        // Comma-separated strings are still accepted in JSON.
+        let joined = json.replace("[0,1]", "\"0,1\"");
+        assert_eq!(serde_json::from_str::<Instance>(&joined)?, instance);
+
+        let mut reader = std::io::Cursor::new("1 10\n3 1 0 1\n\n\n");
+        let error = crate::data::deserialize::<_, Instance>(&mut reader);
+        assert!(error.is_err_and(|error| error
+            .to_string()
+            .ends_with(&InstanceError::EligibleOutOfRange(0, 1, 1).to_string())));
+
+        Ok(())
+    }
 }
@@ -1,6 +1,7 @@
-use super::Instance;
+use super::{Instance, Task};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
+use thiserror::Error;
 
 /// Schedule info for a task. Contains the start time and processor of the task.
 #[non_exhaustive]
@@ -16,6 +17,27 @@ impl ScheduleInfo {
     pub const fn new(start: u64, processor: usize) -> Self {
         Self { processor, start }
     }
+
+    /// Returns the completion time of the task placed with this info, exclusive.
+    #[must_use]
+    pub const fn end(&self, task: &Task) -> u64 {
+        task.completion(self.start)
+    }
+}
+
+/// A reason why a schedule is invalid, returned by [`Schedule::verify_detailed`].
+#[derive(Clone, Copy, Debug, Eq, Error, PartialEq)]
+pub enum VerifyError {
+    #[error("task {0} is placed on processor {1}, which does not exist")]
+    ProcessorOutOfRange(usize, usize),
+    #[error("task {0} is placed on processor {1}, which it is not eligible for")]
+    NotEligible(usize, usize),
+    #[error("task {0} starts before its release time")]
+    BeforeRelease(usize),
+    #[error("tasks {0} and {1} overlap on the same processor")]
+    Overlap(usize, usize),
+    #[error("conflicting tasks {0} and {1} overlap")]
+    Conflict(usize, usize),
 }
 
 /// A schedule. Contains the schedule info for every task.
@@ -36,6 +58,12 @@ impl<'a> Schedule<'a> {
         }
     }
 
+    /// Returns the scheduled instance.
+    #[must_use]
+    pub const fn instance(&self) -> &'a Instance {
+        self.instance
+    }
+
     /// Schedule info for a task.
     pub fn schedule(&mut self, task: usize, schedule_info: ScheduleInfo) {
         self.schedule[task] = Some(schedule_info);
@@ -52,14 +80,26 @@ impl<'a> Schedule<'a> {
         self.schedule[task].as_ref()
     }
 
+    /// Copies the schedule of a sub-instance, where `mapping[local]` is the task in this instance.
+    /// Tasks left unscheduled in `other` are unscheduled here too. Processors are shared, so
+    /// pieces placed on the same processor may overlap and the result should be verified.
+    ///
+    /// # Panics
+    /// - If the mapping is shorter than the tasks of `other` or refers to a missing task.
+    pub fn apply_from(&mut self, other: &Schedule, mapping: &[usize]) {
+        for (local, info) in other.schedule.iter().enumerate() {
+            self.schedule[mapping[local]] = *info;
+        }
+    }
+
     /// Check if the given task with the given start time is in conflict with another task.
     #[must_use]
     pub fn in_conflict(&self, task: usize, start: u64) -> bool {
         self.instance.graph.conflicts(task).iter().any(|&other| {
-            self.schedule[other].map_or(false, |info| {
+            self.schedule[other].is_some_and(|info| {
                 let task = &self.instance.tasks[task];
                 let other = &self.instance.tasks[other];
-                start < info.start + other.time && info.start < start + task.time
+                start < info.end(other) && info.start < task.completion(start)
             })
         })
     }
@@ -70,7 +110,7 @@ impl<'a> Schedule<'a> {
         let mut score = 0;
         for (info, task) in self.schedule.iter().zip(&self.instance.tasks) {
             if let Some(schedule_info) = info {
-                if schedule_info.start + task.time <= self.instance.deadline {
+                if task.fits_before(schedule_info.start, self.instance.deadline) {
                     score += task.weight;
                 }
             }
@@ -78,42 +118,402 @@ impl<'a> Schedule<'a> {
         score
     }
 
+    /// Returns the total weight of tasks finishing before the deadline.
+    /// It equals the score, the weight of tardy tasks is the rest of [`Instance::total_weight`].
+    #[must_use]
+    pub fn scheduled_weight(&self) -> u64 {
+        self.calculate_score()
+    }
+
+    /// Calculates the score minus the penalties of overlapping soft conflicts.
+    /// Without soft conflicts it equals [`Self::calculate_score`].
+    #[must_use]
+    pub fn calculate_score_with_penalties(&self) -> i128 {
+        let mut score = i128::from(self.calculate_score());
+        for (first, second, penalty) in self.instance.graph.soft_conflicts() {
+            if let (Some(a), Some(b)) = (self.schedule[first], self.schedule[second]) {
+                let tasks = &self.instance.tasks;
+                if a.start < b.end(&tasks[second]) && b.start < a.end(&tasks[first]) {
+                    score -= i128::from(penalty);
+                }
+            }
+        }
+        score
+    }
+
+    /// Returns the time at which the last scheduled task finishes.
+    #[must_use]
+    pub fn makespan(&self) -> u64 {
+        let ends = self.schedule.iter().zip(&self.instance.tasks);
+        let ends = ends.filter_map(|(info, task)| info.map(|info| info.end(task)));
+        ends.max().unwrap_or_default()
+    }
+
+    /// Shifts every task as early as possible, removing idle gaps.
+    ///
+    /// Tasks keep their machines and their order on the machines, conflicts and release
+    /// times are respected. No task is added or removed, so a valid schedule stays valid
+    /// and the score cannot decrease. Returns whether any task moved.
+    pub fn compact(&mut self) -> bool {
+        let mut order: Vec<_> = (0..self.schedule.len()).collect();
+        order.retain(|&task| self.schedule[task].is_some());
+        order.sort_by_key(|&task| self.schedule[task].map(|info| info.start));
+
+        let mut free = vec![0; self.instance.processors];
+        let mut changed = false;
+
+        for task in order {
+            let Some(info) = self.schedule[task].take() else {
+                continue;
+            };
+            let lower = free[info.processor].max(self.instance.tasks[task].release);
+
+            let mut starts: Vec<_> = self
+                .instance
+                .graph
+                .conflicts(task)
+                .iter()
+                .filter_map(|&other| {
+                    let end = self.schedule[other]?.end(&self.instance.tasks[other]);
+                    (lower < end && end < info.start).then_some(end)
+                })
+                .collect();
+            starts.push(lower);
+            starts.sort_unstable();
+
+            // The original start is always valid, as preceding tasks only moved earlier.
+            let start = starts
+                .into_iter()
+                .find(|&start| start <= info.start && !self.in_conflict(task, start))
+                .unwrap_or(info.start);
+
+            self.schedule[task] = Some(ScheduleInfo::new(start, info.processor));
+            free[info.processor] = self.instance.tasks[task].completion(start);
+            changed |= start < info.start;
+        }
+
+        changed
+    }
+
+    /// Reassigns tasks to machines to balance their total processing time.
+    ///
+    /// Start times are kept, so conflicts and the score do not change. Tasks are assigned in
+    /// order of start time to the least loaded eligible machine that is free at that time.
+    /// If some task finds no such machine, the schedule is left unchanged.
+    pub fn rebalance(&mut self) {
+        let mut order: Vec<_> = (0..self.schedule.len()).collect();
+        order.retain(|&task| self.schedule[task].is_some());
+        order.sort_by_key(|&task| self.schedule[task].map(|info| info.start));
+
+        let mut free = vec![0; self.instance.processors];
+        let mut load = vec![0; self.instance.processors];
+        let mut balanced = self.schedule.clone();
+
+        for task in order {
+            let Some(info) = self.schedule[task] else {
+                continue;
+            };
+
+            let task_data = &self.instance.tasks[task];
+            let machine = (0..self.instance.processors)
+                .filter(|&machine| free[machine] <= info.start && task_data.is_eligible(machine))
+                .min_by_key(|&machine| load[machine]);
+            let Some(machine) = machine else {
+                return;
+            };
+
+            free[machine] = info.end(task_data);
+            load[machine] += task_data.time;
+            balanced[task] = Some(ScheduleInfo::new(info.start, machine));
+        }
+
+        self.schedule = balanced;
+    }
+
     /// Checks if schedule is valid.
+    /// Tasks placed after the deadline are accepted, they are tardy and do not count to the score.
     #[must_use]
     pub fn verify(&self) -> bool {
+        self.verify_detailed().is_ok()
+    }
+
+    /// Checks if schedule is valid like [`Self::verify`], returning the first violation found.
+    ///
+    /// # Errors
+    /// - If a task is placed on a processor that does not exist or it is not eligible for.
+    /// - If a task starts before its release time.
+    /// - If two tasks overlap on the same processor.
+    /// - If two conflicting tasks overlap.
+    pub fn verify_detailed(&self) -> Result<(), VerifyError> {
+        let tasks = &self.instance.tasks;
         let mut machines = vec![BTreeMap::new(); self.instance.processors];
 
         for (id, info) in self.schedule.iter().enumerate() {
-            if let Some(info) = info {
-                let machine = &mut machines[info.processor];
+            let Some(info) = info else {
+                continue;
+            };
+            let Some(machine) = machines.get_mut(info.processor) else {
+                return Err(VerifyError::ProcessorOutOfRange(id, info.processor));
+            };
 
-                if machine.contains_key(&info.start) {
-                    return false;
-                }
+            if !tasks[id].is_eligible(info.processor) {
+                return Err(VerifyError::NotEligible(id, info.processor));
+            }
 
-                machine.insert(info.start, id);
+            if info.start < tasks[id].release {
+                return Err(VerifyError::BeforeRelease(id));
             }
+
+            if let Some(&other) = machine.get(&info.start) {
+                return Err(VerifyError::Overlap(other, id));
+            }
+
+            machine.insert(info.start, id);
         }
 
         for machine in machines {
-            let mut last_end = 0;
+            let mut last = None;
             for (start, task) in machine {
-                if start < last_end {
-                    return false;
+                if let Some((other, _)) = last.filter(|&(_, end)| start < end) {
+                    return Err(VerifyError::Overlap(other, task));
                 }
 
-                last_end = start + self.instance.tasks[task].time;
+                last = Some((task, tasks[task].completion(start)));
             }
         }
 
         for (id, info) in self.schedule.iter().enumerate() {
-            if let Some(info) = info {
-                if self.in_conflict(id, info.start) {
-                    return false;
-                }
+            let Some(info) = info else {
+                continue;
+            };
+            let overlapping = self.instance.graph.conflicts(id).iter().find(|&&other| {
+                self.schedule[other].is_some_and(|other_info| {
+                    info.start < other_info.end(&tasks[other])
+                        && other_info.start < info.end(&tasks[id])
+                })
+            });
+            if let Some(&other) = overlapping {
+                return Err(VerifyError::Conflict(id.min(other), id.max(other)));
             }
         }
 
-        true
+        Ok(())
+    }
+
+    /// Checks if schedule is valid and every placed task finishes before the deadline.
+    /// Tardy tasks must be left unscheduled, placing them after the deadline is rejected.
+    #[must_use]
+    pub fn verify_strict(&self) -> bool {
+        let deadline = self.instance.deadline;
+        let mut placed = self.schedule.iter().zip(&self.instance.tasks);
+        self.verify()
+            && placed.all(|(info, task)| {
+                info.map_or(true, |info| task.fits_before(info.start, deadline))
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cast_u64;
+    use crate::core::{Conflict, Task};
+
+    #[test]
+    fn apply_from_should_lift_subinstance_schedules() {
+        let task = |weight| Task::new(1, weight);
+        let tasks = (1..=4).map(task).collect();
+        let conflicts = vec![Conflict::new(0, 2), Conflict::new(1, 3)];
+        let instance = Instance::new(2, 2, tasks, conflicts);
+
+        let mut schedule = Schedule::new(&instance);
+        for (piece, processor) in [(vec![0, 2], 0), (vec![1, 3], 1)] {
+            let (sub, mapping) = instance.subinstance(&piece);
+            let mut partial = Schedule::new(&sub);
+            partial.schedule(0, ScheduleInfo::new(0, processor));
+            partial.schedule(1, ScheduleInfo::new(1, processor));
+            schedule.apply_from(&partial, &mapping);
+        }
+
+        assert!(schedule.verify());
+        assert_eq!(schedule.calculate_score(), 10);
+        assert_eq!(schedule.get_schedule(2), Some(&ScheduleInfo::new(1, 0)));
+        assert_eq!(schedule.get_schedule(3), Some(&ScheduleInfo::new(1, 1)));
+    }
+
+    #[test]
+    fn compact_should_remove_gaps() {
+        let task = |time| Task::new(time, 1);
+        let tasks = vec![task(2), task(2), task(4)];
+        let instance = Instance::new(2, 10, tasks, vec![Conflict::new(0, 2)]);
+
+        let mut schedule = Schedule::new(&instance);
+        schedule.schedule(0, ScheduleInfo::new(5, 0));
+        schedule.schedule(1, ScheduleInfo::new(10, 0));
+        schedule.schedule(2, ScheduleInfo::new(1, 1));
+        assert!(schedule.verify());
+        assert_eq!(schedule.makespan(), 12);
+        assert_eq!(schedule.calculate_score(), 2);
+
+        schedule.compact();
+
+        assert!(schedule.verify());
+        assert_eq!(schedule.makespan(), 8);
+        assert_eq!(schedule.calculate_score(), 3);
+        assert_eq!(schedule.get_schedule(2), Some(&ScheduleInfo::new(0, 1)));
+        assert_eq!(schedule.get_schedule(0), Some(&ScheduleInfo::new(4, 0)));
+        assert_eq!(schedule.get_schedule(1), Some(&ScheduleInfo::new(6, 0)));
+    }
+
+    #[test]
+    fn verify_detailed_should_report_violation() {
+        let task = |eligible| Task {
+            eligible,
+            ..Task::new(2, 1)
+        };
+        let tasks = vec![task(None), task(None), task(Some(vec![1]))];
+        let instance = Instance::new(2, 10, tasks, vec![Conflict::new(0, 1)]);
+
+        let mut schedule = Schedule::new(&instance);
+        schedule.schedule(0, ScheduleInfo::new(0, 0));
+        schedule.schedule(1, ScheduleInfo::new(1, 1));
+        assert_eq!(schedule.verify_detailed(), Err(VerifyError::Conflict(0, 1)));
+
+        schedule.schedule(1, ScheduleInfo::new(1, 0));
+        assert_eq!(schedule.verify_detailed(), Err(VerifyError::Overlap(0, 1)));
+
+        schedule.schedule(1, ScheduleInfo::new(2, 0));
+        assert_eq!(schedule.verify_detailed(), Ok(()));
+
+        schedule.schedule(2, ScheduleInfo::new(4, 0));
+        assert_eq!(
+            schedule.verify_detailed(),
+            Err(VerifyError::NotEligible(2, 0))
+        );
+
+        schedule.schedule(2, ScheduleInfo::new(4, 2));
+        assert_eq!(
+            schedule.verify_detailed(),
+            Err(VerifyError::ProcessorOutOfRange(2, 2))
+        );
+        assert!(!schedule.verify());
+    }
+
+    #[test]
+    fn verify_should_reject_start_before_release() {
+        let task = Task {
+            release: 3,
+            ..Task::new(2, 1)
+        };
+        let instance = Instance::new_no_conflict(1, 10, vec![task]);
+
+        let mut schedule = Schedule::new(&instance);
+        schedule.schedule(0, ScheduleInfo::new(2, 0));
+        assert_eq!(
+            schedule.verify_detailed(),
+            Err(VerifyError::BeforeRelease(0))
+        );
+
+        schedule.schedule(0, ScheduleInfo::new(3, 0));
+        assert!(schedule.verify());
+    }
+
+    #[test]
+    fn strict_verify_should_reject_tasks_after_deadline() {
+        let task = Task::new(2, 1);
+        let instance = Instance::new_no_conflict(1, 4, vec![task; 3]);
+
+        let mut schedule = Schedule::new(&instance);
+        schedule.schedule(0, ScheduleInfo::new(0, 0));
+        schedule.schedule(1, ScheduleInfo::new(2, 0));
+        assert!(schedule.verify_strict());
+
+        schedule.schedule(2, ScheduleInfo::new(4, 0));
+        assert!(schedule.verify());
+        assert!(!schedule.verify_strict());
+    }
+
+    #[test]
+    fn overlapping_soft_conflicts_should_be_penalized() {
+        let task = Task::new(2, 3);
+        let conflicts = vec![Conflict::soft(0, 1, 2), Conflict::soft(0, 2, 5)];
+        let instance = Instance::new(2, 10, vec![task; 3], conflicts);
+
+        let mut schedule = Schedule::new(&instance);
+        schedule.schedule(0, ScheduleInfo::new(0, 0));
+        schedule.schedule(1, ScheduleInfo::new(1, 1));
+        schedule.schedule(2, ScheduleInfo::new(2, 0));
+        assert!(schedule.verify());
+        assert_eq!(schedule.calculate_score(), 9);
+        assert_eq!(schedule.calculate_score_with_penalties(), 7);
+    }
+
+    #[test]
+    fn scheduled_weight_should_exclude_tardy_tasks() {
+        let instance = Instance::new_no_conflict(
+            1,
+            4,
+            vec![Task::new(2, 3), Task::new(2, 4), Task::new(2, 5)],
+        );
+
+        let mut schedule = Schedule::new(&instance);
+        schedule.schedule(0, ScheduleInfo::new(0, 0));
+        schedule.schedule(1, ScheduleInfo::new(2, 0));
+        schedule.schedule(2, ScheduleInfo::new(4, 0));
+
+        assert_eq!(schedule.scheduled_weight(), 7);
+        assert_eq!(instance.total_weight() - schedule.scheduled_weight(), 5);
+    }
+
+    #[test]
+    fn rebalance_should_spread_tasks() {
+        let task = Task::new(2, 1);
+        let instance = Instance::new_no_conflict(2, 10, vec![task; 4]);
+
+        let mut schedule = Schedule::new(&instance);
+        for id in 0..4 {
+            schedule.schedule(id, ScheduleInfo::new(cast_u64(id) * 2, 0));
+        }
+        let score = schedule.calculate_score();
+
+        schedule.rebalance();
+
+        assert!(schedule.verify());
+        assert_eq!(schedule.calculate_score(), score);
+        for id in 0..4 {
+            let info = schedule.get_schedule(id).copied();
+            assert_eq!(info, Some(ScheduleInfo::new(cast_u64(id) * 2, id % 2)));
+        }
+    }
+
+    #[test]
+    fn rebalance_should_keep_tasks_on_eligible_machines() {
+        let task = |eligible| Task {
+            eligible,
+            ..Task::new(2, 1)
+        };
+        let tasks = vec![task(None), task(Some(vec![0])), task(Some(vec![0]))];
+        let instance = Instance::new_no_conflict(2, 10, tasks);
+
+        let mut schedule = Schedule::new(&instance);
+        for id in 0..3 {
+            schedule.schedule(id, ScheduleInfo::new(cast_u64(id) * 2, 0));
+        }
+
+        schedule.rebalance();
+
+        assert_eq!(schedule.verify_detailed(), Ok(()));
+        assert_eq!(schedule.get_schedule(0), Some(&ScheduleInfo::new(0, 0)));
+        assert_eq!(schedule.get_schedule(1), Some(&ScheduleInfo::new(2, 0)));
+        assert_eq!(schedule.get_schedule(2), Some(&ScheduleInfo::new(4, 0)));
+
+        // Task 0 would move to machine 0, which task 1 needs while task 0 runs,
+        // so the schedule is kept.
+        let mut schedule = Schedule::new(&instance);
+        schedule.schedule(0, ScheduleInfo::new(0, 1));
+        schedule.schedule(1, ScheduleInfo::new(1, 0));
+        schedule.rebalance();
+        assert_eq!(schedule.get_schedule(0), Some(&ScheduleInfo::new(0, 1)));
+        assert_eq!(schedule.get_schedule(1), Some(&ScheduleInfo::new(1, 0)));
     }
 }
@@ -0,0 +1,71 @@
+use super::{Schedule, ScheduleInfo};
+use std::fmt::{Display, Formatter, Result};
+
+/// Reason of a placement decision.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Reason {
+    /// The task starts as soon as the machine is free.
+    Free,
+    /// The task waits for its release time.
+    Release,
+    /// The task is delayed or made tardy by conflicting tasks.
+    Conflict,
+    /// The task does not fit before the deadline.
+    Deadline,
+    /// The task is not eligible for any machine.
+    Ineligible,
+    /// The placement is read from the final schedule, the scheduler does not report reasons.
+    Final,
+}
+
+/// Placement decision made by a scheduler for a single task.
+/// Tardy tasks have no placement.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Decision {
+    pub task: usize,
+    pub placement: Option<ScheduleInfo>,
+    pub reason: Reason,
+}
+
+impl Decision {
+    /// Creates a new decision.
+    #[must_use]
+    pub const fn new(task: usize, placement: Option<ScheduleInfo>, reason: Reason) -> Self {
+        Self {
+            task,
+            placement,
+            reason,
+        }
+    }
+
+    /// Reads the decisions from the final schedule, with [`Reason::Final`] for every task.
+    #[must_use]
+    pub fn from_schedule(schedule: &Schedule) -> Vec<Self> {
+        (0..schedule.instance().tasks.len())
+            .map(|task| Self::new(task, schedule.get_schedule(task).copied(), Reason::Final))
+            .collect()
+    }
+}
+
+impl Display for Decision {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self.placement {
+            Some(info) => write!(
+                f,
+                "task {}: machine {} at {}",
+                self.task, info.processor, info.start
+            )?,
+            None => write!(f, "task {}: tardy", self.task)?,
+        }
+
+        match self.reason {
+            Reason::Free => write!(f, " (machine free)"),
+            Reason::Release => write!(f, " (waiting for release)"),
+            Reason::Conflict => write!(f, " (conflicting tasks)"),
+            Reason::Deadline => write!(f, " (deadline exceeded)"),
+            Reason::Ineligible => write!(f, " (no eligible machine)"),
+            Reason::Final => Ok(()),
+        }
+    }
+}
@@ -1,50 +1,161 @@
-use super::{Instance, Schedule, ScheduleInfo, Task};
+use super::{Instance, Schedule, ScheduleInfo, Task, VerifyError};
 use std::cmp::Ordering;
-use std::collections::BTreeSet;
+use std::collections::BTreeMap;
 
 /// Task with its id.
 pub type TaskWithId = (usize, Task);
 
+/// Order of machines with the same free time.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum MachineOrder {
+    /// Machines free at the same time are ordered by id.
+    #[default]
+    Id,
+    /// Machines free at the same time are ordered by the number of scheduled tasks,
+    /// then by id. It spreads tasks over the machines.
+    FewestTasks,
+}
+
+impl MachineOrder {
+    /// Compares two machines by free time, breaking ties according to the order.
+    #[must_use]
+    pub fn compare(self, first: &Machine, second: &Machine) -> Ordering {
+        self.key(first).cmp(&self.key(second))
+    }
+
+    /// Returns the key of a machine, machines with lower keys come first.
+    const fn key(self, machine: &Machine) -> (u64, usize, usize) {
+        let tie = match self {
+            Self::Id => 0,
+            Self::FewestTasks => machine.tasks,
+        };
+        (machine.free, tie, machine.id)
+    }
+}
+
+/// Order in which tardy tasks are placed again.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum TardyOrder {
+    /// Tasks are ordered by [`weight_density_comparator`], ties by id.
+    #[default]
+    Weighted,
+    /// Tasks with more conflicts come first, as they have fewer slots left.
+    /// Ties are ordered like [`TardyOrder::Weighted`].
+    ConflictDegree,
+}
+
+impl TardyOrder {
+    /// Compares two tasks of the instance according to the order.
+    #[must_use]
+    pub fn compare(self, instance: &Instance, first: usize, second: usize) -> Ordering {
+        let tasks = &instance.tasks;
+        let weighted =
+            || weight_density_comparator(&tasks[first], &tasks[second]).then(first.cmp(&second));
+        match self {
+            Self::Weighted => weighted(),
+            Self::ConflictDegree => {
+                let degree = |task| instance.graph.degree(task);
+                degree(second).cmp(&degree(first)).then_with(weighted)
+            }
+        }
+    }
+}
+
 /// Machine is a resource that can be used to process a task.
-/// It's ordered by free time.
+/// It keeps the time it becomes free and the number of tasks scheduled on it.
 #[non_exhaustive]
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct Machine {
     pub id: usize,
     pub free: u64,
+    pub tasks: usize,
 }
 
 impl Machine {
     /// Creates a new machine with free time 0.
     #[must_use]
     pub const fn new(id: usize) -> Self {
-        Self { id, free: 0 }
+        Self::with_free_time(id, 0, 0)
     }
 
-    const fn with_free_time(id: usize, free: u64) -> Self {
-        Self { id, free }
+    const fn with_free_time(id: usize, free: u64, tasks: usize) -> Self {
+        Self { id, free, tasks }
     }
 }
 
-impl PartialOrd<Self> for Machine {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
+/// Set of machines ordered by free time, ties are broken by its [`MachineOrder`].
+/// The order is kept by the set, so a taken machine can be changed and inserted back.
+#[derive(Clone, Debug, Default)]
+pub struct Machines {
+    order: MachineOrder,
+    machines: BTreeMap<(u64, usize, usize), Machine>,
 }
 
-impl Ord for Machine {
-    fn cmp(&self, other: &Self) -> Ordering {
-        match self.free.cmp(&other.free) {
-            Ordering::Equal => self.id.cmp(&other.id),
-            order => order,
+impl Machines {
+    /// Creates `count` machines free at time 0, ordered by the given order.
+    #[must_use]
+    pub fn new(count: usize, order: MachineOrder) -> Self {
+        let mut machines = Self {
+            order,
+            machines: BTreeMap::new(),
+        };
+        for id in 0..count {
+            machines.insert(Machine::new(id));
         }
+        machines
+    }
+
+    /// Inserts a machine, ordered by its current free time.
+    pub fn insert(&mut self, machine: Machine) {
+        self.machines.insert(self.order.key(&machine), machine);
+    }
+
+    /// Returns the first machine.
+    #[must_use]
+    pub fn first(&self) -> Option<&Machine> {
+        self.machines.values().next()
+    }
+
+    /// Takes the first machine from the set.
+    pub fn pop_first(&mut self) -> Option<Machine> {
+        self.machines.pop_first().map(|(_, machine)| machine)
+    }
+
+    /// Takes the first machine from the set on which the task may run.
+    /// Ineligible machines which are free earlier are skipped.
+    pub fn pop_eligible(&mut self, task: &Task) -> Option<Machine> {
+        let (&key, _) = self
+            .machines
+            .iter()
+            .find(|(_, machine)| task.is_eligible(machine.id))?;
+        self.machines.remove(&key)
+    }
+
+    /// Returns the number of machines in the set.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.machines.len()
+    }
+
+    /// Returns whether the set has no machines.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.machines.is_empty()
     }
 }
 
 /// Compares two tasks by their weight and processing time.
+/// Tasks with higher weight per unit of time come first.
 #[must_use]
 pub fn weighted_task_comparator(first: &TaskWithId, second: &TaskWithId) -> Ordering {
-    (first.1.time * second.1.weight).cmp(&(second.1.time * first.1.weight))
+    weight_density_comparator(&first.1, &second.1)
+}
+
+/// Compares two tasks like [`weighted_task_comparator`], without their ids.
+#[must_use]
+pub fn weight_density_comparator(first: &Task, second: &Task) -> Ordering {
+    let product = |time, weight| u128::from(time) * u128::from(weight);
+    product(first.time, second.weight).cmp(&product(second.time, first.weight))
 }
 
 /// A builder for creating a schedule.
@@ -55,6 +166,9 @@ pub struct ScheduleBuilder<'a> {
     schedule: Schedule<'a>,
     machines: Vec<Vec<usize>>,
     tardies: Vec<usize>,
+    order: MachineOrder,
+    tardy_order: TardyOrder,
+    score: u64,
 }
 
 impl<'a> ScheduleBuilder<'a> {
@@ -66,13 +180,58 @@ impl<'a> ScheduleBuilder<'a> {
             schedule: Schedule::new(instance),
             machines: vec![Vec::new(); instance.processors],
             tardies: Vec::new(),
+            order: MachineOrder::default(),
+            tardy_order: TardyOrder::default(),
+            score: 0,
+        }
+    }
+
+    /// Creates a schedule builder from an existing schedule.
+    ///
+    /// Tasks are assigned to their machines in order of start time, so later fixes keep the
+    /// schedule. Unscheduled tasks and tasks finishing after the deadline become tardy.
+    #[must_use]
+    pub fn from_schedule(instance: &'a Instance, schedule: &Schedule) -> Self {
+        let mut builder = Self::new(instance);
+        let mut scheduled = Vec::with_capacity(instance.tasks.len());
+
+        for (id, task) in instance.tasks.iter().enumerate() {
+            match schedule.get_schedule(id) {
+                Some(info) if task.fits_before(info.start, instance.deadline) => {
+                    scheduled.push((info.start, id, info.processor));
+                }
+                _ => builder.tardy(id),
+            }
         }
+
+        scheduled.sort_unstable();
+        for (start, id, machine) in scheduled {
+            builder.schedule(id, start, machine);
+        }
+
+        builder
+    }
+
+    /// Sets the order of machines with the same free time.
+    #[must_use]
+    pub const fn with_machine_order(mut self, order: MachineOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Sets the order in which tardy tasks are placed again.
+    #[must_use]
+    pub const fn with_tardy_order(mut self, order: TardyOrder) -> Self {
+        self.tardy_order = order;
+        self
     }
 
     /// Schedules a task on a machine at a given time.
     /// Time must be within deadline and bigger than the last task.
     pub fn schedule(&mut self, id: usize, time: u64, machine: usize) {
+        self.unschedule(id);
         self.schedule.schedule(id, ScheduleInfo::new(time, machine));
+        self.score += self.on_time_weight(id);
         self.machines[machine].push(id);
     }
 
@@ -106,27 +265,54 @@ impl<'a> ScheduleBuilder<'a> {
         self.tardies.len()
     }
 
-    /// Calculates the score of the schedule.
+    /// Returns the instance being scheduled.
+    #[must_use]
+    pub const fn instance(&self) -> &'a Instance {
+        self.instance
+    }
+
+    /// Returns the tardy task at the given index.
+    #[must_use]
+    pub fn tardy_task(&self, index: usize) -> usize {
+        self.tardies[index]
+    }
+
+    /// Returns the task at the given index of a machine.
+    #[must_use]
+    pub fn machine_task(&self, machine: usize, index: usize) -> usize {
+        self.machines[machine][index]
+    }
+
+    /// Returns the `(machine, index)` position of a scheduled task.
+    #[must_use]
+    pub fn position(&self, task: usize) -> Option<(usize, usize)> {
+        let machine = self.schedule.get_schedule(task)?.processor;
+        let index = self.machines[machine].iter().position(|&id| id == task)?;
+        Some((machine, index))
+    }
+
+    /// Returns the score of the schedule.
+    /// The score is kept up to date as tasks are scheduled, so it takes constant time.
     #[must_use]
     pub fn calculate_score(&self) -> u64 {
-        self.schedule.calculate_score()
+        debug_assert_eq!(self.score, self.schedule.calculate_score());
+        self.score
     }
 
     /// Creates an ordered set of machines with order of free time.
+    /// Ties are broken by the machine order of the builder.
     #[must_use]
-    pub fn new_machine_free_times(&self) -> BTreeSet<Machine> {
-        self.machines
-            .iter()
-            .enumerate()
-            .map(|(id, tasks)| {
-                let free = tasks
-                    .last()
-                    .and_then(|&task| self.schedule.get_schedule(task).map(|info| (task, info)))
-                    .map(|(task, info)| info.start + self.instance.tasks[task].time)
-                    .unwrap_or_default();
-                Machine::with_free_time(id, free)
-            })
-            .collect()
+    pub fn new_machine_free_times(&self) -> Machines {
+        let mut machines = Machines::new(0, self.order);
+        for (id, tasks) in self.machines.iter().enumerate() {
+            let free = tasks
+                .last()
+                .and_then(|&task| self.schedule.get_schedule(task).map(|info| (task, info)))
+                .map(|(task, info)| info.end(&self.instance.tasks[task]))
+                .unwrap_or_default();
+            machines.insert(Machine::with_free_time(id, free, tasks.len()));
+        }
+        machines
     }
 
     /// Check if the given task with the given start time is in conflict with another task.
@@ -136,22 +322,36 @@ impl<'a> ScheduleBuilder<'a> {
     }
 
     /// Calculates first available time for a task that is not in conflict with other tasks.
+    /// The time is never earlier than the release time of the task.
     /// It returns None if there is no available time within deadline.
     #[must_use]
     pub fn calculate_non_conflict_time(&self, task: usize, minimum_time: u64) -> Option<u64> {
-        self.instance
-            .graph
-            .conflicts(task)
-            .iter()
+        let data = &self.instance.tasks[task];
+        let mut start = minimum_time.max(data.release);
+
+        // Sweeping the intervals by start time, every overlapping one pushes the task behind it.
+        for (begin, end) in self.conflict_intervals(task) {
+            if begin >= data.completion(start) {
+                break;
+            }
+            start = start.max(end);
+        }
+
+        Some(start).filter(|&start| data.fits_before(start, self.instance.deadline))
+    }
+
+    /// Returns the `(start, end)` intervals of scheduled tasks in conflict with the task,
+    /// ordered by start time.
+    fn conflict_intervals(&self, task: usize) -> Vec<(u64, u64)> {
+        let conflicts = self.instance.graph.conflicts(task).iter();
+        let mut intervals: Vec<_> = conflicts
             .filter_map(|&other| {
-                let t = self.instance.tasks[other].time;
-                self.schedule.get_schedule(other).map(|info| info.start + t)
+                let info = self.schedule.get_schedule(other)?;
+                Some((info.start, info.end(&self.instance.tasks[other])))
             })
-            .filter(|&time| time >= minimum_time)
-            .filter(|&time| !self.schedule.in_conflict(task, time))
-            .min()
-            .or(Some(minimum_time))
-            .filter(|&time| time + self.instance.tasks[task].time <= self.instance.deadline)
+            .collect();
+        intervals.sort_unstable();
+        intervals
     }
 
     /// Reorganizes the schedule using the given operations.
@@ -164,13 +364,15 @@ impl<'a> ScheduleBuilder<'a> {
         let (machines, tardy) = op(&mut self.machines, &mut self.tardies);
 
         for task in tardy {
-            self.schedule.remove_schedule(task);
+            self.unschedule(task);
         }
 
-        for (machine, index) in &machines {
-            for &task in &self.machines[*machine][*index..] {
-                self.schedule.remove_schedule(task);
+        for &(machine, index) in &machines {
+            let tasks = std::mem::take(&mut self.machines[machine]);
+            for &task in &tasks[index..] {
+                self.unschedule(task);
             }
+            self.machines[machine] = tasks;
         }
 
         for (machine, index) in machines {
@@ -180,6 +382,95 @@ impl<'a> ScheduleBuilder<'a> {
         self.fix_tardy();
     }
 
+    /// Moves scheduled tasks earlier into idle gaps of their machines.
+    ///
+    /// It applies [`Schedule::compact`] to the built schedule. Machines and the order of their
+    /// tasks are kept, so the score does not change. Returns whether any task moved.
+    pub fn compact(&mut self) -> bool {
+        self.schedule.compact()
+    }
+
+    /// Removes a task from its machine or the tardy tasks and fixes the schedule.
+    /// The task is neither scheduled nor tardy afterwards, it can be added back with
+    /// [`Self::move_task`]. Returns false if the task was neither scheduled nor tardy.
+    pub fn remove_task(&mut self, task: usize) -> bool {
+        let source = self.position(task);
+        let found = source.is_some() || self.tardies.contains(&task);
+        self.unschedule(task);
+
+        self.reorganize_schedule(|machines, tardy_tasks| {
+            if let Some((machine, index)) = source {
+                machines[machine].remove(index);
+                (vec![(machine, index)], vec![])
+            } else {
+                tardy_tasks.retain(|&id| id != task);
+                (vec![], vec![])
+            }
+        });
+
+        found
+    }
+
+    /// Moves a task to the given position of a machine and fixes the schedule.
+    /// The task may be scheduled, tardy or removed. The position is counted after the task
+    /// is taken from its current place.
+    ///
+    /// Returns whether the task ends up scheduled on the machine. Invalid positions
+    /// leave the schedule unchanged.
+    pub fn move_task(&mut self, task: usize, machine: usize, position: usize) -> bool {
+        let source = self.position(task);
+        let Some(tasks) = self.machines.get(machine) else {
+            return false;
+        };
+        let len = tasks.len() - usize::from(source.is_some_and(|(from, _)| from == machine));
+        if position > len {
+            return false;
+        }
+
+        self.reorganize_schedule(|machines, tardy_tasks| {
+            let mut fixes = Vec::with_capacity(2);
+            match source {
+                // Fixing a machine once from the earlier index covers both changes.
+                Some((from, index)) if from == machine => {
+                    machines[from].remove(index);
+                    fixes.push((from, index.min(position)));
+                }
+                Some((from, index)) => {
+                    machines[from].remove(index);
+                    fixes.push((from, index));
+                    fixes.push((machine, position));
+                }
+                None => {
+                    tardy_tasks.retain(|&id| id != task);
+                    fixes.push((machine, position));
+                }
+            }
+            machines[machine].insert(position, task);
+            (fixes, vec![])
+        });
+
+        self.position(task)
+            .is_some_and(|(scheduled, _)| scheduled == machine)
+    }
+
+    /// Returns the weight of a task if it is scheduled before the deadline.
+    fn on_time_weight(&self, task: usize) -> u64 {
+        let data = &self.instance.tasks[task];
+        let info = self.schedule.get_schedule(task);
+        let on_time = info.is_some_and(|info| data.fits_before(info.start, self.instance.deadline));
+        if on_time {
+            data.weight
+        } else {
+            0
+        }
+    }
+
+    /// Removes the schedule info of a task and its weight from the score.
+    fn unschedule(&mut self, task: usize) {
+        self.score -= self.on_time_weight(task);
+        self.schedule.remove_schedule(task);
+    }
+
     fn fix_machine(&mut self, machine: usize, index: usize) {
         let mut free = if index == 0 {
             0
@@ -187,16 +478,22 @@ impl<'a> ScheduleBuilder<'a> {
             let task = self.machines[machine][index - 1];
             self.schedule
                 .get_schedule(task)
-                .map(|info| info.start + self.instance.tasks[task].time)
+                .map(|info| info.end(&self.instance.tasks[task]))
                 .unwrap_or_default()
         };
 
         for &task in &self.machines[machine][index..] {
-            let processing_time = self.instance.tasks[task].time;
-            let time = if self.schedule.in_conflict(task, free) {
-                self.calculate_non_conflict_time(task, free)
-            } else if free + processing_time <= self.instance.deadline {
-                Some(free)
+            if !self.instance.tasks[task].is_eligible(machine) {
+                self.tardies.push(task);
+                continue;
+            }
+
+            let data = &self.instance.tasks[task];
+            let start = free.max(data.release);
+            let time = if self.schedule.in_conflict(task, start) {
+                self.calculate_non_conflict_time(task, start)
+            } else if data.fits_before(start, self.instance.deadline) {
+                Some(start)
             } else {
                 None
             };
@@ -204,7 +501,8 @@ impl<'a> ScheduleBuilder<'a> {
             if let Some(time) = time {
                 let info = ScheduleInfo::new(time, machine);
                 self.schedule.schedule(task, info);
-                free = time + processing_time;
+                self.score += data.weight;
+                free = data.completion(time);
             } else {
                 self.tardies.push(task);
             }
@@ -214,9 +512,9 @@ impl<'a> ScheduleBuilder<'a> {
     }
 
     fn fix_tardy(&mut self) {
-        self.tardies.sort_unstable_by(|&a, &b| {
-            weighted_task_comparator(&(a, self.instance.tasks[a]), &(b, self.instance.tasks[b]))
-        });
+        let (instance, order) = (self.instance, self.tardy_order);
+        self.tardies
+            .sort_unstable_by(|&a, &b| order.compare(instance, a, b));
 
         let mut machines = self.new_machine_free_times();
         let mut tasks = Vec::new();
@@ -224,21 +522,24 @@ impl<'a> ScheduleBuilder<'a> {
         std::mem::swap(&mut self.tardies, &mut tasks);
 
         for task in tasks {
-            let Some(mut machine) = machines.pop_first() else {
-                unreachable!("Machine number is always greater than 0")
+            let Some(mut machine) = machines.pop_eligible(&self.instance.tasks[task]) else {
+                self.tardy(task);
+                continue;
             };
 
-            let time = if self.in_conflict(task, machine.free) {
-                self.calculate_non_conflict_time(task, machine.free)
-            } else if machine.free + self.instance.tasks[task].time <= self.instance.deadline {
-                Some(machine.free)
+            let start = machine.free.max(self.instance.tasks[task].release);
+            let time = if self.in_conflict(task, start) {
+                self.calculate_non_conflict_time(task, start)
+            } else if self.instance.tasks[task].fits_before(start, self.instance.deadline) {
+                Some(start)
             } else {
                 None
             };
 
             if let Some(time) = time {
                 self.schedule(task, time, machine.id);
-                machine.free = time + self.instance.tasks[task].time;
+                machine.free = self.instance.tasks[task].completion(time);
+                machine.tasks += 1;
             } else {
                 self.tardy(task);
             }
@@ -248,8 +549,240 @@ impl<'a> ScheduleBuilder<'a> {
     }
 }
 
+impl<'a> ScheduleBuilder<'a> {
+    /// Returns the built schedule after checking it is valid.
+    /// Unlike the conversion into [`Schedule`], it catches moves that broke the schedule.
+    ///
+    /// # Errors
+    /// - If the schedule is invalid, see [`Schedule::verify_detailed`].
+    pub fn try_finish(self) -> Result<Schedule<'a>, VerifyError> {
+        self.schedule.verify_detailed()?;
+        Ok(self.schedule)
+    }
+}
+
 impl<'a> From<ScheduleBuilder<'a>> for Schedule<'a> {
     fn from(builder: ScheduleBuilder<'a>) -> Self {
         builder.schedule
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::core::Conflict;
+
+    #[test]
+    fn comparator_should_not_overflow() {
+        let first = (0, Task::new(u64::MAX, u64::MAX - 1));
+        let second = (1, Task::new(u64::MAX - 1, u64::MAX - 2));
+
+        // The second task has slightly lower weight per unit of time.
+        assert_eq!(weighted_task_comparator(&first, &second), Ordering::Less);
+        assert_eq!(weighted_task_comparator(&second, &first), Ordering::Greater);
+        assert_eq!(weighted_task_comparator(&first, &first), Ordering::Equal);
+    }
+
+    #[test]
+    fn tardy_order_should_break_ties_by_id() {
+        let tasks = vec![Task::new(2, 4), Task::new(1, 2), Task::new(1, 3)];
+        let instance = Instance::new(1, 4, tasks, Vec::new());
+
+        let order = TardyOrder::Weighted;
+        assert_eq!(order.compare(&instance, 0, 1), Ordering::Less);
+        assert_eq!(order.compare(&instance, 1, 0), Ordering::Greater);
+        assert_eq!(order.compare(&instance, 2, 0), Ordering::Less);
+    }
+
+    #[test]
+    fn machine_order_should_break_ties() {
+        let task = |time| Task::new(time, 1);
+        let instance = Instance::new_no_conflict(2, 10, vec![task(2), task(2), task(4)]);
+
+        let mut builder = ScheduleBuilder::new(&instance);
+        builder.schedule(0, 0, 0);
+        builder.schedule(1, 2, 0);
+        builder.schedule(2, 0, 1);
+        let first = builder.new_machine_free_times().first().map(|m| m.id);
+        assert_eq!(first, Some(0));
+
+        let builder = builder.with_machine_order(MachineOrder::FewestTasks);
+        let first = builder.new_machine_free_times().first().map(|m| m.id);
+        assert_eq!(first, Some(1));
+    }
+
+    #[test]
+    fn machines_should_reorder_changed_machine() {
+        let mut machines = Machines::new(2, MachineOrder::FewestTasks);
+        let Some(mut machine) = machines.pop_first() else {
+            unreachable!("Machines are not empty");
+        };
+        assert_eq!(machine.id, 0);
+
+        // Free at the same time, the machine with fewer tasks comes first.
+        machine.tasks += 1;
+        machines.insert(machine);
+        assert_eq!(machines.len(), 2);
+        assert_eq!(machines.pop_first().map(|m| m.id), Some(1));
+        assert_eq!(machines.pop_first(), Some(machine));
+        assert!(machines.is_empty());
+    }
+
+    #[test]
+    fn conflict_degree_should_place_constrained_tardies_first() {
+        let task = |weight| Task::new(1, weight);
+        let tasks = vec![task(2), task(1), task(1)];
+        let instance = Instance::new(2, 2, tasks, vec![Conflict::new(1, 2)]);
+
+        let mut builder = ScheduleBuilder::new(&instance);
+        builder.schedule(2, 1, 0);
+        builder.tardy(0);
+        builder.tardy(1);
+
+        // Task 0 takes the only slot where task 1 does not overlap task 2.
+        let mut weighted = builder.clone();
+        weighted.reorganize_schedule(|_, _| (Vec::new(), Vec::new()));
+        assert_eq!(weighted.tardy_len(), 1);
+        assert_eq!(weighted.calculate_score(), 3);
+
+        let mut degree = builder.with_tardy_order(TardyOrder::ConflictDegree);
+        degree.reorganize_schedule(|_, _| (Vec::new(), Vec::new()));
+        assert_eq!(degree.tardy_len(), 0);
+        assert_eq!(degree.calculate_score(), 4);
+        assert!(Schedule::from(degree).verify());
+    }
+
+    #[test]
+    fn tasks_should_move_between_machines_and_tardy() {
+        let task = |time| Task::new(time, 1);
+        let tasks = vec![task(2), task(2), task(4)];
+        let instance = Instance::new(2, 6, tasks, vec![Conflict::new(1, 2)]);
+
+        let mut builder = ScheduleBuilder::new(&instance);
+        builder.schedule(0, 0, 0);
+        builder.schedule(1, 2, 0);
+        builder.schedule(2, 2, 1);
+
+        // Task 2 does not fit after task 1 on machine 0, so it becomes tardy.
+        assert!(!builder.move_task(2, 0, 2));
+        assert_eq!(builder.tardy_len(), 1);
+        assert!(Schedule::from(builder.clone()).verify());
+
+        assert!(builder.remove_task(1));
+        assert!(builder.move_task(2, 0, 1));
+        assert_eq!(builder.get_schedule(2), Some(&ScheduleInfo::new(2, 0)));
+        assert!(builder.move_task(1, 1, 0));
+        assert!(!builder.move_task(1, 1, 2));
+        assert!(Schedule::from(builder.clone()).verify());
+        assert_eq!(builder.calculate_score(), 3);
+    }
+
+    #[test]
+    fn compact_should_fill_gaps_before_tasks() {
+        let task = |time, release| Task {
+            release,
+            ..Task::new(time, 1)
+        };
+        let tasks = vec![task(2, 0), task(2, 0), task(3, 1), task(1, 0)];
+        let instance = Instance::new(2, 8, tasks, vec![Conflict::new(1, 2)]);
+
+        let mut builder = ScheduleBuilder::new(&instance);
+        builder.schedule(0, 3, 0);
+        builder.schedule(1, 6, 0);
+        builder.schedule(2, 2, 1);
+        builder.schedule(3, 7, 1);
+        let score = builder.calculate_score();
+
+        assert!(builder.compact());
+        assert_eq!(builder.get_schedule(0), Some(&ScheduleInfo::new(0, 0)));
+        assert_eq!(builder.get_schedule(2), Some(&ScheduleInfo::new(1, 1)));
+        // Task 1 waits for the conflicting task 2 to finish.
+        assert_eq!(builder.get_schedule(1), Some(&ScheduleInfo::new(4, 0)));
+        assert_eq!(builder.get_schedule(3), Some(&ScheduleInfo::new(4, 1)));
+        assert_eq!(builder.position(1), Some((0, 1)));
+        assert_eq!(builder.calculate_score(), score);
+        assert!(Schedule::from(builder.clone()).verify());
+
+        assert!(!builder.compact());
+    }
+
+    #[test]
+    fn try_finish_should_verify_schedule() {
+        let task = Task::new(2, 1);
+        let instance = Instance::new_no_conflict(1, 6, vec![task; 2]);
+
+        let mut builder = ScheduleBuilder::new(&instance);
+        builder.schedule(0, 0, 0);
+        builder.schedule(1, 2, 0);
+        assert!(builder.clone().try_finish().is_ok());
+
+        // Scheduling does not check the machine, so the tasks overlap.
+        builder.schedule(1, 1, 0);
+        assert_eq!(builder.try_finish(), Err(VerifyError::Overlap(0, 1)));
+    }
+
+    #[test]
+    fn builder_should_keep_schedule_order() {
+        let task = |time| Task::new(time, 1);
+        let instance = Instance::new_no_conflict(2, 6, vec![task(2), task(2), task(3), task(2)]);
+
+        let mut schedule = Schedule::new(&instance);
+        schedule.schedule(0, ScheduleInfo::new(4, 0));
+        schedule.schedule(1, ScheduleInfo::new(0, 0));
+        schedule.schedule(2, ScheduleInfo::new(1, 1));
+        schedule.schedule(3, ScheduleInfo::new(5, 1));
+
+        let builder = ScheduleBuilder::from_schedule(&instance, &schedule);
+        assert_eq!(builder.position(1), Some((0, 0)));
+        assert_eq!(builder.position(0), Some((0, 1)));
+        assert_eq!(builder.position(2), Some((1, 0)));
+        assert_eq!(builder.tardy_len(), 1);
+        assert_eq!(builder.tardy_task(0), 3);
+
+        schedule.remove_schedule(3);
+        assert_eq!(Schedule::from(builder), schedule);
+    }
+
+    #[test]
+    fn non_conflict_time_should_be_first_free_candidate() {
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let tasks: Vec<_> = (0..300)
+            .map(|_| Task::new(rng.gen_range(1..=10), 1))
+            .collect();
+        let pairs = (0..300).flat_map(|i| std::iter::repeat(i).zip(i + 1..300));
+        let conflicts = pairs
+            .filter(|_| rng.gen_bool(0.5))
+            .map(|(i, j)| Conflict::new(i, j))
+            .collect();
+        let instance = Instance::new(1, 1000, tasks, conflicts);
+
+        let mut builder = ScheduleBuilder::new(&instance);
+        for task in 0..150 {
+            builder.schedule(task, rng.gen_range(0..1000), 0);
+        }
+
+        // The smallest end of a conflicting task after which the task fits without conflicts.
+        let expected = |task: usize, minimum: u64| {
+            let conflicts = instance.graph.conflicts(task).iter();
+            conflicts
+                .filter_map(|&other| {
+                    let info = builder.get_schedule(other)?;
+                    Some(info.end(&instance.tasks[other]))
+                })
+                .filter(|&time| time >= minimum && !builder.in_conflict(task, time))
+                .min()
+                .filter(|&time| instance.tasks[task].fits_before(time, instance.deadline))
+        };
+
+        for task in 150..300 {
+            for minimum in (0..1000).step_by(50) {
+                if builder.in_conflict(task, minimum) {
+                    let time = builder.calculate_non_conflict_time(task, minimum);
+                    assert_eq!(time, expected(task, minimum));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use super::{de, ser};
+use crate::core::{Instance, Schedule, ScheduleInfo};
+use serde::de::Error as _;
+use serde::{Deserialize, Serialize};
+use std::io::BufRead;
+
+/// An instance with the placements of a schedule, read by [`deserialize_bundled`].
+///
+/// A schedule borrows its instance, so it is not stored but created by [`Bundle::schedule`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bundle {
+    pub instance: Instance,
+    placements: Vec<Option<ScheduleInfo>>,
+}
+
+impl Bundle {
+    /// Returns the schedule of the bundled instance.
+    #[must_use]
+    pub fn schedule(&self) -> Schedule<'_> {
+        let mut schedule = Schedule::new(&self.instance);
+        for (task, info) in self.placements.iter().enumerate() {
+            if let Some(info) = info {
+                schedule.schedule(task, *info);
+            }
+        }
+        schedule
+    }
+}
+
+/// Serialize an instance followed by its schedule, so the result is readable on its own.
+///
+/// # Errors
+/// - If the instance or the schedule cannot be serialized.
+pub fn to_string_bundled(instance: &Instance, schedule: &Schedule) -> ser::Result<String> {
+    let mut serializer = ser::Serializer::default();
+    instance.serialize(&mut serializer)?;
+    schedule.serialize(&mut serializer)?;
+    Ok(serializer.finish())
+}
+
+/// Deserialize an instance followed by its schedule, written by [`to_string_bundled`].
+///
+/// # Errors
+/// - If the instance or the schedule is not valid.
+/// - If the schedule does not place every task of the instance or refers to a missing processor.
+pub fn deserialize_bundled<I: BufRead>(input: &mut I) -> de::Result<Bundle> {
+    let mut deserializer = de::Deserializer::new(input);
+    let instance =
+        Instance::deserialize(&mut deserializer).map_err(|error| deserializer.locate(error))?;
+    let placements = Vec::<Option<ScheduleInfo>>::deserialize(&mut deserializer)
+        .map_err(|error| deserializer.locate(error))?;
+
+    if placements.len() != instance.tasks.len() {
+        return Err(de::Error::custom(format!(
+            "schedule has {} tasks, but the instance has {}",
+            placements.len(),
+            instance.tasks.len()
+        )));
+    }
+    if let Some(info) = placements
+        .iter()
+        .flatten()
+        .find(|info| info.processor >= instance.processors)
+    {
+        return Err(de::Error::custom(format!(
+            "schedule refers to processor {}, but there are only {} processors",
+            info.processor, instance.processors
+        )));
+    }
+
+    Ok(Bundle {
+        instance,
+        placements,
+    })
+}
+
+/// Deserialize a schedule of the instance, written by [`super::to_string`].
+///
+/// Placements are not checked beyond the number of tasks, use [`Schedule::verify_detailed`].
+///
+/// # Errors
+/// - If the schedule is not valid.
+/// - If the schedule does not place every task of the instance.
+pub fn deserialize_schedule<'a, I: BufRead>(
+    input: &mut I,
+    instance: &'a Instance,
+) -> de::Result<Schedule<'a>> {
+    let placements: Vec<Option<ScheduleInfo>> = super::deserialize(input)?;
+    if placements.len() != instance.tasks.len() {
+        return Err(de::Error::custom(format!(
+            "schedule has {} tasks, but the instance has {}",
+            placements.len(),
+            instance.tasks.len()
+        )));
+    }
+
+    let mut schedule = Schedule::new(instance);
+    for (task, info) in placements.into_iter().enumerate() {
+        if let Some(info) = info {
+            schedule.schedule(task, info);
+        }
+    }
+    Ok(schedule)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::core::Scheduler;
+    use std::io::BufReader;
+
+    #[test]
+    fn bundle_should_round_trip() -> anyhow::Result<()> {
+        let file = std::fs::File::open("samples/3_50_0.in")?;
+        let instance: Instance = crate::data::deserialize(&mut BufReader::new(file))?;
+        let schedule = crate::algo::List.schedule(&instance);
+
+        let bundled = to_string_bundled(&instance, &schedule)?;
+        assert!(bundled.starts_with(&crate::data::to_string(&instance)?));
+
+        let bundle = deserialize_bundled(&mut bundled.as_bytes())?;
+        assert_eq!(bundle.instance, instance);
+        assert_eq!(
+            bundle.schedule().calculate_score(),
+            schedule.calculate_score()
+        );
+        for task in 0..instance.tasks.len() {
+            assert_eq!(
+                bundle.schedule().get_schedule(task),
+                schedule.get_schedule(task)
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn schedule_should_round_trip() -> anyhow::Result<()> {
+        let file = std::fs::File::open("samples/3_50_0.in")?;
+        let instance: Instance = crate::data::deserialize(&mut BufReader::new(file))?;
+        let schedule = crate::algo::List.schedule(&instance);
+
+        let content = crate::data::to_string(&schedule)?;
+        let read = deserialize_schedule(&mut content.as_bytes(), &instance)?;
+        assert_eq!(read, schedule);
+
+        let mut other = instance.clone();
+        other.tasks.push(other.tasks[0].clone());
+        assert!(deserialize_schedule(&mut content.as_bytes(), &other).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn bundle_should_reject_schedule_of_other_instance() -> anyhow::Result<()> {
+        let file = std::fs::File::open("samples/3_50_0.in")?;
+        let instance: Instance = crate::data::deserialize(&mut BufReader::new(file))?;
+        let mut other = instance.clone();
+        other.tasks.push(other.tasks[0].clone());
+        let schedule = crate::algo::List.schedule(&instance);
+
+        let bundled = to_string_bundled(&other, &schedule)?;
+        assert!(deserialize_bundled(&mut bundled.as_bytes()).is_err());
+        Ok(())
+    }
+}
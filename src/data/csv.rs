@@ -0,0 +1,80 @@
+use crate::core::Schedule;
+use crate::data::Report;
+use std::borrow::Cow;
+use std::fmt::Write;
+
+/// Formats the report as CSV with `scheduler,name,score,error,time` columns.
+///
+/// Rows are sorted by the instance name and preceded by a header row.
+#[must_use]
+pub fn report_to_csv(report: &Report) -> String {
+    let mut csv = String::from("scheduler,name,score,error,time\n");
+    let scheduler = escape(report.scheduler_name());
+
+    let mut entries: Vec<_> = report.entries().iter().collect();
+    entries.sort_by(|first, second| first.name.cmp(&second.name));
+
+    for entry in entries {
+        let name = escape(&entry.name);
+        let (score, error, time) = (entry.score, entry.error, entry.time);
+        writeln!(csv, "{scheduler},{name},{score},{error},{time}")
+            .unwrap_or_else(|_| unreachable!("Writing to string never fails"));
+    }
+
+    csv
+}
+
+/// Formats the schedule as CSV with `task,processor,start,completion` columns.
+///
+/// Rows are sorted by the task id and preceded by a header row. Tardy tasks are omitted.
+#[must_use]
+pub fn schedule_to_csv(schedule: &Schedule) -> String {
+    let mut csv = String::from("task,processor,start,completion\n");
+    let tasks = &schedule.instance().tasks;
+
+    for (task, data) in tasks.iter().enumerate() {
+        if let Some(info) = schedule.get_schedule(task) {
+            let (processor, start) = (info.processor, info.start);
+            let completion = info.end(data);
+            writeln!(csv, "{task},{processor},{start},{completion}")
+                .unwrap_or_else(|_| unreachable!("Writing to string never fails"));
+        }
+    }
+
+    csv
+}
+
+/// Quotes the field if it contains a separator, a quote or a line break.
+fn escape(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::core::{Instance, ScheduleInfo, Task};
+
+    #[test]
+    fn schedule_should_be_sorted_by_task() {
+        let task = |time| Task::new(time, 1);
+        let instance = Instance::new(2, 4, vec![task(2), task(1), task(3)], vec![]);
+
+        let mut schedule = Schedule::new(&instance);
+        schedule.schedule(2, ScheduleInfo::new(1, 1));
+        schedule.schedule(0, ScheduleInfo::new(0, 0));
+
+        let expected = "task,processor,start,completion\n0,0,0,2\n2,1,1,4\n";
+        assert_eq!(schedule_to_csv(&schedule), expected);
+    }
+
+    #[test]
+    fn fields_should_be_escaped() {
+        assert_eq!(escape("plain"), "plain");
+        assert_eq!(escape("a,b"), "\"a,b\"");
+        assert_eq!(escape("a\"b"), "\"a\"\"b\"");
+    }
+}
@@ -28,6 +28,13 @@ pub enum Error {
     ParseFloatErr(#[from] std::num::ParseFloatError),
     #[error("parse char error: {0}")]
     ParseCharErr(#[from] std::char::ParseCharError),
+    #[error("line {line}, column {column} (byte {offset}): {source}")]
+    At {
+        line: usize,
+        column: usize,
+        offset: usize,
+        source: Box<Self>,
+    },
 }
 
 impl serde::de::Error for Error {
@@ -42,7 +49,16 @@ pub(super) type Result<T> = std::result::Result<T, Error>;
 #[derive(Debug)]
 pub struct Deserializer<'a, R: BufRead> {
     source: &'a mut R,
-    buffer: VecDeque<String>,
+    buffer: VecDeque<(usize, String)>,
+    line_end: bool,
+    /// Number of the current line, counted from 1.
+    line: usize,
+    /// Column of the last read value, counted from 1.
+    column: usize,
+    /// Byte offset of the start of the current line.
+    line_offset: usize,
+    /// Number of bytes read from the source.
+    consumed: usize,
 }
 
 impl<'a, R: BufRead> Deserializer<'a, R> {
@@ -51,6 +67,33 @@ impl<'a, R: BufRead> Deserializer<'a, R> {
         Self {
             source,
             buffer: VecDeque::new(),
+            line_end: false,
+            line: 0,
+            column: 1,
+            line_offset: 0,
+            consumed: 0,
+        }
+    }
+
+    /// Skips blank lines and returns whether the whole input was consumed.
+    pub(super) fn is_finished(&mut self) -> Result<bool> {
+        while self.buffer.iter().all(|(_, value)| value.is_empty()) {
+            self.buffer.clear();
+            if self.source.fill_buf()?.is_empty() {
+                return Ok(true);
+            }
+            self.load_line()?;
+        }
+        Ok(false)
+    }
+
+    /// Wraps the error with the position of the last read value.
+    pub(super) fn locate(&self, error: Error) -> Error {
+        Error::At {
+            line: self.line.max(1),
+            column: self.column,
+            offset: self.line_offset + self.column - 1,
+            source: Box::new(error),
         }
     }
 
@@ -59,7 +102,10 @@ impl<'a, R: BufRead> Deserializer<'a, R> {
             self.load_line()?;
         }
 
-        self.buffer.pop_front().ok_or(Error::UnexpectedEmptyLine)
+        let (column, value) = self.buffer.pop_front().ok_or(Error::UnexpectedEmptyLine)?;
+        self.column = column;
+        self.line_end = self.buffer.is_empty() && !value.is_empty();
+        Ok(value)
     }
 
     fn peek_next(&mut self) -> Result<&String> {
@@ -67,20 +113,30 @@ impl<'a, R: BufRead> Deserializer<'a, R> {
             self.load_line()?;
         }
 
-        self.buffer.front().ok_or(Error::UnexpectedEmptyLine)
+        let value = self.buffer.front().ok_or(Error::UnexpectedEmptyLine)?;
+        Ok(&value.1)
     }
 
     fn load_line(&mut self) -> Result<()> {
         let mut line = String::new();
+        let read = self.source.read_line(&mut line)?;
 
-        if self.source.read_line(&mut line)? == 0 {
+        if read == 0 {
+            self.column = self.consumed - self.line_offset + 1;
             return Err(Error::UnexpectedEndOfInput);
         }
 
+        self.line += 1;
+        self.column = 1;
+        self.line_offset = self.consumed;
+        self.consumed += read;
+
         let trimmed = line.trim();
+        let mut column = line.len() - line.trim_start().len() + 1;
 
-        for line in trimmed.split(' ') {
-            self.buffer.push_back(line.to_string());
+        for value in trimmed.split(' ') {
+            self.buffer.push_back((column, value.to_string()));
+            column += value.len() + 1;
         }
 
         Ok(())
@@ -103,6 +159,11 @@ impl<'a, R: BufRead> serde::de::Deserializer<'a> for &mut Deserializer<'a, R> {
         Err(Error::AnyNotSupported)
     }
 
+    /// Types read the compact form of their fields, like the custom serializer writes them.
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
     fn deserialize_bool<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value> {
         visitor.visit_bool(self.next()?.parse()?)
     }
@@ -190,7 +251,11 @@ impl<'a, R: BufRead> serde::de::Deserializer<'a> for &mut Deserializer<'a, R> {
     }
 
     fn deserialize_option<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value> {
-        if self.peek_next()? == "-" {
+        // A value missing at the end of a non-empty line is treated as `None`,
+        // so trailing optional fields can be omitted.
+        if self.line_end && self.buffer.is_empty() && !self.source.fill_buf()?.is_empty() {
+            visitor.visit_none()
+        } else if self.peek_next()? == "-" {
             self.next()?;
             visitor.visit_none()
         } else {
@@ -273,7 +338,7 @@ impl<'a, R: BufRead> serde::de::Deserializer<'a> for &mut Deserializer<'a, R> {
 /// Struct responsible for deserializing a sequence of data to structs and tuples.
 struct SimpleSeqAccess<'a, 'b, R: BufRead>(&'b mut Deserializer<'a, R>);
 
-impl<'a, 'b, R: BufRead> SeqAccess<'a> for SimpleSeqAccess<'a, 'b, R> {
+impl<'a, R: BufRead> SeqAccess<'a> for SimpleSeqAccess<'a, '_, R> {
     type Error = Error;
 
     fn next_element_seed<T: DeserializeSeed<'a>>(&mut self, seed: T) -> Result<Option<T::Value>> {
@@ -357,6 +422,27 @@ mod tests {
         };
     }
 
+    #[test]
+    fn error_should_contain_position() {
+        let error = crate::data::deserialize::<_, Vec<Vec<u64>>>(&mut Cursor::new("2\n1 2\n3 x\n"));
+        let message = error.map(|_| ()).map_err(|error| error.to_string());
+        assert_eq!(
+            message,
+            Err("line 3, column 3 (byte 8): parse int error: invalid digit found in string".into())
+        );
+
+        let error = crate::data::deserialize::<_, (u64, u64)>(&mut Cursor::new("1\n"));
+        assert!(matches!(
+            error,
+            Err(At {
+                line: 1,
+                column: 3,
+                offset: 2,
+                ..
+            })
+        ));
+    }
+
     #[test]
     fn deserialize_bool() {
         test!(bool, "true false", true, false);
@@ -561,6 +647,11 @@ mod tests {
         test!(Option<i32>, "- 1 -", None, Some(1), None);
     }
 
+    #[test]
+    fn deserialize_option_at_line_end() {
+        test!((i32, Option<i32>), "1\n2 3", (1, None), (2, Some(3)));
+    }
+
     #[test]
     fn deserialize_option_error() {
         test!(err, Option<i32>, "a", ParseIntErr(_), EOF);
@@ -0,0 +1,113 @@
+use crate::core::Conflict;
+use std::io::BufRead;
+use thiserror::Error;
+
+/// Enum representing errors of reading DIMACS graphs.
+#[derive(Debug, Error)]
+pub enum DimacsError {
+    #[error("IO error: {0}")]
+    IOErr(#[from] std::io::Error),
+    #[error("line {0}: malformed line '{1}'")]
+    Malformed(usize, String),
+    #[error("line {0}: edge before the problem line")]
+    MissingProblem(usize),
+    #[error("line {0}: duplicate problem line")]
+    DuplicateProblem(usize),
+    #[error("line {0}: vertex {1} out of range 1..={2}")]
+    VertexOutOfRange(usize, usize, usize),
+    #[error("line {0}: self-loop on vertex {1}")]
+    SelfLoop(usize, usize),
+    #[error("expected {0} edges, found {1}")]
+    EdgeCount(usize, usize),
+}
+
+/// Reads conflicts from a graph in DIMACS edge format.
+///
+/// The graph starts with a `p edge n m` line followed by `m` lines `e u v`.
+/// Vertices are numbered from 1 and translated to task ids from 0.
+/// Lines starting with `c` are comments.
+///
+/// # Errors
+/// - If the input cannot be read.
+/// - If a line is malformed, an edge refers to a missing vertex or the edge count does not match.
+pub fn read_dimacs_conflicts(reader: impl BufRead) -> Result<Vec<Conflict>, DimacsError> {
+    let mut problem = None;
+    let mut conflicts = Vec::new();
+
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let number = index + 1;
+        let fields: Vec<_> = line.split_whitespace().collect();
+        let malformed = || DimacsError::Malformed(number, line.clone());
+
+        match fields.as_slice() {
+            [] | ["c", ..] => {}
+            ["p", _, vertices, edges] => {
+                if problem.is_some() {
+                    return Err(DimacsError::DuplicateProblem(number));
+                }
+                let vertices: usize = vertices.parse().map_err(|_| malformed())?;
+                let edges: usize = edges.parse().map_err(|_| malformed())?;
+                problem = Some((vertices, edges));
+            }
+            ["e", from, to] => {
+                let (vertices, _) = problem.ok_or(DimacsError::MissingProblem(number))?;
+                let vertex = |field: &str| {
+                    let vertex: usize = field.parse().map_err(|_| malformed())?;
+                    if vertex == 0 || vertex > vertices {
+                        return Err(DimacsError::VertexOutOfRange(number, vertex, vertices));
+                    }
+                    Ok(vertex - 1)
+                };
+                let (from, to) = (vertex(from)?, vertex(to)?);
+                if from == to {
+                    return Err(DimacsError::SelfLoop(number, from + 1));
+                }
+                conflicts.push(Conflict::new(from, to));
+            }
+            _ => return Err(malformed()),
+        }
+    }
+
+    match problem {
+        Some((_, edges)) if edges != conflicts.len() => {
+            Err(DimacsError::EdgeCount(edges, conflicts.len()))
+        }
+        _ => Ok(conflicts),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn dimacs_should_be_read() -> Result<(), DimacsError> {
+        let input = "c example\np edge 3 2\ne 1 2\n\ne 3 2\n";
+        let conflicts = read_dimacs_conflicts(input.as_bytes())?;
+        assert_eq!(conflicts, vec![Conflict::new(0, 1), Conflict::new(2, 1)]);
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_dimacs_should_be_rejected() {
+        let read = |input: &str| read_dimacs_conflicts(input.as_bytes()).map_err(|e| e.to_string());
+
+        assert_eq!(
+            read("e 1 2\n"),
+            Err("line 1: edge before the problem line".into())
+        );
+        assert_eq!(
+            read("p edge 2 1\ne 1 x\n"),
+            Err("line 2: malformed line 'e 1 x'".into())
+        );
+        assert_eq!(
+            read("p edge 2 1\ne 1 3\n"),
+            Err("line 2: vertex 3 out of range 1..=2".into())
+        );
+        assert_eq!(
+            read("p edge 2 2\ne 1 2\n"),
+            Err("expected 2 edges, found 1".into())
+        );
+    }
+}
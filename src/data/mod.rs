@@ -1,17 +1,49 @@
+mod bundle;
+mod csv;
 mod de;
+mod dimacs;
 mod run;
 mod ser;
+mod svg;
 
+pub use bundle::{deserialize_bundled, deserialize_schedule, to_string_bundled, Bundle};
+pub use csv::{report_to_csv, schedule_to_csv};
+pub use dimacs::{read_dimacs_conflicts, DimacsError};
 pub use run::*;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use std::io::BufRead;
+use std::io::{BufRead, Read};
+pub use svg::to_svg;
 
 /// Deserialize a value from buffered input.
 ///
 /// # Errors
-/// - If the input is not valid.
+/// - If the input is not valid. The error contains the position of the invalid value.
 pub fn deserialize<'de, I: BufRead, T: Deserialize<'de>>(input: &'de mut I) -> de::Result<T> {
-    T::deserialize(&mut de::Deserializer::new(input))
+    let mut deserializer = de::Deserializer::new(input);
+    T::deserialize(&mut deserializer).map_err(|error| deserializer.locate(error))
+}
+
+/// Deserialize consecutive values from buffered input until its end.
+///
+/// Values are stored one after another without separators, e.g. an instance ends with
+/// the empty line closing its conflicts and the next instance starts on the following line.
+/// Blank lines before a value and at the end of the input are skipped, so values cannot
+/// start with an empty line.
+///
+/// # Errors
+/// - If any value is not valid. The error contains the position of the invalid value.
+pub fn deserialize_many<I: BufRead, T: DeserializeOwned>(input: &mut I) -> de::Result<Vec<T>> {
+    let mut deserializer = de::Deserializer::new(input);
+    let mut values = Vec::new();
+
+    while !deserializer.is_finished()? {
+        let value =
+            T::deserialize(&mut deserializer).map_err(|error| deserializer.locate(error))?;
+        values.push(value);
+    }
+
+    Ok(values)
 }
 
 /// Serialize a value to string.
@@ -24,6 +56,22 @@ pub fn to_string<T: Serialize>(value: &T) -> ser::Result<String> {
     Ok(serializer.finish())
 }
 
+/// Deserialize a value from JSON input.
+///
+/// # Errors
+/// - If the input is not valid JSON of the value.
+pub fn deserialize_json<T: DeserializeOwned>(reader: impl Read) -> serde_json::Result<T> {
+    serde_json::from_reader(reader)
+}
+
+/// Serialize a value to JSON string.
+///
+/// # Errors
+/// - If the value cannot be serialized.
+pub fn to_json<T: Serialize>(value: &T) -> serde_json::Result<String> {
+    serde_json::to_string(value)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -107,6 +155,49 @@ mod tests {
         };
     }
 
+    #[test]
+    fn json_should_match_custom_format() -> anyhow::Result<()> {
+        for entry in std::fs::read_dir("samples")? {
+            let mut reader = std::io::BufReader::new(std::fs::File::open(entry?.path())?);
+            let instance: crate::core::Instance = deserialize(&mut reader)?;
+
+            let json = to_json(&instance)?;
+            let deserialized: crate::core::Instance = deserialize_json(json.as_bytes())?;
+
+            assert_eq!(instance, deserialized);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn instances_should_be_read_from_one_reader() -> anyhow::Result<()> {
+        let mut instances = Vec::new();
+        let mut content = String::new();
+
+        for entry in std::fs::read_dir("samples")? {
+            let mut reader = std::io::BufReader::new(std::fs::File::open(entry?.path())?);
+            let instance: crate::core::Instance = deserialize(&mut reader)?;
+            content.push_str(&to_string(&instance)?);
+            instances.push(instance);
+        }
+
+        let deserialized: Vec<crate::core::Instance> = deserialize_many(&mut content.as_bytes())?;
+        assert_eq!(instances, deserialized);
+
+        let empty: Vec<crate::core::Instance> = deserialize_many(&mut &b""[..])?;
+        assert!(empty.is_empty());
+
+        content.push_str("\n  \n");
+        let deserialized: Vec<crate::core::Instance> = deserialize_many(&mut content.as_bytes())?;
+        assert_eq!(instances, deserialized);
+
+        let empty: Vec<crate::core::Instance> = deserialize_many(&mut &b"\n"[..])?;
+        assert!(empty.is_empty());
+
+        Ok(())
+    }
+
     #[test]
     fn serialize_and_deserialize() {
         test_impl!(UnitStruct, NewType, TupleStruct, Enum, Struct, Advanced);
@@ -1,10 +1,20 @@
-use crate::core::Scheduler;
+use crate::algo::{List, PolynomialTime};
+use crate::cast_u64;
+use crate::core::{capacity_upper_bound, Instance, Schedule, Scheduler, StableHasher};
 use crate::data::deserialize;
 use anyhow::anyhow;
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 use std::fmt::{Display, Formatter, Result};
 use std::fs::File;
 use std::io::BufReader;
+use std::iter::once;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::time::Duration;
 
 /// Report of running a directory of samples.
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -31,6 +41,63 @@ impl Report {
     pub fn entries(&self) -> &[ReportEntry] {
         &self.entries
     }
+
+    /// Adds the entries of another report, e.g. of the same scheduler on another directory.
+    /// Entries of instances with the same name are replaced.
+    pub fn merge(&mut self, other: Self) {
+        for entry in other.entries {
+            self.entries.retain(|old| old.name != entry.name);
+            self.entries.push(entry);
+        }
+    }
+
+    /// Formats a table comparing the reports per instance.
+    ///
+    /// Every row is an instance with the score and error of each scheduler, the best scores
+    /// of a row are marked with `*`. Instances missing in a report are shown as `-`.
+    #[must_use]
+    pub fn comparison_table(reports: &[Self]) -> String {
+        let entries = reports.iter().flat_map(|report| &report.entries);
+        let names: BTreeSet<&str> = entries.map(|entry| entry.name.as_str()).collect();
+
+        let header = reports.iter().map(|report| report.scheduler.clone());
+        let mut rows = vec![once("instance".to_owned())
+            .chain(header)
+            .collect::<Vec<_>>()];
+        for name in names {
+            let entries: Vec<_> = reports
+                .iter()
+                .map(|report| report.entries.iter().find(|entry| entry.name == name))
+                .collect();
+            let best = entries.iter().flatten().map(|entry| entry.score).max();
+
+            let cells = entries.iter().map(|entry| {
+                entry.map_or_else(
+                    || "-".into(),
+                    |entry| {
+                        let mark = if Some(entry.score) == best { "*" } else { "" };
+                        format!("{} ({:.2}){mark}", entry.score, entry.error)
+                    },
+                )
+            });
+            rows.push(once(name.to_owned()).chain(cells).collect());
+        }
+
+        let widths: Vec<_> = (0..=reports.len())
+            .map(|column| rows.iter().map(|row| row[column].len()).max().unwrap_or(0))
+            .collect();
+
+        let mut table = String::new();
+        for row in rows {
+            let cells = row.iter().zip(&widths);
+            let cells: Vec<_> = cells
+                .map(|(cell, width)| format!("{cell:width$}"))
+                .collect();
+            table.push_str(cells.join(" | ").trim_end());
+            table.push('\n');
+        }
+        table
+    }
 }
 
 impl Display for Report {
@@ -42,7 +109,7 @@ impl Display for Report {
         }
 
         let mut entries = self.entries.clone();
-        entries.sort_by(|a, b| parse_number(&a.name).cmp(&parse_number(&b.name)));
+        entries.sort_by_key(|entry| parse_number(&entry.name));
 
         #[allow(clippy::cast_precision_loss)]
         let entries_len = entries.len() as f64;
@@ -60,12 +127,34 @@ impl Display for Report {
             let error = error_sum / entries_len;
 
             writeln!(f, "average time {time:.2}s, average error: {error:.2}")?;
+
+            let (min, max, median) = spread(self.entries.iter().map(|entry| entry.time));
+            writeln!(f, "time min {min:.2}s, max {max:.2}s, median {median:.2}s")?;
+
+            let (min, max, median) = spread(self.entries.iter().map(|entry| entry.error));
+            writeln!(f, "error min {min:.2}, max {max:.2}, median {median:.2}")?;
         }
 
         writeln!(f, "-------------------")
     }
 }
 
+/// Computes minimum, maximum and median of non-empty values.
+/// The median of an even number of values is the mean of the two middle ones.
+fn spread(values: impl Iterator<Item = f64>) -> (f64, f64, f64) {
+    let mut values: Vec<_> = values.collect();
+    values.sort_by(f64::total_cmp);
+
+    let middle = values.len() / 2;
+    let median = if values.len() % 2 == 0 {
+        (values[middle - 1] + values[middle]) / 2.0
+    } else {
+        values[middle]
+    };
+
+    (values[0], values[values.len() - 1], median)
+}
+
 /// Report of running a single sample.
 #[non_exhaustive]
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -74,6 +163,50 @@ pub struct ReportEntry {
     pub score: u64,
     pub error: f64,
     pub time: f64,
+    /// Whether the scheduler exceeded the time limit and the `List` schedule was used instead.
+    #[serde(default)]
+    pub truncated: bool,
+    /// Error of the scheduler, the `List` schedule was used instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure: Option<String>,
+    /// Provable upper bound of the score, if one can be computed for the instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bound: Option<u64>,
+    /// Gap between the score and the bound in percents. Unlike the error, which compares
+    /// with the result in the file name, it is an upper limit of the distance from optimum.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gap: Option<f64>,
+    /// Mean score of repeated runs. The entry then holds the best run and the mean time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mean_score: Option<f64>,
+}
+
+impl ReportEntry {
+    /// Merges entries of repeated runs on the same instance.
+    /// The best run is kept, with the mean time and the mean score of all runs.
+    fn merge_runs(entries: Vec<Self>) -> Option<Self> {
+        #[allow(clippy::cast_precision_loss)]
+        let runs = entries.len() as f64;
+        let time = entries.iter().map(|entry| entry.time).sum::<f64>() / runs;
+        #[allow(clippy::cast_precision_loss)]
+        let mean_score = entries.iter().map(|entry| entry.score as f64).sum::<f64>() / runs;
+
+        let mut entries = entries.into_iter();
+        let first = entries.next()?;
+        // The first of equally good runs is kept.
+        let best = entries.fold(first, |best, entry| {
+            if entry.score > best.score {
+                entry
+            } else {
+                best
+            }
+        });
+        Some(Self {
+            time,
+            mean_score: Some(mean_score),
+            ..best
+        })
+    }
 }
 
 impl Display for ReportEntry {
@@ -82,7 +215,25 @@ impl Display for ReportEntry {
             f,
             "{}: {:.2}s, score: {}, error: {:.2}",
             self.name, self.time, self.score, self.error
-        )
+        )?;
+
+        if let Some(gap) = self.gap {
+            write!(f, ", gap: {gap:.2}")?;
+        }
+
+        if let Some(mean_score) = self.mean_score {
+            write!(f, ", mean: {mean_score:.2}")?;
+        }
+
+        if self.truncated {
+            write!(f, " (timeout)")?;
+        }
+
+        if let Some(failure) = &self.failure {
+            write!(f, " (failed: {failure})")?;
+        }
+
+        Ok(())
     }
 }
 
@@ -124,47 +275,513 @@ pub fn samples(valid: usize, solver: &mut dyn Scheduler) -> anyhow::Result<()> {
 /// - If the schedule is invalid.
 /// - If the score is incorrect.
 pub fn run(dir: &str, valid: usize, solver: &mut dyn Scheduler) -> anyhow::Result<Report> {
+    run_impl(dir, valid, solver, None)
+}
+
+/// Run all samples in the `dir` directory with reproducible seeds.
+///
+/// Before each instance the solver is reseeded with a seed derived from `seed`
+/// and the instance content, so results do not depend on the order of instances.
+///
+/// # Arguments
+/// - `valid` is the maximum number of machines to check validity,
+/// - `solver` is the scheduler to run,
+/// - `seed` is the base seed.
+///
+/// # Errors
+/// - If a file cannot be read.
+///
+/// # Panics
+/// - If the schedule is invalid.
+/// - If the score is incorrect.
+pub fn run_seeded(
+    dir: &str,
+    valid: usize,
+    solver: &mut dyn Scheduler,
+    seed: u64,
+) -> anyhow::Result<Report> {
+    run_impl(dir, valid, solver, Some(seed))
+}
+
+/// Derives a seed for an instance from the base seed and the instance content.
+/// Seeds are the same on every platform and Rust release.
+#[must_use]
+pub fn instance_seed(seed: u64, instance: &Instance) -> u64 {
+    let mut hasher = StableHasher::new();
+    hasher.write(seed);
+    hasher.write(instance.content_hash());
+    hasher.finish()
+}
+
+/// Options of running samples in parallel.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RunOptions {
+    /// Base seed. The schedulers are reseeded before each instance like in [`run_seeded`],
+    /// so the results do not depend on which thread runs which instance.
+    pub seed: Option<u64>,
+    /// Time limit for a single instance. Every instance is scheduled on a separate thread.
+    /// When the scheduler exceeds the limit, the `List` schedule is reported instead and
+    /// the entry is marked as truncated. Schedulers cannot be cancelled, so the timed out
+    /// thread keeps running in the background.
+    pub timeout: Option<Duration>,
+    /// Whether to schedule the first instance once before timing begins.
+    /// Every scheduler created for a worker thread is warmed up before its first timed run.
+    /// With a timeout every run uses a new scheduler, so the instance is scheduled once
+    /// within the timeout instead. The result is discarded, so it only affects reported
+    /// times, not scores.
+    pub warmup: bool,
+    /// Time budget for a single instance, passed to [`Scheduler::try_schedule_with_budget`].
+    /// Anytime schedulers run until it is spent, others ignore it.
+    pub budget: Option<Duration>,
+    /// Called with the number of finished instances, their total and the name of the last one.
+    /// Instances finish in parallel, so calls may come from any thread in any order of names.
+    pub progress: Option<fn(usize, usize, &str)>,
+    /// Order in which the instances are scheduled, all of them are loaded to be sorted.
+    /// Instances run in parallel, so the order is followed only roughly.
+    pub order: InstanceOrder,
+    /// Number of runs of randomized schedulers on each instance, at least one run is done.
+    /// Run `r` uses the base seed plus `r`. The entries keep the best run, the mean time
+    /// and the mean score. Deterministic schedulers run once.
+    pub repeat: usize,
+    /// Whether to read samples from subdirectories too. Entries are named by the path
+    /// relative to the directory, like `small/2_12_0_unit.in`.
+    pub recursive: bool,
+}
+
+/// Order in which instances are scheduled.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum InstanceOrder {
+    /// Instances are ordered by file name.
+    #[default]
+    ByName,
+    /// Instances with more tasks come first.
+    ByTaskCount,
+    /// Instances with denser conflict graphs come first.
+    ByConflictDensity,
+}
+
+impl InstanceOrder {
+    /// Sorts the instances, ties keep their order.
+    fn sort(self, samples: &mut [&Sample]) {
+        let density = |sample: &Sample| {
+            let instance = &sample.instance;
+            instance.graph.density(instance.tasks.len())
+        };
+        match self {
+            Self::ByName => {}
+            Self::ByTaskCount => {
+                samples.sort_by_key(|sample| std::cmp::Reverse(sample.instance.tasks.len()));
+            }
+            Self::ByConflictDensity => {
+                samples.sort_by(|first, second| density(second).total_cmp(&density(first)));
+            }
+        }
+    }
+}
+
+/// Run all samples in the `dir` directory in parallel.
+///
+/// Every worker thread creates its own scheduler with `factory`. The samples are loaded with
+/// [`Samples::load`], use [`run_samples`] to schedule the same samples with many schedulers.
+///
+/// # Arguments
+/// - `valid` is the maximum number of machines to check validity,
+/// - `factory` creates a scheduler,
+/// - `options` are the additional options.
+///
+/// # Errors
+/// - If a file cannot be read.
+///
+/// # Panics
+/// - If the schedule is invalid.
+/// - If the score is incorrect.
+pub fn run_parallel(
+    dir: &str,
+    valid: usize,
+    factory: fn() -> Box<dyn Scheduler>,
+    options: &RunOptions,
+) -> anyhow::Result<Report> {
+    let samples = Samples::load(dir, options.recursive)?;
+    Ok(run_samples(&samples, valid, factory, options))
+}
+
+/// Run the loaded samples in parallel, like [`run_parallel`].
+/// The `recursive` option is ignored, it applies when the samples are loaded.
+///
+/// # Panics
+/// - If the schedule is invalid.
+/// - If the score is incorrect.
+#[must_use]
+pub fn run_samples(
+    samples: &Samples,
+    valid: usize,
+    factory: fn() -> Box<dyn Scheduler>,
+    options: &RunOptions,
+) -> Report {
+    let RunOptions {
+        seed,
+        timeout,
+        warmup,
+        budget,
+        progress,
+        order,
+        repeat,
+        ..
+    } = *options;
+
+    let solver = factory();
     let mut report = Report::new(solver.name().into());
+    let mut samples: Vec<_> = samples.samples.iter().collect();
+    order.sort(&mut samples);
+
+    // With a timeout every run gets a new scheduler on its own thread, so only the
+    // process-wide state is warmed up.
+    if let (true, Some(timeout)) = (warmup, timeout) {
+        if let Some(sample) = first_supported(&samples, solver.as_ref()) {
+            schedule_with_timeout(&sample.instance, factory, (seed, None), timeout);
+        }
+    }
+    let init = || {
+        let mut solver = factory();
+        if warmup && timeout.is_none() {
+            if let Some(sample) = first_supported(&samples, solver.as_ref()) {
+                // The result is discarded, errors are reported by the timed run.
+                let _ = solver.try_schedule(&sample.instance);
+            }
+        }
+        solver
+    };
+
+    let done = AtomicUsize::new(0);
+    let report_progress = |file: &SampleFile| {
+        if let Some(progress) = progress {
+            progress(
+                done.fetch_add(1, Ordering::Relaxed) + 1,
+                samples.len(),
+                &file.name,
+            );
+        }
+    };
+
+    let entries = samples
+        .par_iter()
+        .map_init(init, |solver, &sample| {
+            let Sample {
+                file,
+                instance,
+                bound,
+            } = sample;
+            if !solver.supports(instance) {
+                report_progress(file);
+                return None;
+            }
+
+            let runs = if solver.is_randomized() { repeat } else { 1 };
+            let mut entries: Vec<_> = (0..runs.max(1))
+                .map(|run| {
+                    let seed = seed.map(|seed| seed.wrapping_add(cast_u64(run)));
+                    timeout.map_or_else(
+                        || {
+                            run_file(file, (instance, *bound), valid, |i| {
+                                or_list(i, schedule_seeded(solver.as_mut(), i, seed, budget))
+                            })
+                        },
+                        |timeout| {
+                            run_file(file, (instance, *bound), valid, |i| {
+                                schedule_with_timeout(i, factory, (seed, budget), timeout)
+                            })
+                        },
+                    )
+                })
+                .collect();
+            let entry = if entries.len() > 1 {
+                ReportEntry::merge_runs(entries)
+            } else {
+                entries.pop()
+            };
+            report_progress(file);
+            entry
+        })
+        .collect::<Vec<_>>();
+    report.entries = entries.into_iter().flatten().collect();
+
+    report
+}
+
+/// Returns the first sample with an instance supported by the scheduler.
+fn first_supported<'a>(samples: &[&'a Sample], solver: &dyn Scheduler) -> Option<&'a Sample> {
+    let mut samples = samples.iter().copied();
+    samples.find(|sample| solver.supports(&sample.instance))
+}
+
+/// Instances of a sample directory with the upper bounds of their scores.
+/// The bounds are computed once when loading, so repeated runs and schedulers share them.
+#[derive(Debug)]
+pub struct Samples {
+    samples: Vec<Sample>,
+}
+
+impl Samples {
+    /// Reads the samples in the `dir` directory, sorted by name, and computes their bounds.
+    /// When `recursive` is set, samples in subdirectories are read too.
+    ///
+    /// # Errors
+    /// - If a file cannot be read.
+    pub fn load(dir: &str, recursive: bool) -> anyhow::Result<Self> {
+        let files = sample_files(dir, recursive)?;
+        let samples = files.into_par_iter().map(|file| {
+            let instance = read_instance(&file)?;
+            let bound = upper_bound(&instance);
+            Ok(Sample {
+                file,
+                instance,
+                bound,
+            })
+        });
+        Ok(Self {
+            samples: samples.collect::<anyhow::Result<_>>()?,
+        })
+    }
+
+    /// Returns the number of samples.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Returns whether there are no samples.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+}
+
+/// A sample instance with its file and the upper bound of its score.
+#[derive(Debug)]
+struct Sample {
+    file: SampleFile,
+    instance: Instance,
+    bound: u64,
+}
+
+/// A sample file with the information parsed from its name.
+#[derive(Debug)]
+struct SampleFile {
+    path: PathBuf,
+    name: String,
+    machines: usize,
+    result: u64,
+}
 
+/// Lists `.in` files in the `dir` directory, sorted by name.
+/// When `recursive` is set, files in subdirectories are listed with their relative path.
+fn sample_files(dir: &str, recursive: bool) -> anyhow::Result<Vec<SampleFile>> {
+    let mut files = Vec::new();
+    collect_sample_files(Path::new(dir), "", recursive, &mut files)?;
+    files.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(files)
+}
+
+/// Adds `.in` files in the `dir` directory to `files`, their names start with `prefix`.
+fn collect_sample_files(
+    dir: &Path,
+    prefix: &str,
+    recursive: bool,
+    files: &mut Vec<SampleFile>,
+) -> anyhow::Result<()> {
     for file in std::fs::read_dir(dir)? {
         let file = file?;
 
+        if recursive && file.file_type()?.is_dir() {
+            let prefix = format!("{prefix}{}/", file.file_name().to_string_lossy());
+            collect_sample_files(&file.path(), &prefix, recursive, files)?;
+            continue;
+        }
+
         if file.path().extension() != Some("in".as_ref()) {
             continue;
         }
 
-        let (name, machines, result, is_unit) = parse_filename(&file.file_name())?;
+        let (name, machines, result) = parse_filename(&file.file_name())?;
+        files.push(SampleFile {
+            path: file.path(),
+            name: format!("{prefix}{name}"),
+            machines,
+            result,
+        });
+    }
+
+    Ok(())
+}
+
+/// Reason why the `List` schedule is reported instead of the schedule of the scheduler.
+enum Fallback {
+    None,
+    Timeout,
+    Failure(String),
+}
 
-        if solver.non_unit() || is_unit {
-            let instance = deserialize(&mut BufReader::new(File::open(file.path())?))?;
+/// Schedules the instance, a budget is passed to [`Scheduler::try_schedule_with_budget`].
+/// Without a budget [`Scheduler::try_schedule`] is used. Solver errors are returned in both cases.
+fn schedule_seeded<'a>(
+    solver: &mut dyn Scheduler,
+    instance: &'a Instance,
+    seed: Option<u64>,
+    budget: Option<Duration>,
+) -> anyhow::Result<Schedule<'a>> {
+    if let Some(seed) = seed {
+        solver.set_seed(instance_seed(seed, instance));
+    }
 
-            let time = std::time::Instant::now();
-            let schedule = solver.schedule(&instance);
-            let time = time.elapsed().as_secs_f64();
+    match budget {
+        Some(budget) => solver.try_schedule_with_budget(instance, budget),
+        None => solver.try_schedule(instance),
+    }
+}
 
-            assert!(schedule.verify(), "Invalid schedule created");
+/// Falls back to the `List` schedule if the scheduler failed.
+fn or_list<'a>(
+    instance: &'a Instance,
+    schedule: anyhow::Result<Schedule<'a>>,
+) -> (Schedule<'a>, Fallback) {
+    match schedule {
+        Ok(schedule) => (schedule, Fallback::None),
+        Err(error) => (
+            List.schedule(instance),
+            Fallback::Failure(error.to_string()),
+        ),
+    }
+}
 
-            let score = schedule.calculate_score();
-            if valid >= machines {
-                assert_eq!(score, result, "Invalid score {name}");
-            }
+/// Schedules the instance on a new thread, falling back to `List` after `timeout`
+/// or if the scheduler fails or panics. Returns the schedule and the reason of the fallback.
+fn schedule_with_timeout(
+    instance: &Instance,
+    factory: fn() -> Box<dyn Scheduler>,
+    (seed, budget): (Option<u64>, Option<Duration>),
+    timeout: Duration,
+) -> (Schedule<'_>, Fallback) {
+    let (sender, receiver) = mpsc::channel();
+    let shared = Arc::new(instance.clone());
 
-            #[allow(clippy::cast_precision_loss)]
-            let error = 100.0 - (100 * score) as f64 / result as f64;
+    std::thread::spawn(move || {
+        let schedule = schedule_seeded(factory().as_mut(), &shared, seed, budget);
+        let infos = schedule.map_err(|error| error.to_string()).map(|schedule| {
+            let tasks = 0..shared.tasks.len();
+            tasks
+                .map(|task| schedule.get_schedule(task).copied())
+                .collect::<Vec<_>>()
+        });
+        // The receiver is gone if the time limit was exceeded.
+        let _ = sender.send(infos);
+    });
 
-            report.entries.push(ReportEntry {
-                name,
-                score,
-                error,
-                time,
-            });
+    let infos = match receiver.recv_timeout(timeout) {
+        Ok(Ok(infos)) => infos,
+        Ok(Err(error)) => return (List.schedule(instance), Fallback::Failure(error)),
+        Err(RecvTimeoutError::Timeout) => return (List.schedule(instance), Fallback::Timeout),
+        // The sender is dropped unsent only if the scheduler thread unwound from a panic.
+        Err(RecvTimeoutError::Disconnected) => {
+            let failure = Fallback::Failure("scheduler panicked".to_string());
+            return (List.schedule(instance), failure);
+        }
+    };
+
+    let mut schedule = Schedule::new(instance);
+    for (task, info) in infos.into_iter().enumerate() {
+        if let Some(info) = info {
+            schedule.schedule(task, info);
+        }
+    }
+    (schedule, Fallback::None)
+}
+
+fn read_instance(file: &SampleFile) -> anyhow::Result<Instance> {
+    Ok(deserialize(&mut BufReader::new(File::open(&file.path)?))?)
+}
+
+/// Schedules the instance and reports the result, `bound` is the upper bound of its score.
+fn run_file(
+    file: &SampleFile,
+    (instance, bound): (&Instance, u64),
+    valid: usize,
+    schedule: impl for<'a> FnOnce(&'a Instance) -> (Schedule<'a>, Fallback),
+) -> ReportEntry {
+    let time = std::time::Instant::now();
+    let (schedule, fallback) = schedule(instance);
+    let time = time.elapsed().as_secs_f64();
+
+    assert!(schedule.verify(), "Invalid schedule created");
+
+    let score = schedule.calculate_score();
+    if valid >= file.machines && matches!(fallback, Fallback::None) {
+        assert_eq!(score, file.result, "Invalid score {}", file.name);
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    let error = 100.0 - (100 * score) as f64 / file.result as f64;
+
+    #[allow(clippy::cast_precision_loss)]
+    let gap = match bound {
+        0 => 0.0,
+        _ => 100.0 - (100 * score) as f64 / bound as f64,
+    };
+
+    ReportEntry {
+        name: file.name.clone(),
+        score,
+        error,
+        time,
+        truncated: matches!(fallback, Fallback::Timeout),
+        failure: match fallback {
+            Fallback::Failure(error) => Some(error),
+            _ => None,
+        },
+        bound: Some(bound),
+        gap: Some(gap),
+        mean_score: None,
+    }
+}
+
+/// Computes an upper bound of the score.
+/// For tasks with equal times on at least two machines the matching bound is used when it is
+/// tighter than the capacity bound. The matching ignores release times and eligibility, which
+/// only relaxes the problem.
+fn upper_bound(instance: &Instance) -> u64 {
+    let capacity = capacity_upper_bound(instance);
+    if instance.processors < 2 || !instance.has_equal_times() {
+        return capacity;
+    }
+    let matching = PolynomialTime.estimate_upper_bound(instance).ok();
+    matching.map_or(capacity, |matching| matching.min(capacity))
+}
+
+fn run_impl(
+    dir: &str,
+    valid: usize,
+    solver: &mut dyn Scheduler,
+    seed: Option<u64>,
+) -> anyhow::Result<Report> {
+    let mut report = Report::new(solver.name().into());
+
+    for file in sample_files(dir, false)? {
+        let instance = read_instance(&file)?;
+        if !solver.supports(&instance) {
+            continue;
         }
+
+        let bound = upper_bound(&instance);
+        let entry = run_file(&file, (&instance, bound), valid, |instance| {
+            or_list(instance, schedule_seeded(solver, instance, seed, None))
+        });
+        report.entries.push(entry);
     }
 
     Ok(report)
 }
 
-fn parse_filename(filename: &std::ffi::OsString) -> anyhow::Result<(String, usize, u64, bool)> {
+fn parse_filename(filename: &std::ffi::OsString) -> anyhow::Result<(String, usize, u64)> {
     static NAME_ERR: &str = "Cannot read filename";
 
     let name = filename.to_str().ok_or_else(|| anyhow!(NAME_ERR))?;
@@ -173,11 +790,11 @@ fn parse_filename(filename: &std::ffi::OsString) -> anyhow::Result<(String, usiz
     let machines = parts.next().ok_or_else(|| anyhow!(NAME_ERR))?.parse()?;
     let result = parts.next().ok_or_else(|| anyhow!(NAME_ERR))?.parse()?;
     let _: usize = parts.next().ok_or_else(|| anyhow!(NAME_ERR))?.parse()?;
-    let is_unit = parts.next().is_some_and(|x| x == "unit");
-    Ok((name.into(), machines, result, is_unit))
+    Ok((name.into(), machines, result))
 }
 
 fn parse_number(filename: &str) -> Option<usize> {
+    let filename = filename.rsplit('/').next()?;
     filename.split('.').next()?.split('_').nth(2)?.parse().ok()
 }
 
@@ -188,18 +805,179 @@ mod test {
     #[test]
     fn test_parse_filename() -> anyhow::Result<()> {
         let filename = "10_1234_0_unit.in".into();
-        let (name, machines, result, is_unit) = parse_filename(&filename)?;
+        let (name, machines, result) = parse_filename(&filename)?;
         assert_eq!(name, "10_1234_0_unit.in");
         assert_eq!(machines, 10);
         assert_eq!(result, 1234);
-        assert!(is_unit);
 
         let filename = "2_14_2.in".into();
-        let (name, machines, result, is_unit) = parse_filename(&filename)?;
+        let (name, machines, result) = parse_filename(&filename)?;
         assert_eq!(name, "2_14_2.in");
         assert_eq!(machines, 2);
         assert_eq!(result, 14);
-        assert!(!is_unit);
+        Ok(())
+    }
+
+    #[test]
+    fn test_report_spread() {
+        let entry = |name: &str, time, error| ReportEntry {
+            name: name.into(),
+            score: 0,
+            error,
+            time,
+            truncated: false,
+            failure: None,
+            bound: None,
+            gap: None,
+            mean_score: None,
+        };
+        let mut report = Report::new("Test".into());
+        report.entries.push(entry("2_1_0.in", 3.0, 10.0));
+        report.entries.push(entry("2_1_1.in", 1.0, 0.0));
+        report.entries.push(entry("2_1_2.in", 2.0, 40.0));
+        report.entries.push(entry("2_1_3.in", 4.0, 20.0));
+
+        let display = report.to_string();
+        assert!(display.contains("time min 1.00s, max 4.00s, median 2.50s\n"));
+        assert!(display.contains("error min 0.00, max 40.00, median 15.00\n"));
+        assert!(display.contains("2_1_0.in: 3.00s, score: 0, error: 10.00\n"));
+    }
+
+    #[test]
+    fn test_run_seeded() -> anyhow::Result<()> {
+        let first = run_seeded("samples", 0, &mut crate::algo::Genetic::new(1, 5), 7)?;
+        let second = run_seeded("samples", 0, &mut crate::algo::Genetic::new(2, 5), 7)?;
+
+        let scores = |report: &Report| {
+            let mut scores: Vec<_> = report
+                .entries
+                .iter()
+                .map(|e| (e.name.clone(), e.score))
+                .collect();
+            scores.sort();
+            scores
+        };
+        assert_eq!(scores(&first), scores(&second));
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_parallel() -> anyhow::Result<()> {
+        let factory = || -> Box<dyn Scheduler> { Box::new(crate::algo::Genetic::new(0, 5)) };
+        let options = RunOptions {
+            seed: Some(7),
+            warmup: true,
+            ..RunOptions::default()
+        };
+        let parallel = run_parallel("samples", 0, factory, &options)?;
+        let sequential = run_seeded("samples", 0, factory().as_mut(), 7)?;
+
+        let scores = |report: &Report| {
+            let entries = report.entries.iter();
+            entries
+                .map(|e| (e.name.clone(), e.score))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(parallel.entries.len(), 6);
+        assert_eq!(scores(&parallel), scores(&sequential));
+        Ok(())
+    }
+
+    /// Fails on its first instance, so it succeeds only after a warm-up.
+    #[derive(Default)]
+    struct Cold {
+        warm: bool,
+    }
+
+    impl Scheduler for Cold {
+        fn schedule<'a>(&mut self, instance: &'a Instance) -> Schedule<'a> {
+            List.schedule(instance)
+        }
+
+        fn try_schedule<'a>(&mut self, instance: &'a Instance) -> anyhow::Result<Schedule<'a>> {
+            if std::mem::replace(&mut self.warm, true) {
+                Ok(self.schedule(instance))
+            } else {
+                Err(anyhow!("cold"))
+            }
+        }
+
+        fn name(&self) -> &'static str {
+            "Cold"
+        }
+    }
+
+    #[test]
+    fn warmup_should_warm_every_thread_scheduler() -> anyhow::Result<()> {
+        let factory = || -> Box<dyn Scheduler> { Box::<Cold>::default() };
+        let options = RunOptions {
+            warmup: true,
+            ..RunOptions::default()
+        };
+        let report = run_parallel("samples", 0, factory, &options)?;
+        assert!(report.entries.iter().all(|entry| entry.failure.is_none()));
+        Ok(())
+    }
+
+    struct Slow;
+
+    impl Scheduler for Slow {
+        fn schedule<'a>(&mut self, instance: &'a Instance) -> Schedule<'a> {
+            std::thread::sleep(Duration::from_millis(500));
+            List.schedule(instance)
+        }
+
+        fn name(&self) -> &'static str {
+            "Slow"
+        }
+    }
+
+    #[test]
+    fn test_run_timeout() -> anyhow::Result<()> {
+        let factory = || -> Box<dyn Scheduler> { Box::new(Slow) };
+        let options = RunOptions {
+            timeout: Some(Duration::from_millis(10)),
+            ..RunOptions::default()
+        };
+        let report = run_parallel("samples", 0, factory, &options)?;
+        assert_eq!(report.entries.len(), 6);
+        assert!(report.entries.iter().all(|entry| entry.truncated));
+
+        let factory = || -> Box<dyn Scheduler> { Box::new(List) };
+        let options = RunOptions {
+            timeout: Some(Duration::from_secs(60)),
+            ..RunOptions::default()
+        };
+        let report = run_parallel("samples", 0, factory, &options)?;
+        assert!(report.entries.iter().all(|entry| !entry.truncated));
+        Ok(())
+    }
+
+    struct Panicking;
+
+    impl Scheduler for Panicking {
+        fn schedule<'a>(&mut self, _: &'a Instance) -> Schedule<'a> {
+            panic!("scheduler bug")
+        }
+
+        fn name(&self) -> &'static str {
+            "Panicking"
+        }
+    }
+
+    #[test]
+    fn panicking_scheduler_should_be_reported_as_failure() -> anyhow::Result<()> {
+        let factory = || -> Box<dyn Scheduler> { Box::new(Panicking) };
+        let options = RunOptions {
+            timeout: Some(Duration::from_secs(60)),
+            ..RunOptions::default()
+        };
+        let report = run_parallel("samples", 0, factory, &options)?;
+        assert!(!report.entries.is_empty());
+        for entry in &report.entries {
+            assert!(!entry.truncated);
+            assert_eq!(entry.failure.as_deref(), Some("scheduler panicked"));
+        }
         Ok(())
     }
 
@@ -213,4 +991,283 @@ mod test {
         assert!(parse_filename(&"1a0_1234_0.in".into()).is_err());
         assert!(parse_filename(&"10_1234_0a2.in".into()).is_err());
     }
+
+    struct Failing;
+
+    impl Scheduler for Failing {
+        fn schedule<'a>(&mut self, _: &'a Instance) -> Schedule<'a> {
+            unreachable!("The runner uses the fallible path")
+        }
+
+        fn try_schedule<'a>(&mut self, _: &'a Instance) -> anyhow::Result<Schedule<'a>> {
+            Err(anyhow!("no license"))
+        }
+
+        fn try_schedule_with_budget<'a>(
+            &mut self,
+            instance: &'a Instance,
+            _: Duration,
+        ) -> anyhow::Result<Schedule<'a>> {
+            self.try_schedule(instance)
+        }
+
+        fn name(&self) -> &'static str {
+            "Failing"
+        }
+    }
+
+    #[test]
+    fn gap_should_be_reported() -> anyhow::Result<()> {
+        let report = run("samples", 0, &mut PolynomialTime)?;
+        assert!(!report.entries.is_empty());
+        for entry in &report.entries {
+            // The matching is exact for two machines, so the bound is reached.
+            if entry.name.starts_with("2_") {
+                assert_eq!(entry.bound, Some(entry.score));
+                assert_eq!(entry.gap, Some(0.0));
+                assert!(entry.to_string().ends_with(", gap: 0.00"));
+            }
+        }
+
+        // Instances with different times get the capacity bound.
+        let report = run("samples", 0, &mut List)?;
+        let entry = report
+            .entries
+            .iter()
+            .find(|entry| !entry.name.contains("unit"));
+        assert!(entry.is_some_and(|entry| {
+            entry.bound.is_some_and(|bound| bound >= entry.score)
+                && entry.gap.is_some_and(|gap| gap >= 0.0)
+        }));
+        Ok(())
+    }
+
+    #[test]
+    fn upper_bound_should_hold_for_single_machine() {
+        let task = |weight| crate::core::Task::new(1, weight);
+        let instance = Instance::new_no_conflict(1, 3, (1..=4).map(task).collect());
+
+        assert_eq!(List.schedule(&instance).calculate_score(), 9);
+        assert_eq!(upper_bound(&instance), 9);
+    }
+
+    #[test]
+    fn comparison_table_should_mark_best_scores() {
+        let entry = |name: &str, score, error| ReportEntry {
+            name: name.into(),
+            score,
+            error,
+            time: 1.0,
+            truncated: false,
+            failure: None,
+            bound: None,
+            gap: None,
+            mean_score: None,
+        };
+        let mut first = Report::new("List".into());
+        first.entries.push(entry("3_50_0.in", 45, 10.0));
+        first.merge(Report {
+            scheduler: "List".into(),
+            entries: vec![entry("2_12_0_unit.in", 12, 0.0)],
+        });
+        let mut second = Report::new("Tresoldi".into());
+        second.entries.push(entry("3_50_0.in", 50, 0.0));
+
+        let table = Report::comparison_table(&[first, second]);
+        assert_eq!(
+            table,
+            "instance       | List       | Tresoldi\n\
+             2_12_0_unit.in | 12 (0.00)* | -\n\
+             3_50_0.in      | 45 (10.00) | 50 (0.00)*\n"
+        );
+    }
+
+    #[test]
+    fn loaded_samples_should_be_shared_by_schedulers() -> anyhow::Result<()> {
+        let samples = Samples::load("samples", false)?;
+        assert_eq!(samples.len(), sample_files("samples", false)?.len());
+        for sample in &samples.samples {
+            assert_eq!(sample.bound, upper_bound(&sample.instance));
+        }
+
+        let list = || -> Box<dyn Scheduler> { Box::new(List) };
+        let polynomial = || -> Box<dyn Scheduler> { Box::new(PolynomialTime) };
+        let options = RunOptions::default();
+        for factory in [list, polynomial] {
+            let shared = run_samples(&samples, 0, factory, &options);
+            let loaded = run_parallel("samples", 0, factory, &options)?;
+            let summary = |report: &Report| {
+                let entries = report.entries.iter();
+                entries
+                    .map(|entry| (entry.name.clone(), entry.score, entry.bound))
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(summary(&shared), summary(&loaded));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn progress_should_count_all_instances() -> anyhow::Result<()> {
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+        static LAST: AtomicUsize = AtomicUsize::new(0);
+
+        let factory = || -> Box<dyn Scheduler> { Box::new(PolynomialTime) };
+        let options = RunOptions {
+            progress: Some(|done, total, _| {
+                CALLS.fetch_add(1, Ordering::Relaxed);
+                LAST.fetch_max(done, Ordering::Relaxed);
+                assert!(done <= total);
+            }),
+            ..RunOptions::default()
+        };
+        run_parallel("samples", 0, factory, &options)?;
+
+        // Unsupported instances are skipped, but still counted.
+        let total = sample_files("samples", false)?.len();
+        assert_eq!(CALLS.load(Ordering::Relaxed), total);
+        assert_eq!(LAST.load(Ordering::Relaxed), total);
+        Ok(())
+    }
+
+    #[test]
+    fn repeated_runs_should_keep_best_and_mean() -> anyhow::Result<()> {
+        let options = RunOptions {
+            seed: Some(3),
+            repeat: 3,
+            ..RunOptions::default()
+        };
+
+        let factory = || -> Box<dyn Scheduler> { Box::new(crate::algo::Genetic::new(0, 5)) };
+        let report = run_parallel("samples", 0, factory, &options)?;
+        assert!(!report.entries.is_empty());
+        for entry in &report.entries {
+            #[allow(clippy::cast_precision_loss)]
+            let best = entry.score as f64;
+            assert!(entry.mean_score.is_some_and(|mean| mean <= best));
+            assert!(entry.to_string().contains(", mean: "));
+        }
+
+        // Deterministic schedulers run once.
+        let factory = || -> Box<dyn Scheduler> { Box::new(List) };
+        let report = run_parallel("samples", 0, factory, &options)?;
+        assert!(report
+            .entries
+            .iter()
+            .all(|entry| entry.mean_score.is_none()));
+        Ok(())
+    }
+
+    #[test]
+    fn merged_runs_should_keep_first_best_run() {
+        let entry = |name: &str, score, time| ReportEntry {
+            name: name.into(),
+            score,
+            error: 0.0,
+            time,
+            truncated: false,
+            failure: None,
+            bound: None,
+            gap: None,
+            mean_score: None,
+        };
+        let runs = vec![entry("a", 1, 1.0), entry("b", 4, 2.0), entry("c", 4, 6.0)];
+        let merged = ReportEntry::merge_runs(runs);
+        assert!(merged.is_some_and(|merged| {
+            merged.name == "b"
+                && merged.score == 4
+                && (merged.time - 3.0).abs() < f64::EPSILON
+                && merged.mean_score == Some(3.0)
+        }));
+        assert!(ReportEntry::merge_runs(vec![]).is_none());
+    }
+
+    #[test]
+    fn instances_should_run_in_order() -> anyhow::Result<()> {
+        let factory = || -> Box<dyn Scheduler> { Box::new(List) };
+        let tasks = |report: &Report| -> anyhow::Result<Vec<usize>> {
+            let mut tasks = Vec::new();
+            for entry in &report.entries {
+                let path = PathBuf::from("samples").join(&entry.name);
+                let instance: Instance = deserialize(&mut BufReader::new(File::open(path)?))?;
+                tasks.push(instance.tasks.len());
+            }
+            Ok(tasks)
+        };
+
+        let report = run_parallel("samples", 0, factory, &RunOptions::default())?;
+        let names: Vec<_> = report.entries.iter().map(|entry| &entry.name).collect();
+        assert!(names.windows(2).all(|pair| pair[0] <= pair[1]));
+
+        let options = RunOptions {
+            order: InstanceOrder::ByTaskCount,
+            ..RunOptions::default()
+        };
+        let report = run_parallel("samples", 0, factory, &options)?;
+        assert!(tasks(&report)?.windows(2).all(|pair| pair[0] >= pair[1]));
+        Ok(())
+    }
+
+    #[test]
+    fn recursive_run_should_keep_relative_paths() -> anyhow::Result<()> {
+        let factory = || -> Box<dyn Scheduler> { Box::new(List) };
+        let dir = std::env::temp_dir().join(format!("cspcj-recursive-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("small"))?;
+        std::fs::copy("samples/2_12_0_unit.in", dir.join("2_12_0_unit.in"))?;
+        std::fs::copy("samples/2_12_0_unit.in", dir.join("small/2_12_0_unit.in"))?;
+        let dir_name = dir.to_string_lossy();
+
+        let flat = run_parallel(&dir_name, 0, factory, &RunOptions::default());
+        let options = RunOptions {
+            recursive: true,
+            ..RunOptions::default()
+        };
+        let recursive = run_parallel(&dir_name, 0, factory, &options);
+        std::fs::remove_dir_all(&dir)?;
+
+        let names = |report: &Report| -> Vec<String> {
+            report
+                .entries
+                .iter()
+                .map(|entry| entry.name.clone())
+                .collect()
+        };
+        assert_eq!(names(&flat?), ["2_12_0_unit.in"]);
+        let recursive = recursive?;
+        assert_eq!(
+            names(&recursive),
+            ["2_12_0_unit.in", "small/2_12_0_unit.in"]
+        );
+        assert_eq!(recursive.entries[0].score, recursive.entries[1].score);
+        assert_eq!(parse_number("small_1/2_12_3_unit.in"), Some(3));
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_failure() -> anyhow::Result<()> {
+        let expected = run("samples", 0, &mut List)?;
+        let report = run("samples", 0, &mut Failing)?;
+        assert_eq!(report.entries.len(), expected.entries.len());
+        for (entry, expected) in report.entries.iter().zip(&expected.entries) {
+            assert_eq!(entry.failure.as_deref(), Some("no license"));
+            assert_eq!(entry.score, expected.score);
+            assert!(entry.to_string().ends_with(" (failed: no license)"));
+        }
+
+        let factory = || -> Box<dyn Scheduler> { Box::new(Failing) };
+        let options = RunOptions {
+            timeout: Some(Duration::from_secs(60)),
+            ..RunOptions::default()
+        };
+        let report = run_parallel("samples", 0, factory, &options)?;
+        assert!(report.entries.iter().all(|entry| entry.failure.is_some()));
+
+        let options = RunOptions {
+            budget: Some(Duration::from_millis(1)),
+            ..RunOptions::default()
+        };
+        let report = run_parallel("samples", 0, factory, &options)?;
+        assert!(report.entries.iter().all(|entry| entry.failure.is_some()));
+        Ok(())
+    }
 }
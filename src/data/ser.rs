@@ -72,6 +72,12 @@ impl ser::Serializer for &mut Serializer {
     type SerializeStruct = Self;
     type SerializeStructVariant = Self;
 
+    /// The custom format writes positional fields separated by whitespace, so types pick
+    /// their compact form, like the comma-separated eligible processors of a task.
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
     fn serialize_bool(self, value: bool) -> Result<()> {
         self.add_to_buffer(&value);
         Ok(())
@@ -0,0 +1,121 @@
+use crate::core::Schedule;
+use std::fmt::{Result, Write};
+
+const LANE_HEIGHT: u64 = 30;
+const LABEL_WIDTH: u64 = 40;
+const CHART_WIDTH: u64 = 800;
+const AXIS_HEIGHT: u64 = 20;
+const TARDY_WIDTH: u64 = 100;
+const TARDY_LINE: u64 = 15;
+
+/// Draws the schedule as a Gantt chart in SVG.
+///
+/// Every processor has a horizontal lane with time on the x-axis up to the deadline.
+/// Scheduled tasks are colored rectangles labelled with their ids,
+/// tardy tasks are listed in a column on the right.
+#[must_use]
+pub fn to_svg(schedule: &Schedule) -> String {
+    let mut svg = String::new();
+    write_svg(schedule, &mut svg).unwrap_or_else(|_| unreachable!("Writing to string never fails"));
+    svg
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn write_svg(schedule: &Schedule, svg: &mut String) -> Result {
+    let instance = schedule.instance();
+    let lanes = crate::cast_u64(instance.processors) * LANE_HEIGHT;
+    let tardy: Vec<_> = (0..instance.tasks.len())
+        .filter(|&task| schedule.get_schedule(task).is_none())
+        .collect();
+
+    let width = LABEL_WIDTH + CHART_WIDTH + TARDY_WIDTH;
+    let height = lanes.max((crate::cast_u64(tardy.len()) + 1) * TARDY_LINE) + AXIS_HEIGHT;
+    let scale = CHART_WIDTH as f64 / instance.deadline.max(1) as f64;
+    let x = |time: u64| (time as f64).mul_add(scale, LABEL_WIDTH as f64);
+
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" font-family="sans-serif" font-size="12">"#
+    )?;
+
+    for processor in 0..instance.processors {
+        let y = crate::cast_u64(processor) * LANE_HEIGHT;
+        writeln!(
+            svg,
+            r##"<rect x="{LABEL_WIDTH}" y="{y}" width="{CHART_WIDTH}" height="{LANE_HEIGHT}" fill="none" stroke="#ccc"/>"##
+        )?;
+        writeln!(svg, r#"<text x="5" y="{}">P{processor}</text>"#, y + 20)?;
+    }
+
+    for (task, data) in instance.tasks.iter().enumerate() {
+        let Some(info) = schedule.get_schedule(task) else {
+            continue;
+        };
+        let y = crate::cast_u64(info.processor) * LANE_HEIGHT;
+        let (start, end) = (x(info.start), x(info.end(data)));
+        // Golden angle spreads the hues of consecutive tasks.
+        let hue = task * 137 % 360;
+        writeln!(
+            svg,
+            r#"<rect x="{start:.1}" y="{}" width="{:.1}" height="{}" fill="hsl({hue},70%,60%)" stroke="black"/>"#,
+            y + 2,
+            end - start,
+            LANE_HEIGHT - 4
+        )?;
+        writeln!(
+            svg,
+            r#"<text x="{:.1}" y="{}" text-anchor="middle">{task}</text>"#,
+            (start + end) / 2.0,
+            y + 20
+        )?;
+    }
+
+    let deadline = x(instance.deadline);
+    writeln!(
+        svg,
+        r#"<line x1="{deadline:.1}" y1="0" x2="{deadline:.1}" y2="{lanes}" stroke="red" stroke-width="2"/>"#
+    )?;
+    writeln!(
+        svg,
+        r#"<text x="{LABEL_WIDTH}" y="{}">0</text>"#,
+        lanes + 15
+    )?;
+    writeln!(
+        svg,
+        r#"<text x="{deadline:.1}" y="{}" text-anchor="end">{}</text>"#,
+        lanes + 15,
+        instance.deadline
+    )?;
+
+    let column = LABEL_WIDTH + CHART_WIDTH + 10;
+    writeln!(svg, r#"<text x="{column}" y="{TARDY_LINE}">Tardy</text>"#)?;
+    for (line, task) in (2..).zip(tardy) {
+        writeln!(
+            svg,
+            r#"<text x="{column}" y="{}">{task}</text>"#,
+            line * TARDY_LINE
+        )?;
+    }
+
+    writeln!(svg, "</svg>")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::core::{Conflict, Instance, ScheduleInfo, Task};
+
+    #[test]
+    fn svg_should_match_golden_file() {
+        let task = |time| Task::new(time, 1);
+        let tasks = vec![task(2), task(2), task(1), task(4)];
+        let instance = Instance::new(2, 4, tasks, vec![Conflict::new(0, 1)]);
+
+        let mut schedule = Schedule::new(&instance);
+        schedule.schedule(0, ScheduleInfo::new(0, 0));
+        schedule.schedule(1, ScheduleInfo::new(2, 0));
+        schedule.schedule(2, ScheduleInfo::new(1, 1));
+
+        assert_eq!(to_svg(&schedule), include_str!("golden.svg"));
+    }
+}
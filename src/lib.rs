@@ -3,34 +3,104 @@
 #![warn(clippy::unimplemented, clippy::redundant_type_annotations)]
 
 use anyhow::Result;
-use std::io::BufRead;
+use std::io::{BufRead, Write};
 
 pub mod algo;
 pub mod core;
 pub mod data;
 
-/// Runs the given scheduler on the instance read from reader and writes the schedule to stdout.
-/// Also writes the score to stdout.
+/// Runs the given scheduler on the instance read from reader and writes the schedule to output.
+///
+/// The score is always written to stdout.
+/// If `trace` is set, placement decisions of the scheduler are written to stderr.
+/// If `json` is set, the instance is read as JSON instead of the custom format.
+/// Warns on stderr if the deadline is provably too small to schedule all tasks.
 /// Returns an error if the instance could not be read or the schedule could not be written.
 ///
 /// # Errors
 /// - If the instance could not be read from the reader.
-/// - If the schedule could not be written to stdout.
+/// - If the scheduler fails.
+/// - If the schedule could not be written to the output.
 ///
 /// # Panics
 ///  - If the schedule is invalid in debug mode.
-pub fn run_reader(scheduler: &mut dyn core::Scheduler, reader: &mut impl BufRead) -> Result<()> {
-    let instance: core::Instance = data::deserialize(reader)?;
-    let schedule = scheduler.schedule(&instance);
+pub fn run_reader(
+    scheduler: &mut dyn core::Scheduler,
+    reader: &mut impl BufRead,
+    output: &mut impl Write,
+    trace: bool,
+    json: bool,
+) -> Result<()> {
+    let instance: core::Instance = if json {
+        data::deserialize_json(reader)?
+    } else {
+        data::deserialize(reader)?
+    };
+
+    if instance.is_deadline_too_small() {
+        eprintln!("Warning: deadline is too small to schedule all tasks");
+    }
+
+    let schedule = if trace {
+        let (schedule, decisions) = scheduler.try_schedule_traced(&instance)?;
+        for decision in decisions {
+            eprintln!("{decision}");
+        }
+        schedule
+    } else {
+        scheduler.try_schedule(&instance)?
+    };
 
     debug_assert!(schedule.verify(), "Schedule is invalid: {schedule:?}");
 
-    println!("{}", data::to_string(&schedule)?);
+    writeln!(output, "{}", data::to_string(&schedule)?)?;
+    output.flush()?;
     println!("{}", schedule.calculate_score());
 
     Ok(())
 }
 
+/// Writes statistics of the instance read from reader to stdout as JSON.
+/// The statistics are the [`core::InstanceStats`] of the instance.
+///
+/// Warns on stderr if the deadline is provably too small to schedule all tasks.
+///
+/// # Errors
+/// - If the instance could not be read from the reader.
+pub fn stats_reader(reader: &mut impl BufRead) -> Result<()> {
+    let instance: core::Instance = data::deserialize(reader)?;
+
+    if instance.is_deadline_too_small() {
+        eprintln!("Warning: deadline is too small to schedule all tasks");
+    }
+
+    println!("{}", data::to_json(&instance.stats())?);
+
+    Ok(())
+}
+
+/// Checks the schedule read from `schedule` against the instance read from `instance`.
+///
+/// The score is written to stdout even if the schedule is invalid.
+/// Warns on stderr if the deadline is provably too small to schedule all tasks.
+///
+/// # Errors
+/// - If the instance or the schedule could not be read.
+/// - If the schedule is invalid, the error describes the first violation found.
+pub fn validate_reader(instance: &mut impl BufRead, schedule: &mut impl BufRead) -> Result<()> {
+    let instance: core::Instance = data::deserialize(instance)?;
+    let schedule = data::deserialize_schedule(schedule, &instance)?;
+
+    if instance.is_deadline_too_small() {
+        eprintln!("Warning: deadline is too small to schedule all tasks");
+    }
+
+    println!("{}", schedule.calculate_score());
+    schedule.verify_detailed()?;
+
+    Ok(())
+}
+
 #[cfg(not(target_pointer_width = "64"))]
 compile_error!("Must be 64-bit system!");
 
@@ -53,3 +123,83 @@ pub fn cast_usize(value: u64) -> usize {
 pub fn cast_u64(value: usize) -> u64 {
     u64::try_from(value).unwrap_or_else(|_| unreachable!("Must be 64-bit system!"))
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::core::Scheduler;
+
+    #[test]
+    fn run_reader_should_write_schedule_to_output() -> Result<()> {
+        let content = std::fs::read_to_string("samples/3_50_0.in")?;
+        let mut output = Vec::new();
+
+        run_reader(
+            &mut algo::List,
+            &mut content.as_bytes(),
+            &mut output,
+            false,
+            false,
+        )?;
+
+        let instance: core::Instance = data::deserialize(&mut content.as_bytes())?;
+        let schedule = data::to_string(&algo::List.schedule(&instance))?;
+        assert_eq!(String::from_utf8(output)?, format!("{schedule}\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn validate_reader_should_report_violation() -> Result<()> {
+        let content = std::fs::read_to_string("samples/3_50_0.in")?;
+        let instance: core::Instance = data::deserialize(&mut content.as_bytes())?;
+        let mut schedule = algo::List.schedule(&instance);
+
+        let valid = data::to_string(&schedule)?;
+        validate_reader(&mut content.as_bytes(), &mut valid.as_bytes())?;
+
+        schedule.schedule(0, core::ScheduleInfo::new(0, instance.processors));
+        let invalid = data::to_string(&schedule)?;
+        let error = validate_reader(&mut content.as_bytes(), &mut invalid.as_bytes());
+        let error = error.err().map(|error| error.to_string());
+        assert_eq!(
+            error,
+            Some(core::VerifyError::ProcessorOutOfRange(0, instance.processors).to_string())
+        );
+        Ok(())
+    }
+
+    struct Failing;
+
+    impl core::Scheduler for Failing {
+        fn schedule<'a>(&mut self, _: &'a core::Instance) -> core::Schedule<'a> {
+            unreachable!("The reader uses the fallible path")
+        }
+
+        fn try_schedule<'a>(&mut self, _: &'a core::Instance) -> Result<core::Schedule<'a>> {
+            Err(anyhow::anyhow!("no license"))
+        }
+
+        fn name(&self) -> &'static str {
+            "Failing"
+        }
+    }
+
+    #[test]
+    fn run_reader_should_return_scheduler_error_when_tracing() -> Result<()> {
+        let content = std::fs::read_to_string("samples/3_50_0.in")?;
+        let mut scheduler = algo::Decomposed::new(Failing);
+
+        let error = run_reader(
+            &mut scheduler,
+            &mut content.as_bytes(),
+            &mut Vec::new(),
+            true,
+            false,
+        );
+        assert_eq!(
+            error.err().map(|error| error.to_string()),
+            Some("no license".to_string())
+        );
+        Ok(())
+    }
+}
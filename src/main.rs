@@ -1,9 +1,12 @@
 use clap::{Parser, ValueEnum};
-use cspcj::core::{Conflict, Instance, Scheduler, Task};
-use cspcj::{algo, cast_u64, data, run_reader};
+use cspcj::core::{Conflict, Instance, Schedule, Scheduler, Task};
+use cspcj::{algo, cast_u64, data, run_reader, stats_reader, validate_reader};
 use rand::prelude::*;
-use std::io::Write;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::io::{BufRead, BufReader, Write};
 use std::num::NonZero;
+use std::time::Duration;
 
 #[derive(Copy, Clone, Debug)]
 struct Algorithm(usize, &'static str);
@@ -35,11 +38,117 @@ impl ValueEnum for Algorithm {
     }
 }
 
+/// Output format of benchmark reports.
+#[derive(Copy, Clone, Debug, Default, ValueEnum)]
+enum Format {
+    /// Human-readable text.
+    #[default]
+    Text,
+    /// JSON array of reports.
+    Json,
+    /// CSV rows of all reports with a single header row.
+    Csv,
+    /// Table comparing the schedulers per instance, the best scores are marked with `*`.
+    Table,
+}
+
+/// Order of benchmark instances.
+#[derive(Copy, Clone, Debug, Default, ValueEnum)]
+enum Order {
+    /// By file name.
+    #[default]
+    Name,
+    /// Instances with more tasks first.
+    TaskCount,
+    /// Instances with denser conflict graphs first.
+    ConflictDensity,
+}
+
+impl From<Order> for data::InstanceOrder {
+    fn from(value: Order) -> Self {
+        match value {
+            Order::Name => Self::ByName,
+            Order::TaskCount => Self::ByTaskCount,
+            Order::ConflictDensity => Self::ByConflictDensity,
+        }
+    }
+}
+
+/// Distribution of task weights of generated instances.
+#[derive(Copy, Clone, Debug, Default, ValueEnum)]
+enum WeightDist {
+    /// Weights are drawn uniformly from 1 to the maximum weight.
+    #[default]
+    Uniform,
+    /// Weights grow with the processing time, the longest tasks have the maximum weight.
+    ProportionalToTime,
+    /// Weights shrink with the processing time, unit tasks have the maximum weight.
+    InverseToTime,
+}
+
+impl WeightDist {
+    /// Returns the weight of a task with the given processing time.
+    fn weight(self, time: u64, max_time: u64, max_weight: u64, rng: &mut impl Rng) -> u64 {
+        match self {
+            Self::Uniform => rng.gen_range(1..=max_weight),
+            Self::ProportionalToTime => {
+                // The product may exceed `u64`, the quotient is at most the maximum weight.
+                let weight =
+                    (u128::from(max_weight) * u128::from(time)).div_ceil(u128::from(max_time));
+                u64::try_from(weight).unwrap_or(max_weight)
+            }
+            Self::InverseToTime => max_weight.div_ceil(time),
+        }
+    }
+}
+
+/// Structure of generated conflict graphs.
+///
+/// Conflicts are generated in an order in which any prefix keeps the structure, so a density
+/// sweep adds conflicts to the previous instance.
+#[derive(Copy, Clone, Debug, Default, ValueEnum)]
+enum GraphModel {
+    /// Conflicts are drawn uniformly from all pairs of tasks.
+    #[default]
+    Random,
+    /// Tasks are intervals of equal length at random positions, overlapping intervals conflict.
+    Interval,
+    /// Tasks form disjoint cliques, which are merged pairwise as the ratio grows.
+    Cluster,
+}
+
 /// Application solving the capacitated scheduling problem.
 #[derive(Debug, Parser)]
 enum Application {
     /// Run one of the implemented algorithms.
-    Run { algorithm: Algorithm },
+    Run {
+        algorithm: Algorithm,
+        /// The input file. If not given, the instance is read from stdin.
+        input: Option<String>,
+        /// Write placement decisions of the scheduler to stderr.
+        #[clap(short, long, default_value = "false")]
+        trace: bool,
+        /// Read the instance as JSON instead of the custom format.
+        #[clap(short, long, default_value = "false")]
+        json: bool,
+        /// Seed of the random number generator for reproducible runs.
+        /// It has no effect on deterministic algorithms like `List`.
+        #[clap(short, long)]
+        seed: Option<u64>,
+        /// Write the schedule to the file instead of stdout. The score is still written to stdout.
+        #[clap(short, long)]
+        output: Option<String>,
+    },
+    /// Print statistics of an instance read from stdin as JSON.
+    Stats,
+    /// Check a schedule of an instance and print its score.
+    /// Exits with an error describing the first violation if the schedule is invalid.
+    Validate {
+        /// The instance file.
+        instance: String,
+        /// The schedule file, in the format written by `run`.
+        schedule: String,
+    },
     /// Run benchmarks on a set of instances.
     Bench {
         /// The input directory.
@@ -47,6 +156,47 @@ enum Application {
         /// Exclude scheduling algorithms.
         #[clap(short, long, value_delimiter = ',')]
         exclude: Vec<Algorithm>,
+        /// Run only the given scheduling algorithms.
+        #[clap(short, long, value_delimiter = ',', conflicts_with = "exclude")]
+        include: Vec<Algorithm>,
+        /// Time limit for a single instance in seconds.
+        /// Schedulers exceeding it are reported with the `List` schedule.
+        #[clap(short, long)]
+        timeout: Option<f64>,
+        /// Format of the reports.
+        #[clap(short, long, default_value = "text")]
+        format: Format,
+        /// Write the reports to the file instead of stdout.
+        #[clap(short, long)]
+        output: Option<String>,
+        /// Run every scheduler once on the first instance before timing begins.
+        /// It makes reported times steadier and does not affect scores.
+        #[clap(short, long, default_value = "false")]
+        warmup: bool,
+        /// Base seed of the random number generators for reproducible runs.
+        /// Schedulers are reseeded before each instance with a seed derived from it.
+        /// It has no effect on deterministic algorithms like `List`.
+        #[clap(short, long)]
+        seed: Option<u64>,
+        /// Run randomized schedulers the given number of times on each instance.
+        /// The best score is reported with the mean score and time. With `--seed`,
+        /// run `r` uses the base seed plus `r`. Deterministic schedulers run once.
+        #[clap(short, long, default_value = "1")]
+        repeat: NonZero<usize>,
+        /// Time budget for a single instance in seconds.
+        /// Anytime algorithms run until it is spent instead of a fixed number of iterations.
+        #[clap(short, long)]
+        budget: Option<f64>,
+        /// Print the number of finished instances to stderr.
+        #[clap(short, long, default_value = "false")]
+        progress: bool,
+        /// Order in which the instances are scheduled.
+        #[clap(long, default_value = "name")]
+        order: Order,
+        /// Read instances from subdirectories of the input directory too.
+        /// Reports name them by their path relative to the input directory.
+        #[clap(long, default_value = "false")]
+        recursive: bool,
     },
     /// Generate test cases for the scheduling problem.
     Gen {
@@ -63,6 +213,10 @@ enum Application {
         /// Conflict ratio. 1.0 means that all tasks are in conflict with each other.
         #[clap(short, long, default_value = "0.5")]
         conflict_ratio: f64,
+        /// Generate one instance per conflict ratio from MIN to MAX with STEP, instead of using
+        /// `conflict_ratio`. Instances share tasks and each adds conflicts to the previous one.
+        #[clap(long, num_args = 3, value_names = ["MIN", "MAX", "STEP"])]
+        density_sweep: Option<Vec<f64>>,
         /// Whether all tasks have the same processing time.
         #[clap(short, long, default_value = "false")]
         same_duration: bool,
@@ -72,22 +226,59 @@ enum Application {
         /// Maximum weight of a task.
         #[clap(short, long, default_value = "5")]
         max_weight: NonZero<u64>,
+        /// Distribution of task weights.
+        #[clap(short = 'w', long, default_value = "uniform")]
+        weight_distribution: WeightDist,
+        /// Structure of the conflict graph.
+        #[clap(short, long, default_value = "random")]
+        graph_model: GraphModel,
         /// Path to output the generated instances. If the directory does not exist, it will be created.
         #[clap(short, long, default_value = "output")]
         output: String,
+        /// Append all instances to this file in the output directory instead of writing
+        /// one file per instance. Instances are stored one after another.
+        #[clap(short, long)]
+        bundle: Option<String>,
+        /// Write the schedule behind the estimated optimum next to each instance as a `.sol` file.
+        /// It is the `PolynomialTime` schedule for unit instances and the `ILP2` schedule
+        /// otherwise, which needs the `gurobi` feature. Its score is below the estimate in the
+        /// file name when the estimate is not exact.
+        #[clap(long, default_value = "false", conflicts_with = "bundle")]
+        emit_solution: bool,
     },
 }
 
-fn schedulers(exclude: &[Algorithm]) -> impl Iterator<Item = Box<dyn Scheduler>> + '_ {
-    let iter = algo::SCHEDULERS.iter().map(|init| init());
-    iter.filter(|scheduler| !exclude.iter().any(|name| name.1 == scheduler.name()))
+/// Returns the schedulers to run, all of them if `include` is empty.
+fn schedulers<'a>(
+    exclude: &'a [Algorithm],
+    include: &'a [Algorithm],
+) -> impl Iterator<Item = fn() -> Box<dyn Scheduler>> + 'a {
+    let iter = algo::SCHEDULERS.iter().copied();
+    iter.filter(|init| {
+        let name = init().name();
+        let listed = |algorithms: &[Algorithm]| algorithms.iter().any(|algo| algo.1 == name);
+        !listed(exclude) && (include.is_empty() || listed(include))
+    })
+}
+
+fn print_progress(done: usize, total: usize, name: &str) {
+    eprint!("\r\x1b[K{done}/{total} {name}");
+    if done == total {
+        eprintln!();
+    }
 }
 
 fn compute_deadline(max_time: u64, tasks_number: usize, processors: usize, ratio: f64) -> u64 {
     ((max_time * cast_u64(tasks_number)) as f64 * ratio / (processors * 2) as f64).ceil() as u64
 }
 
-fn gen_tasks(tasks_number: usize, max_time: u64, max_weight: u64, unit: bool) -> Vec<Task> {
+fn gen_tasks(
+    tasks_number: usize,
+    max_time: u64,
+    max_weight: u64,
+    unit: bool,
+    weights: WeightDist,
+) -> Vec<Task> {
     let mut rng = thread_rng();
     let mut tasks = Vec::with_capacity(tasks_number);
     for _ in 0..tasks_number {
@@ -96,40 +287,240 @@ fn gen_tasks(tasks_number: usize, max_time: u64, max_weight: u64, unit: bool) ->
         } else {
             rng.gen_range(1..=max_time)
         };
-        let weight = rng.gen_range(1..=max_weight);
-        tasks.push(Task { time, weight });
+        let weight = weights.weight(time, max_time, max_weight, &mut rng);
+        tasks.push(Task::new(time, weight));
     }
     tasks
 }
 
-fn gen_conflicts(tasks: usize, ratio: f64) -> Vec<Conflict> {
-    let required = (((tasks * (tasks - 1)) / 2) as f64 * ratio).ceil() as usize;
-    (0..tasks)
-        .flat_map(|i| std::iter::repeat(i).zip(i + 1..tasks))
+/// Returns the first `count` conflicts in an order given by the graph model.
+/// Taking a prefix gives a graph of the model, longer prefixes contain the shorter ones.
+/// Only the returned pairs are generated, so sparse graphs of many tasks stay cheap.
+fn gen_conflicts(tasks: usize, model: GraphModel, count: usize) -> Vec<Conflict> {
+    let mut rng = thread_rng();
+    let pairs = match model {
+        GraphModel::Random => random_pairs(tasks, count, &mut rng),
+        GraphModel::Interval => interval_pairs(tasks, count, &mut rng),
+        GraphModel::Cluster => cluster_pairs(tasks, count, &mut rng),
+    };
+
+    pairs
+        .into_iter()
         .map(|(i, j)| Conflict::new(i, j))
-        .choose_multiple(&mut thread_rng(), required)
+        .collect()
+}
+
+/// Samples `count` distinct pairs of tasks uniformly, in random order.
+fn random_pairs(tasks: usize, count: usize, rng: &mut impl Rng) -> Vec<(usize, usize)> {
+    let total = tasks * tasks.saturating_sub(1) / 2;
+    // Pair `k` joins task `k % tasks` with the task `k / tasks + 1` places after it, cyclically.
+    // Offsets below half of the tasks cover every pair once, for an even number of tasks
+    // the offset of exactly half is taken only from the first half of the tasks.
+    rand::seq::index::sample(rng, total, count.min(total))
+        .into_iter()
+        .map(|k| {
+            let (i, j) = (k % tasks, (k % tasks + k / tasks + 1) % tasks);
+            (i.min(j), i.max(j))
+        })
+        .collect()
+}
+
+/// Places tasks at random points of a line and returns the `count` closest pairs,
+/// in order of distance.
+fn interval_pairs(tasks: usize, count: usize, rng: &mut impl Rng) -> Vec<(usize, usize)> {
+    let mut order: Vec<(f64, usize)> = (0..tasks).map(|task| (rng.gen(), task)).collect();
+    order.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    // Distances are non-negative, so their bits compare like the values.
+    let distance = |a: usize, b: usize| (order[b].0 - order[a].0).to_bits();
+    let mut heap: BinaryHeap<_> = (1..tasks)
+        .map(|b| Reverse((distance(b - 1, b), b - 1, b)))
+        .collect();
+
+    // The closest pair not taken yet is the next one after some taken pair in sorted order.
+    let mut pairs = Vec::with_capacity(count);
+    while pairs.len() < count {
+        let Some(Reverse((_, a, b))) = heap.pop() else {
+            break;
+        };
+        let (i, j) = (order[a].1, order[b].1);
+        pairs.push((i.min(j), i.max(j)));
+        if b + 1 < tasks {
+            heap.push(Reverse((distance(a, b + 1), a, b + 1)));
+        }
+    }
+    pairs
 }
 
-fn estimate_result(instance: &Instance, unit: bool) -> anyhow::Result<u64> {
+/// Returns `count` pairs of tasks forming cliques which are merged pairwise.
+/// Cliques are aligned blocks of shuffled positions, doubling in size at each level.
+/// Pairs joining two blocks come in random order, after the pairs of smaller blocks.
+fn cluster_pairs(tasks: usize, count: usize, rng: &mut impl Rng) -> Vec<(usize, usize)> {
+    let mut at: Vec<_> = (0..tasks).collect();
+    at.shuffle(rng);
+
+    let mut pairs = Vec::with_capacity(count);
+    let mut half = 1;
+    while pairs.len() < count && half < tasks {
+        // Every block of `2 * half` positions joins its lower half with its upper half.
+        let (block, full) = (2 * half, tasks / (2 * half));
+        let base = full * block;
+        let (lower, upper) = (
+            (tasks - base).min(half),
+            (tasks - base).saturating_sub(half),
+        );
+        let size = full * half * half + lower * upper;
+
+        let take = (count - pairs.len()).min(size);
+        for k in rand::seq::index::sample(rng, size, take) {
+            let (p, q) = if k < full * half * half {
+                let (start, offset) = (k / (half * half) * block, k % (half * half));
+                (start + offset / half, start + half + offset % half)
+            } else {
+                let offset = k - full * half * half;
+                (base + offset / upper, base + half + offset % upper)
+            };
+            let (i, j) = (at[p], at[q]);
+            pairs.push((i.min(j), i.max(j)));
+        }
+        half *= 2;
+    }
+    pairs
+}
+
+fn conflicts_number(tasks: usize, ratio: f64) -> usize {
+    let pairs = (tasks * (tasks - 1)) / 2;
+    ((pairs as f64 * ratio).ceil() as usize).min(pairs)
+}
+
+fn density_levels(sweep: &[f64]) -> anyhow::Result<Vec<f64>> {
+    let &[min, max, step] = sweep else {
+        anyhow::bail!("Density sweep requires MIN, MAX and STEP");
+    };
+    if step <= 0.0 || min > max {
+        anyhow::bail!("Density sweep requires MIN <= MAX and a positive STEP");
+    }
+
+    let levels = ((max - min) / step + 1e-9).floor() as usize;
+    Ok((0..=levels)
+        .map(|level| min + level as f64 * step)
+        .collect())
+}
+
+/// Estimates the result of the instance. With `emit_solution` set, it also returns the schedule
+/// of the solve behind the estimate, if there is one for the instance.
+fn estimate_result(
+    instance: &Instance,
+    unit: bool,
+    emit_solution: bool,
+) -> anyhow::Result<(u64, Option<Schedule<'_>>)> {
     if unit {
-        algo::PolynomialTime.estimate_upper_bound(instance)
-    } else {
-        #[cfg(feature = "gurobi")]
-        return algo::ILP2.estimate_upper_bound(instance, 60.0);
-        #[cfg(not(feature = "gurobi"))]
-        return Ok(instance.tasks.iter().map(|t| t.weight).sum::<u64>());
+        let mut solver = algo::PolynomialTime;
+        if !emit_solution {
+            return Ok((solver.estimate_upper_bound(instance)?, None));
+        }
+        let (estimate, schedule) = solver.estimate_with_schedule(instance)?;
+        // The matching pairs tasks on two machines, which a single machine cannot run.
+        return Ok((estimate, solver.supports(instance).then_some(schedule)));
     }
+
+    #[cfg(feature = "gurobi")]
+    {
+        let solver = algo::ILP2::default();
+        if !emit_solution {
+            return Ok((solver.estimate_upper_bound(instance, 60.0)?, None));
+        }
+        let (estimate, schedule) = solver.estimate_with_schedule(instance, 60.0)?;
+        Ok((estimate, Some(schedule)))
+    }
+    #[cfg(not(feature = "gurobi"))]
+    Ok((instance.total_weight(), None))
 }
 
 fn main() -> anyhow::Result<()> {
     match Application::parse() {
-        Application::Run { algorithm } => {
+        Application::Run {
+            algorithm,
+            input,
+            trace,
+            json,
+            seed,
+            output,
+        } => {
             let mut scheduler = Box::<dyn Scheduler>::from(algorithm);
-            run_reader(scheduler.as_mut(), &mut std::io::stdin().lock())
+            if let Some(seed) = seed {
+                scheduler.set_seed(seed);
+            }
+            let mut output: Box<dyn Write> = match output {
+                Some(path) => Box::new(std::io::BufWriter::new(std::fs::File::create(path)?)),
+                None => Box::new(std::io::stdout()),
+            };
+            let mut input: Box<dyn BufRead> = match input {
+                Some(path) => Box::new(BufReader::new(std::fs::File::open(path)?)),
+                None => Box::new(std::io::stdin().lock()),
+            };
+            run_reader(scheduler.as_mut(), &mut input, &mut output, trace, json)
         }
-        Application::Bench { input, exclude } => {
-            for mut scheduler in schedulers(&exclude) {
-                println!("{}", data::run(&input, 0, scheduler.as_mut())?);
+        Application::Stats => stats_reader(&mut std::io::stdin().lock()),
+        Application::Validate { instance, schedule } => validate_reader(
+            &mut BufReader::new(std::fs::File::open(instance)?),
+            &mut BufReader::new(std::fs::File::open(schedule)?),
+        ),
+        Application::Bench {
+            input,
+            exclude,
+            include,
+            timeout,
+            format,
+            output,
+            warmup,
+            seed,
+            repeat,
+            budget,
+            progress,
+            order,
+            recursive,
+        } => {
+            let mut options = data::RunOptions::default();
+            options.order = order.into();
+            options.progress = progress.then_some(print_progress);
+            options.seed = seed;
+            options.repeat = repeat.get();
+            options.budget = budget.map(Duration::try_from_secs_f64).transpose()?;
+            options.timeout = timeout.map(Duration::try_from_secs_f64).transpose()?;
+            options.warmup = warmup;
+            let mut output: Box<dyn Write> = match output {
+                Some(path) => Box::new(std::fs::File::create(path)?),
+                None => Box::new(std::io::stdout().lock()),
+            };
+            let mut reports = Vec::new();
+            let samples = data::Samples::load(&input, recursive)?;
+
+            for (i, factory) in schedulers(&exclude, &include).enumerate() {
+                let report = data::run_samples(&samples, 0, factory, &options);
+                match format {
+                    Format::Text => writeln!(output, "{report}")?,
+                    Format::Json | Format::Table => reports.push(report),
+                    Format::Csv => {
+                        let csv = data::report_to_csv(&report);
+                        // The header row is written only once.
+                        let rows = if i == 0 {
+                            &csv
+                        } else {
+                            csv.split_once('\n').map_or("", |(_, rows)| rows)
+                        };
+                        write!(output, "{rows}")?;
+                    }
+                }
+            }
+
+            match format {
+                Format::Json => {
+                    serde_json::to_writer_pretty(&mut output, &reports)?;
+                    writeln!(output)?;
+                }
+                Format::Table => write!(output, "{}", data::Report::comparison_table(&reports))?,
+                Format::Text | Format::Csv => {}
             }
             Ok(())
         }
@@ -139,34 +530,108 @@ fn main() -> anyhow::Result<()> {
             max_time,
             deadline_ratio,
             conflict_ratio,
+            density_sweep,
             same_duration,
             amount,
             max_weight,
+            weight_distribution,
+            graph_model,
             output,
+            bundle,
+            emit_solution,
         } => {
             let processors = processors.get();
             let tasks = tasks.get();
             let max_time = max_time.get();
 
+            let densities = match &density_sweep {
+                Some(sweep) => density_levels(sweep)?,
+                None => vec![conflict_ratio],
+            };
+            let deadline = compute_deadline(max_time, tasks, processors, deadline_ratio);
+            // Longer tasks could never be scheduled and would skew the estimated optimum.
+            if deadline < max_time {
+                anyhow::bail!(
+                    "Deadline {deadline} is shorter than the maximum task time {max_time}, \
+                     increase the deadline ratio"
+                );
+            }
+
+            let pairs = conflicts_number(tasks, 1.0);
+            // Instances of the sweep take prefixes of the conflicts of the densest one.
+            let densest = densities
+                .iter()
+                .map(|&density| conflicts_number(tasks, density))
+                .max()
+                .unwrap_or_default();
+            for &density in &densities {
+                if density < 1.0 && pairs > 0 && conflicts_number(tasks, density) == pairs {
+                    eprintln!(
+                        "Warning: conflict ratio {density} rounds up to all {pairs} pairs \
+                         of {tasks} tasks"
+                    );
+                }
+            }
+
+            if emit_solution && !same_duration && !cfg!(feature = "gurobi") {
+                eprintln!("Warning: solutions of non-unit instances need the gurobi feature");
+            }
+
             let output = std::path::Path::new(&output);
             if !output.try_exists()? {
                 std::fs::create_dir_all(output)?;
             }
 
+            let mut bundle = match bundle {
+                Some(name) => Some(
+                    std::fs::OpenOptions::new()
+                        .create(true)
+                        .append(true)
+                        .open(output.join(name))?,
+                ),
+                None => None,
+            };
+
             for i in 0..amount.get() {
-                let instance = Instance::new(
-                    processors,
-                    compute_deadline(max_time, tasks, processors, deadline_ratio),
-                    gen_tasks(tasks, max_time, max_weight.get(), same_duration),
-                    gen_conflicts(tasks, conflict_ratio),
-                );
-                let estimate = estimate_result(&instance, same_duration)?;
-                let filename = format!(
-                    "{processors}_{estimate}_{i}{}.in",
-                    if same_duration { "_unit" } else { "" }
-                );
-                std::fs::File::create(output.join(filename))?
-                    .write_all(data::to_string(&instance)?.as_bytes())?;
+                let weight = max_weight.get();
+                let task_list =
+                    gen_tasks(tasks, max_time, weight, same_duration, weight_distribution);
+                let conflicts = gen_conflicts(tasks, graph_model, densest);
+
+                for &density in &densities {
+                    let required = conflicts_number(tasks, density);
+                    let instance = Instance::new(
+                        processors,
+                        deadline,
+                        task_list.clone(),
+                        conflicts[..required].to_vec(),
+                    );
+                    let content = data::to_string(&instance)?;
+                    if let Some(bundle) = bundle.as_mut() {
+                        bundle.write_all(content.as_bytes())?;
+                        continue;
+                    }
+
+                    let (estimate, schedule) =
+                        estimate_result(&instance, same_duration, emit_solution)?;
+                    // Density is stored in percents, as dots separate the file extension.
+                    let density = match density_sweep {
+                        Some(_) => format!("_d{}", (density * 100.0).round() as u64),
+                        None => String::new(),
+                    };
+                    let stem = format!(
+                        "{processors}_{estimate}_{i}{}{density}",
+                        if same_duration { "_unit" } else { "" }
+                    );
+                    let path = output.join(format!("{stem}.in"));
+                    std::fs::File::create(path)?.write_all(content.as_bytes())?;
+
+                    if let Some(schedule) = schedule {
+                        let path = output.join(format!("{stem}.sol"));
+                        let solution = data::to_string(&schedule)?;
+                        std::fs::File::create(path)?.write_all(solution.as_bytes())?;
+                    }
+                }
             }
             Ok(())
         }